@@ -1,164 +1,442 @@
-use editorial_common::{clean_title, slugify, url_encode, SiteReview};
-use extism_pdk::*;
-use serde::Deserialize;
+use editorial_common::{
+    best_candidate, build_request, cached_review_url, clean_title, configured_base_url,
+    decode_html_entities, fetch_page, find_json_ld_node, forget_review_url, json_ld_image,
+    json_ld_name, json_ld_number, match_confidence, meta_content, prefer_release_type,
+    record_review_url, release_ids_conflict, scale_rating, score_candidate, search_terms, slugify,
+    strip_html_tags, url_encode, AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query,
+    ReleaseType, SearchCandidate, SearchResult, SiteReview, DEFINITIVE_MATCH_SCORE,
+    MIN_MATCH_SCORE,
+};
+use std::ops::Range;
+
+/// Name this source's entries are kept under in the shared caches.
+const SOURCE: &str = "allmusic";
+
+/// Production site root; override with the `allmusic_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://www.allmusic.com";
+const BASE_URL_CONFIG: &str = "allmusic_base_url";
+/// Most album pages fetched to verify the artist of unverified title matches.
+const MAX_UNVERIFIED_PAGES: usize = 3;
+
+/// The site root, honoring the `allmusic_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
 
 /// Attempt to fetch an AllMusic review for the given album.
-pub fn fetch_review(artist: &str, title: &str) -> Option<SiteReview> {
-    let cleaned = clean_title(title);
-    let album_url = search_for_album(artist, cleaned)?;
-
-    // Fetch album page for rating from JSON-LD
-    let req = HttpRequest::new(&album_url).with_header("Accept", "text/html");
-    let resp = http::request::<()>(&req, None).ok()?;
-    if resp.status_code() != 200 {
-        return None;
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its rating from the album page, never
+/// requesting the review text. An unrated ("N/A") album is still returned,
+/// since its review text wasn't checked.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_album(input).into()
+}
+
+/// Parse the album page at a known AllMusic URL, skipping search. The
+/// page's artist isn't checked since there's no album to compare it with.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    let mut review = parse_album_page(url, &body, "").ok_or(FetchError::NotFound)?;
+    add_review_text(&mut review);
+    require_content(review)
+}
+
+/// Without the review text an album can't be judged empty, so it's only
+/// checked for content when the text was requested.
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let mut review = lookup_album(input)?;
+    if !input.options.include_body {
+        return Ok(review);
     }
+    add_review_text(&mut review);
+    require_content(review)
+}
 
-    let body = String::from_utf8(resp.body().to_vec()).ok()?;
-    let mut review = parse_album_page(&album_url, &body, artist)?;
+/// Treat an album with no rating, rating count or review text as not found.
+fn require_content(review: SiteReview) -> Result<SiteReview, FetchError> {
+    if review.rating.is_none() && review.rating_count.is_none() && review.excerpt.is_none() {
+        return Err(FetchError::NotFound);
+    }
+    Ok(review)
+}
 
-    // Fetch review text from the AJAX endpoint (requires XHR + Referer headers)
+/// Fetch review text from the AJAX endpoint (requires XHR + Referer headers).
+/// The review is kept as-is if the request fails.
+fn add_review_text(review: &mut SiteReview) {
+    let album_url = review.source_url.clone();
     let review_url = format!("{}/reviewAjax", album_url);
-    let req = HttpRequest::new(&review_url)
-        .with_header("Accept", "text/html, */*; q=0.01")
+    let req = build_request(&review_url, "text/html, */*; q=0.01")
         .with_header("X-Requested-With", "XMLHttpRequest")
         .with_header("Referer", &album_url);
-    if let Ok(resp) = http::request::<()>(&req, None) {
-        if resp.status_code() == 200 {
-            if let Ok(html) = String::from_utf8(resp.body().to_vec()) {
-                let (excerpt, reviewer) = parse_review_ajax(&html);
-                review.excerpt = excerpt;
-                if reviewer.is_some() {
-                    review.reviewer = reviewer;
-                }
-            }
+    if let Ok(html) = fetch_page(&req) {
+        let (excerpt, reviewer) = parse_review_ajax(&html);
+        review.excerpt = excerpt;
+        if reviewer.is_some() {
+            review.reviewer = reviewer;
         }
     }
-
-    Some(review)
 }
 
-/// Search AllMusic and find the album page URL.
-fn search_for_album(artist: &str, title: &str) -> Option<String> {
-    let title_slug = slugify(title);
-    let artist_slug = slugify(artist);
+/// Search for the album and parse its page (rating and rating count).
+/// Candidates are tried in order until one's page passes the artist check.
+/// The album page found is remembered, and tried first by later lookups of
+/// the same album.
+fn lookup_album(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let artist = input.artist.as_str();
+    let cleaned = clean_title(&input.title);
+    let with_confidence = |mut review: SiteReview, album_url: &str| {
+        let url_slug = link_slug(album_url);
+        let confidence = match_confidence(&url_slug, &slugify(artist), &slugify(cleaned));
+        review.match_confidence = Some(confidence);
+        review
+    };
 
-    let query = format!("{} {}", artist, title);
-    if let Some(url) = search_and_match(&query, &title_slug, &artist_slug) {
-        return Some(url);
+    if let Some(album_url) = cached_review_url(SOURCE, artist, cleaned) {
+        match album_page_review(&album_url, input) {
+            Ok((review, _)) => return Ok(with_confidence(review, &album_url)),
+            Err(FetchError::NotFound) => forget_review_url(SOURCE, artist, cleaned),
+            Err(err) => return Err(err),
+        }
     }
 
-    search_and_match(title, &title_slug, &artist_slug)
+    let candidates = search_for_album(artist, cleaned, input.year, input.release_type)?;
+    let (album_url, review) = first_album_page(candidates, input.year, |album_url| {
+        album_page_review(album_url, input)
+    })?;
+    record_review_url(SOURCE, artist, cleaned, &album_url);
+    Ok(with_confidence(review, &album_url))
 }
 
-/// Search AllMusic and return the best matching album URL.
-fn search_and_match(query: &str, title_slug: &str, artist_slug: &str) -> Option<String> {
-    let encoded = url_encode(query);
-    let search_url = format!("https://www.allmusic.com/search/albums/{}", encoded);
+/// Try candidate album pages in order, returning the first that `fetch`
+/// accepts. With a year, a page whose JSON-LD release year differs is
+/// passed over for a later candidate and only used if none matches: search
+/// rows don't always list a year, so the page settles it.
+fn first_album_page(
+    candidates: Vec<String>,
+    year: Option<i32>,
+    mut fetch: impl FnMut(&str) -> Result<(SiteReview, Option<i32>), FetchError>,
+) -> Result<(String, SiteReview), FetchError> {
+    let mut off_year = None;
+    for album_url in candidates.into_iter().take(MAX_UNVERIFIED_PAGES) {
+        let (review, page_year) = match fetch(&album_url) {
+            Ok(found) => found,
+            Err(FetchError::NotFound) => continue,
+            Err(err) => return Err(err),
+        };
+        if year.is_some() && page_year.is_some() && page_year != year {
+            off_year.get_or_insert((album_url, review));
+            continue;
+        }
+        return Ok((album_url, review));
+    }
+    off_year.ok_or(FetchError::NotFound)
+}
 
-    let req = HttpRequest::new(&search_url).with_header("Accept", "text/html");
-    let resp = http::request::<()>(&req, None).ok()?;
-    if resp.status_code() != 200 {
-        return None;
+/// Fetch an album page and parse its JSON-LD rating and release year,
+/// rejecting pages by another artist or with contradicting release
+/// identifiers.
+fn album_page_review(
+    album_url: &str,
+    input: &AlbumReviewInput,
+) -> Result<(SiteReview, Option<i32>), FetchError> {
+    let body = fetch_page(&build_request(album_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
     }
+    let review = parse_album_page(album_url, &body, &input.artist).ok_or(FetchError::NotFound)?;
+    Ok((review, album_year(&body)))
+}
 
-    let html = String::from_utf8(resp.body().to_vec()).ok()?;
-    find_best_album_match(&html, title_slug, artist_slug)
+/// Read the release year from the album page's MusicAlbum JSON-LD
+/// `datePublished` ("1971-06-22" or just "1971").
+fn album_year(html: &str) -> Option<i32> {
+    let album = find_json_ld_node(html, &["MusicAlbum"])?;
+    let published = album.get("datePublished")?.as_str()?;
+    let digits = published.trim().get(..4)?;
+    if digits.chars().all(|c| c.is_ascii_digit()) {
+        digits.parse().ok()
+    } else {
+        None
+    }
 }
 
-/// Find the best matching album URL from search results HTML.
-fn find_best_album_match(html: &str, title_slug: &str, artist_slug: &str) -> Option<String> {
-    let album_links = extract_album_links(html);
-    let mut first_exact = None;
-
-    // Pass 1: Exact slug match + artist in context (strongest signal)
-    for (url, context) in &album_links {
-        let url_slug = extract_slug_from_url(url);
-        if slug_exact_match(&url_slug, title_slug) {
-            let context_slug = slugify(context);
-            if context_slug.contains(artist_slug) || artist_slug.is_empty() {
-                return Some(url.clone());
-            }
-            if first_exact.is_none() {
-                first_exact = Some(url.clone());
-            }
+/// List the album URLs AllMusic's search offers for an album and the one
+/// `fetch_review` would pick, without fetching the album page.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    let found = search_candidates(
+        &input.artist,
+        clean_title(&input.title),
+        input.year,
+        input.release_type,
+    );
+    found.map(|(result, _)| result).into()
+}
+
+/// Search AllMusic and list the album page URLs to try, best first.
+fn search_for_album(
+    artist: &str,
+    title: &str,
+    year: Option<i32>,
+    release: ReleaseType,
+) -> Result<Vec<String>, FetchError> {
+    let (_, matches) = search_candidates(artist, title, year, release)?;
+    if matches.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    Ok(matches)
+}
+
+/// Search AllMusic, keeping every album URL seen along with the matches to
+/// try (see `find_best_album_match`); the first match is reported as chosen.
+/// Tries artist+title first, then falls back to a title-only search.
+fn search_candidates(
+    artist: &str,
+    title: &str,
+    year: Option<i32>,
+    release: ReleaseType,
+) -> Result<(SearchResult, Vec<String>), FetchError> {
+    let album = Query {
+        year,
+        release,
+        ..Query::new(artist, title)
+    };
+    if album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let base = base_url();
+    let mut result = SearchResult::default();
+    let mut matches = Vec::new();
+
+    // The title-only fallback repeats the first search for a self-titled album
+    let mut queries = vec![search_terms(artist, title), title.to_string()];
+    queries.dedup();
+    for query in queries {
+        let search_url = format!("{}/search/albums/{}", base, url_encode(&query));
+        let html = match fetch_page(&build_request(&search_url, "text/html")) {
+            Ok(html) => html,
+            Err(FetchError::NotFound) => continue,
+            Err(err) => return Err(err),
+        };
+
+        let mut album_links = extract_album_links(&html, &base);
+        let preferred =
+            prefer_release_type(&mut album_links, release, &album.title_slug, |(url, _)| {
+                Some(link_slug(url))
+            });
+        let find = |links| find_best_album_match(&html, links, &album);
+        matches = find(&album_links[..preferred]);
+        if matches.is_empty() {
+            matches = find(&album_links);
         }
+        result.chosen = matches.first().cloned();
+        for (url, _) in album_links {
+            let slug = link_slug(&url);
+            result.add(SearchCandidate::scored(url, slug, &album));
+        }
+        if !matches.is_empty() {
+            break;
+        }
+    }
+
+    Ok((result, matches))
+}
+
+/// Find the album URLs to try among search result links, best first: a
+/// single artist-verified match, or else every title match for the caller
+/// to verify against the artist on its album page (see `match_album_links`).
+/// When a year is given, candidates whose listed release year matches are
+/// tried first (artist-verified passes only), so an original release wins
+/// over a remaster/reissue with a similar slug. Failing that, results that
+/// list no year are all returned, best first, for their album pages' years
+/// to decide between. A definitive match (see `definitive_match`) is
+/// returned without running the passes.
+fn find_best_album_match(html: &str, album_links: &[AlbumLink], album: &Query) -> Vec<String> {
+    if let Some(url) = definitive_match(html, album_links, album) {
+        return vec![url];
     }
+    if let Some(year) = album.year {
+        let dated: Vec<AlbumLink> = album_links
+            .iter()
+            .filter(|(_, context)| context_year(&html[context.clone()]) == Some(year))
+            .cloned()
+            .collect();
+        let matches = match_album_links(html, &dated, album, false);
+        if !matches.is_empty() {
+            return matches;
+        }
 
-    // Pass 2: Contains slug match + artist in context (e.g. URL-encoded titles)
-    for (url, context) in &album_links {
-        let url_slug = extract_slug_from_url(url);
-        if slug_matches(&url_slug, title_slug) {
-            let context_slug = slugify(context);
-            if context_slug.contains(artist_slug) || artist_slug.is_empty() {
-                return Some(url.clone());
+        let mut undated: Vec<AlbumLink> = album_links
+            .iter()
+            .filter(|(_, context)| context_year(&html[context.clone()]).is_none())
+            .cloned()
+            .collect();
+        let mut ranked = Vec::new();
+        while ranked.len() < MAX_UNVERIFIED_PAGES {
+            let matches = match_album_links(html, &undated, album, true);
+            if matches.is_empty() {
+                break;
             }
+            undated.retain(|(url, _)| !matches.contains(url));
+            ranked.extend(matches);
+        }
+        if ranked.len() > 1 {
+            return ranked;
         }
     }
 
-    // Pass 3: Exact slug match without artist context — rely on album page
-    // JSON-LD byArtist verification to reject wrong matches.
-    first_exact
+    match_album_links(html, album_links, album, true)
 }
 
-/// Check if a URL slug exactly matches the expected title slug (or its decoded form).
-fn slug_exact_match(url_slug: &str, title_slug: &str) -> bool {
-    if url_slug == title_slug {
-        return true;
+/// The first link whose context credits the artist and whose title slug
+/// scores `DEFINITIVE_MATCH_SCORE` (see `title_only`), listing the year when
+/// one is given. The passes would pick it anyway, so finding it up front
+/// skips them; a title found among other words never scores that high.
+fn definitive_match(html: &str, album_links: &[AlbumLink], album: &Query) -> Option<String> {
+    if album.artist_slug.is_empty() {
+        return None;
+    }
+    let title_only = title_only(album);
+    let (url, _) = album_links.iter().find(|(url, context)| {
+        let context = &html[context.clone()];
+        let score = score_candidate(&title_only, &Candidate::new(link_slug(url)));
+        score >= DEFINITIVE_MATCH_SCORE
+            && credits_artist(context, album)
+            && album
+                .year
+                .is_none_or(|year| context_year(context) == Some(year))
+    })?;
+    Some(url.clone())
+}
+
+/// The query for scoring a result whose context credits the artist:
+/// AllMusic's album slugs are the title alone, so only the title is scored.
+fn title_only(album: &Query) -> Query {
+    Query {
+        artist_slug: String::new(),
+        artist_aliases: Vec::new(),
+        ..album.clone()
+    }
+}
+
+/// The best-scoring link whose context credits the artist (see
+/// `best_candidate` and `title_only`). Failing that, with
+/// `allow_unverified`, every link whose slug clears `MIN_MATCH_SCORE` on
+/// its own, best first: two artists can share an album title, so each is
+/// checked against the album page's JSON-LD byArtist in turn rather than
+/// trusting the first.
+fn match_album_links(
+    html: &str,
+    album_links: &[AlbumLink],
+    album: &Query,
+    allow_unverified: bool,
+) -> Vec<String> {
+    let credited = best_candidate(&title_only(album), album_links, |(url, context)| {
+        credits_artist(&html[context.clone()], album).then(|| Candidate::new(link_slug(url)))
+    });
+    if let Some((url, _)) = credited {
+        return vec![url.clone()];
+    }
+    if !allow_unverified {
+        return Vec::new();
     }
-    let decoded = simple_url_decode(url_slug);
-    let decoded_slug = slugify(&decoded);
-    decoded_slug == title_slug
+
+    let mut unverified: Vec<(f64, &String)> = album_links
+        .iter()
+        .map(|(url, _)| (score_candidate(album, &Candidate::new(link_slug(url))), url))
+        .filter(|(score, _)| *score >= MIN_MATCH_SCORE)
+        .collect();
+    unverified.sort_by(|a, b| b.0.total_cmp(&a.0));
+    unverified.into_iter().map(|(_, url)| url.clone()).collect()
 }
 
-/// Check if a URL slug matches the expected title slug (substring with length guard).
-fn slug_matches(url_slug: &str, title_slug: &str) -> bool {
-    if url_slug.contains(title_slug) && is_close_length(title_slug, url_slug) {
+/// Whether a search result's context names the artist, under any of its
+/// aliases. A self-titled album's own link text already spells out the
+/// name, so for one of those only a link to the artist's page
+/// ("/artist/weezer-mn...") in the result's own context counts.
+fn credits_artist(context: &str, album: &Query) -> bool {
+    if album.artist_slug.is_empty() {
         return true;
     }
-    let decoded = simple_url_decode(url_slug);
-    let decoded_slug = slugify(&decoded);
-    decoded_slug.contains(title_slug) && is_close_length(title_slug, &decoded_slug)
+    let mut artists = std::iter::once(&album.artist_slug).chain(&album.artist_aliases);
+    if !album.self_titled() {
+        let context = slugify(context);
+        return artists.any(|artist| context.contains(artist.as_str()));
+    }
+    let own = own_context(context);
+    artists.any(|artist| own.contains(&format!("href=\"/artist/{}-mn", artist)))
+}
+
+/// A search result's context cut at the next album link, so a neighbouring
+/// result's details aren't picked up.
+fn own_context(context: &str) -> &str {
+    match context.find("href=\"/album/") {
+        Some(end) => &context[..end],
+        None => context,
+    }
 }
 
-/// Require the title slug to be at least 70% of the URL slug length.
-/// Blocks false positives like "baby" matching "plays-pretty-for-baby".
-fn is_close_length(title_slug: &str, url_slug: &str) -> bool {
-    if url_slug.is_empty() {
-        return false;
+/// Read the release year listed for a search result from its context HTML.
+fn context_year(context: &str) -> Option<i32> {
+    let own = own_context(context);
+    let marker_pos = own.find("class=\"year\"")?;
+    let rest = &own[marker_pos..];
+    let text_start = rest.find('>')? + 1;
+    let digits: String = rest[text_start..]
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    if digits.len() == 4 {
+        digits.parse().ok()
+    } else {
+        None
     }
-    (title_slug.len() as f64 / url_slug.len() as f64) >= 0.7
 }
 
 /// Simple percent-decoding for URL path segments.
+/// Decoded bytes are collected first so multi-byte UTF-8 sequences
+/// (e.g. `%C3%A9` -> "é") reconstruct correctly.
 fn simple_url_decode(s: &str) -> String {
-    let mut result = String::with_capacity(s.len());
+    let mut result = Vec::with_capacity(s.len());
     let bytes = s.as_bytes();
     let mut i = 0;
     while i < bytes.len() {
         if bytes[i] == b'%' && i + 2 < bytes.len() {
-            if let Ok(byte) = u8::from_str_radix(&s[i + 1..i + 3], 16) {
-                result.push(byte as char);
+            if let Some(byte) = s
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                result.push(byte);
                 i += 3;
                 continue;
             }
         }
-        result.push(bytes[i] as char);
+        result.push(bytes[i]);
         i += 1;
     }
-    result
+    String::from_utf8_lossy(&result).into_owned()
 }
 
-/// Extract album links and surrounding context from search results HTML.
-fn extract_album_links(html: &str) -> Vec<(String, String)> {
+/// An album link from a search results page: its URL and the span of the
+/// page after it that holds the result's details.
+type AlbumLink = (String, Range<usize>);
+
+/// Extract album links and the span of their surrounding context from
+/// search results HTML. The context is only sliced out of the page for the
+/// links the matcher inspects.
+fn extract_album_links(html: &str, base: &str) -> Vec<AlbumLink> {
     let pattern = "href=\"/album/";
     let mut results = Vec::new();
     let mut search_from = 0;
 
-    loop {
-        let Some(pos) = html[search_from..].find(pattern) else {
-            break;
-        };
+    while let Some(pos) = html[search_from..].find(pattern) {
         let abs_pos = search_from + pos;
         let path_start = abs_pos + "href=\"".len();
         let Some(end_offset) = html[path_start..].find('"') else {
@@ -168,11 +446,13 @@ fn extract_album_links(html: &str) -> Vec<(String, String)> {
         let path = &html[path_start..path_end];
 
         if path.contains("-mw") {
-            let full_url = format!("https://www.allmusic.com{}", path);
-            let context_end = (path_end + 2000).min(html.len());
-            let context = &html[path_end..context_end];
-            if !results.iter().any(|(u, _): &(String, String)| u == &full_url) {
-                results.push((full_url, context.to_string()));
+            let full_url = format!("{}{}", base, path);
+            let mut context_end = (path_end + 2000).min(html.len());
+            while !html.is_char_boundary(context_end) {
+                context_end -= 1;
+            }
+            if !results.iter().any(|(u, _): &AlbumLink| u == &full_url) {
+                results.push((full_url, path_end..context_end));
             }
         }
 
@@ -185,6 +465,11 @@ fn extract_album_links(html: &str) -> Vec<(String, String)> {
     results
 }
 
+/// An album URL's title slug, percent-decoded and re-slugified.
+fn link_slug(url: &str) -> String {
+    slugify(&simple_url_decode(&extract_slug_from_url(url)))
+}
+
 /// Extract the title slug from an AllMusic album URL.
 fn extract_slug_from_url(url: &str) -> String {
     let path = url.split("/album/").nth(1).unwrap_or("");
@@ -195,43 +480,18 @@ fn extract_slug_from_url(url: &str) -> String {
     }
 }
 
-#[derive(Deserialize)]
-struct AlbumJsonLd {
-    #[serde(rename = "aggregateRating")]
-    aggregate_rating: Option<AggregateRating>,
-    #[serde(rename = "byArtist")]
-    by_artist: Option<Vec<ByArtist>>,
-}
-
-#[derive(Deserialize)]
-struct ByArtist {
-    name: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct AggregateRating {
-    #[serde(rename = "ratingValue")]
-    rating_value: Option<String>,
-    #[serde(rename = "ratingCount")]
-    rating_count: Option<u32>,
-    #[serde(rename = "bestRating")]
-    best_rating: Option<String>,
-}
-
 /// Parse the reviewAjax HTML for review text and reviewer name.
 /// Format: <h3>Album Review by Reviewer Name</h3> <p>Review text...</p>
 fn parse_review_ajax(html: &str) -> (Option<String>, Option<String>) {
-    let reviewer = html
-        .find("<h3>")
-        .and_then(|start| {
-            let inner_start = start + 4;
-            let inner_end = html[inner_start..].find("</h3>")? + inner_start;
-            let h3_text = strip_html_tags(&html[inner_start..inner_end]);
-            // Format: "Album Review by Reviewer Name"
-            h3_text
-                .find(" Review by ")
-                .map(|pos| h3_text[pos + " Review by ".len()..].trim().to_string())
-        });
+    let reviewer = html.find("<h3>").and_then(|start| {
+        let inner_start = start + 4;
+        let inner_end = html[inner_start..].find("</h3>")? + inner_start;
+        let h3_text = decode_html_entities(&strip_html_tags(&html[inner_start..inner_end]));
+        // Format: "Album Review by Reviewer Name"
+        h3_text
+            .find(" Review by ")
+            .map(|pos| h3_text[pos + " Review by ".len()..].trim().to_string())
+    });
 
     let excerpt = {
         let mut paragraphs = Vec::new();
@@ -240,7 +500,7 @@ fn parse_review_ajax(html: &str) -> (Option<String>, Option<String>) {
             let abs_start = search_from + p_pos + 3;
             if let Some(end_offset) = html[abs_start..].find("</p>") {
                 let abs_end = abs_start + end_offset;
-                let text = strip_html_tags(&html[abs_start..abs_end]);
+                let text = decode_html_entities(&strip_html_tags(&html[abs_start..abs_end]));
                 let trimmed = text.trim();
                 if !trimmed.is_empty() {
                     paragraphs.push(trimmed.to_string());
@@ -260,103 +520,264 @@ fn parse_review_ajax(html: &str) -> (Option<String>, Option<String>) {
     (excerpt, reviewer)
 }
 
-/// Strip HTML tags from a string, keeping only text content.
-fn strip_html_tags(html: &str) -> String {
-    let mut result = String::with_capacity(html.len());
-    let mut in_tag = false;
-    for ch in html.chars() {
-        match ch {
-            '<' => in_tag = true,
-            '>' => in_tag = false,
-            _ if !in_tag => result.push(ch),
-            _ => {}
-        }
-    }
-    result
-}
-
-/// Parse an AllMusic album page for rating data from JSON-LD.
+/// Parse an AllMusic album page for rating data from its MusicAlbum
+/// JSON-LD, parsed once and read for the artist check and the rating.
 /// Verifies that the page's byArtist matches the expected artist.
 fn parse_album_page(url: &str, html: &str, artist: &str) -> Option<SiteReview> {
-    let json_ld = extract_album_json_ld(html)?;
-    let album: AlbumJsonLd = serde_json::from_str(&json_ld).ok()?;
+    let album = find_json_ld_node(html, &["MusicAlbum"])?;
 
     // Verify artist from JSON-LD structured data
     let artist_slug = slugify(artist);
     if !artist_slug.is_empty() {
-        let artist_ok = album.by_artist.as_ref().map_or(false, |artists| {
-            artists.iter().any(|a| {
-                a.name
-                    .as_ref()
-                    .map_or(false, |n| slugify(n).contains(&artist_slug))
-            })
-        });
+        let named = |a: &serde_json::Value| {
+            json_ld_name(a).is_some_and(|name| slugify(&name).contains(&artist_slug))
+        };
+        let artist_ok = match album.get("byArtist") {
+            Some(serde_json::Value::Array(artists)) => artists.iter().any(named),
+            Some(by_artist) => named(by_artist),
+            None => false,
+        };
         if !artist_ok {
             return None;
         }
     }
 
-    let agg = album.aggregate_rating?;
+    // Rating and count are independent: a missing/invalid value must not
+    // discard a valid count, and vice versa. A page with neither (e.g. a
+    // "N/A" rating) is still returned so the review text can be added.
+    let agg = album.get("aggregateRating");
+    let raw_rating = agg.and_then(parse_aggregate_rating);
+    let rating_count = agg
+        .and_then(|agg| agg.get("ratingCount"))
+        .and_then(json_ld_number)
+        .filter(|count| *count >= 0.0)
+        .map(|count| count as u32);
+    let image_url = album
+        .get("image")
+        .and_then(json_ld_image)
+        .or_else(|| meta_content(html, "og:image"));
+
+    Some(
+        SiteReview::builder(url)
+            .native_rating(raw_rating)
+            .rating_count(rating_count)
+            .image_url(image_url)
+            .build(),
+    )
+}
 
-    let rating_value: f64 = agg.rating_value.as_deref()?.parse().ok()?;
-    let best: f64 = agg
-        .best_rating
-        .as_deref()
-        .and_then(|s| s.parse().ok())
+/// Read an aggregateRating as (value, best), accepting only values that
+/// normalize into the 0-10 range.
+fn parse_aggregate_rating(agg: &serde_json::Value) -> Option<(f64, f64)> {
+    let rating_value = json_ld_number(agg.get("ratingValue")?)?;
+    let best = agg
+        .get("bestRating")
+        .and_then(json_ld_number)
         .unwrap_or(10.0);
 
-    let rating = if best > 0.0 {
-        (rating_value / best) * 10.0
-    } else {
-        return None;
-    };
-
-    if !(0.0..=10.0).contains(&rating) {
+    if best <= 0.0 {
         return None;
     }
 
-    Some(SiteReview {
-        source_url: url.to_string(),
-        excerpt: None,
-        rating: Some(rating),
-        rating_count: agg.rating_count,
-        reviewer: None,
-        review_date: None,
-    })
+    if (0.0..=10.0).contains(&scale_rating(rating_value, best)) {
+        Some((rating_value, best))
+    } else {
+        None
+    }
 }
 
-/// Extract the JSON-LD block containing MusicAlbum schema from HTML.
-fn extract_album_json_ld(html: &str) -> Option<String> {
-    let marker = "application/ld+json";
-    let mut search_from = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    loop {
-        let tag_pos = html[search_from..].find(marker)?;
-        let abs_pos = search_from + tag_pos;
-
-        let content_start = html[abs_pos..].find('>')? + abs_pos + 1;
-        let content_end = html[content_start..].find("</script>")? + content_start;
-        let json_str = html[content_start..content_end].trim();
-
-        if json_str.contains("\"MusicAlbum\"") {
-            if json_str.starts_with('[') {
-                if let Ok(arr) = serde_json::from_str::<Vec<serde_json::Value>>(json_str) {
-                    for item in &arr {
-                        let s = item.to_string();
-                        if s.contains("\"MusicAlbum\"") {
-                            return Some(s);
-                        }
-                    }
-                }
-            }
-            return Some(json_str.to_string());
-        }
+    #[test]
+    fn url_decode_multibyte_utf8() {
+        assert_eq!(simple_url_decode("%C3%A9"), "é");
+        assert_eq!(simple_url_decode("caf%C3%A9-society"), "café-society");
+    }
 
-        search_from = content_end;
-        if search_from >= html.len().saturating_sub(50) {
-            break;
-        }
+    #[test]
+    fn album_json_ld_found_in_graph() {
+        let album = r#"{"@type":"MusicAlbum","byArtist":[{"name":"Björk"}],"image":"https://cdn.allmusic.com/x.jpg","aggregateRating":{"ratingValue":"4.50","bestRating":"5","ratingCount":12}}"#;
+        let html = format!(
+            r#"<script type="application/ld+json">[{{"@type":"WebPage"}},{{"@graph":[{{"@type":"Organization"}},{}]}}]</script>"#,
+            album
+        );
+        let review = parse_album_page("https://www.allmusic.com/album/x-mw1", &html, "Björk")
+            .expect("album in a graph should be found");
+        assert_eq!(review.rating_raw, Some(4.5));
+        assert_eq!(review.rating_count, Some(12));
+        let image_url = review.image_url.as_deref();
+        assert_eq!(image_url, Some("https://cdn.allmusic.com/x.jpg"));
+    }
+
+    #[test]
+    fn same_title_albums_are_all_candidates() {
+        let html = r#"<ul class="search-results">
+            <li><a href="/album/blue-mw0000001">Blue</a><span class="year">1971</span></li>
+            <li><a href="/album/blue-mw0000002">Blue</a><span class="year">1998</span></li>
+            <li><a href="/album/blue-lines-mw0000003">Blue Lines</a><span class="year">1991</span></li>
+        </ul>"#;
+        let base = "https://www.allmusic.com";
+        let links = extract_album_links(html, base);
+        let bosstones = Query::new("The Mighty Mighty Bosstones", "Blue");
+        let matches = find_best_album_match(html, &links, &bosstones);
+        assert_eq!(
+            matches,
+            vec![
+                format!("{}/album/blue-mw0000001", base),
+                format!("{}/album/blue-mw0000002", base),
+            ]
+        );
+
+        // Each candidate's page settles it: the first is by someone else
+        let page = |artist: &str| {
+            format!(
+                r#"<script type="application/ld+json">{{"@type":"MusicAlbum","byArtist":[{{"name":"{}"}}],"aggregateRating":{{"ratingValue":"4","bestRating":"5"}}}}</script>"#,
+                artist
+            )
+        };
+        let artist = "The Mighty Mighty Bosstones";
+        assert!(parse_album_page(&matches[0], &page("Joni Mitchell"), artist).is_none());
+        assert!(parse_album_page(&matches[1], &page(artist), artist).is_some());
+    }
+
+    #[test]
+    fn short_title_not_matched_inside_longer_one() {
+        let html = r#"<ul class="search-results">
+            <li><a href="/album/plays-pretty-for-baby-mw0000001">Plays Pretty for Baby</a>
+                <a href="/artist/jimmie-vaughan-mn1">Jimmie Vaughan</a></li>
+            <li><a href="/album/baby%21-mw0000002">Baby!</a>
+                <a href="/artist/jimmie-vaughan-mn1">Jimmie Vaughan</a></li>
+        </ul>"#;
+        let links = extract_album_links(html, "https://www.allmusic.com");
+        let baby = Query::new("Jimmie Vaughan", "Baby!");
+        let matches = match_album_links(html, &links, &baby, true);
+        assert_eq!(matches, vec![links[1].0.clone()]);
+        assert!(match_album_links(html, &links[..1], &baby, true).is_empty());
+    }
+
+    #[test]
+    fn undated_same_title_albums_settled_by_page_year() {
+        let html = r#"<ul class="search-results">
+            <li><a href="/album/blue-mw0000001">Blue</a> <a href="/artist/joni-mitchell-mn1">Joni Mitchell</a></li>
+            <li><a href="/album/blue-mw0000002">Blue</a> <a href="/artist/joni-mitchell-mn1">Joni Mitchell</a></li>
+        </ul>"#;
+        let base = "https://www.allmusic.com";
+        let links = extract_album_links(html, base);
+        let joni = Query::new("Joni Mitchell", "Blue");
+        let dated = Query {
+            year: Some(1971),
+            ..joni.clone()
+        };
+        let matches = find_best_album_match(html, &links, &dated);
+        assert_eq!(matches.len(), 2);
+        let single = find_best_album_match(html, &links, &joni);
+        assert_eq!(single, vec![matches[0].clone()]);
+
+        let page = |year: &str| {
+            format!(
+                r#"<script type="application/ld+json">{{"@type":"MusicAlbum","byArtist":[{{"name":"Joni Mitchell"}}],"datePublished":"{}","aggregateRating":{{"ratingValue":"5","bestRating":"5"}}}}</script>"#,
+                year
+            )
+        };
+        // The first row is the 2021 remaster; the second is the original
+        let pages = [page("2021-10-29"), page("1971")];
+        let fetch = |url: &str| {
+            let html = if url.ends_with("1") {
+                &pages[0]
+            } else {
+                &pages[1]
+            };
+            let review =
+                parse_album_page(url, html, "Joni Mitchell").ok_or(FetchError::NotFound)?;
+            Ok((review, album_year(html)))
+        };
+        assert_eq!(album_year(&pages[0]), Some(2021));
+
+        let (url, _) = first_album_page(matches.clone(), Some(1971), fetch)
+            .ok()
+            .unwrap();
+        assert_eq!(url, matches[1]);
+        let (url, _) = first_album_page(matches.clone(), Some(1998), fetch)
+            .ok()
+            .unwrap();
+        assert_eq!(url, matches[0]);
+        let (url, _) = first_album_page(matches.clone(), None, fetch).ok().unwrap();
+        assert_eq!(url, matches[0]);
     }
 
-    None
+    #[test]
+    fn self_titled_album_needs_artist_link() {
+        let html = r#"<ul class="search-results">
+            <li><a href="/album/weezer-mw0000001">Weezer</a>
+                <a href="/artist/weezer-tribute-mn0000009">Weezer Tribute</a></li>
+            <li><a href="/album/weezer-mw0000002">Weezer</a>
+                <a href="/artist/weezer-mn0000003">Weezer</a></li>
+            <li><a href="/album/pinkerton-mw0000004">Pinkerton</a></li>
+        </ul>"#;
+        let base = "https://www.allmusic.com";
+        let links = extract_album_links(html, base);
+        assert_eq!(links.len(), 3);
+        let weezer = Query::new("Weezer", "Weezer");
+        let matches = find_best_album_match(html, &links, &weezer);
+        assert_eq!(matches, vec![format!("{}/album/weezer-mw0000002", base)]);
+
+        // A tribute act's "Weezer" credits a different artist page
+        let definitive = definitive_match(html, &links, &weezer);
+        assert_eq!(definitive.as_ref(), Some(&matches[0]));
+        let partial = Query::new("Weezer", "Weez");
+        assert_eq!(definitive_match(html, &links, &partial), None);
+
+        let tribute = &html[links[0].1.clone()];
+        assert!(!credits_artist(tribute, &weezer));
+        assert!(credits_artist(tribute, &Query::new("Weezer", "Pinkerton")));
+
+        // Listed under another spelling of the artist's name
+        let beatles = r#"<li><a href="/album/abbey-road-mw0000001">Abbey Road</a>
+            <a href="/artist/beatles-mn0000002">Beatles</a></li>"#;
+        let links = extract_album_links(beatles, base);
+        let abbey_road = Query::new("The Beatles", "Abbey Road");
+        assert_eq!(
+            match_album_links(beatles, &links, &abbey_road, false).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn album_page_keeps_count_without_rating_value() {
+        let html = r#"<script type="application/ld+json">{"@type":"MusicAlbum","byArtist":[{"name":"Radiohead"}],"aggregateRating":{"ratingCount":42}}</script>"#;
+        let review = parse_album_page("https://www.allmusic.com/album/x-mw1", html, "Radiohead")
+            .expect("count-only rating should still produce a review");
+        assert_eq!(review.rating, None);
+        assert_eq!(review.rating_count, Some(42));
+    }
+
+    #[test]
+    fn album_page_survives_non_numeric_rating() {
+        let html = r#"<script type="application/ld+json">{"@type":"MusicAlbum","byArtist":[{"name":"Radiohead"}],"aggregateRating":{"ratingValue":"N/A","bestRating":"5"}}</script>"#;
+        let url = "https://www.allmusic.com/album/x-mw1";
+        let mut review = parse_album_page(url, html, "Radiohead")
+            .expect("unrated album should still produce a review");
+        assert_eq!(review.rating, None);
+        assert!(require_content(review.clone()).is_err());
+
+        let ajax = "<h3>Album Review by Jane Doe</h3> <p>A quietly great record.</p>";
+        let (excerpt, reviewer) = parse_review_ajax(ajax);
+        review.excerpt = excerpt;
+        review.reviewer = reviewer;
+        let review = require_content(review).expect("review text is enough");
+        assert_eq!(review.excerpt.as_deref(), Some("A quietly great record."));
+        assert_eq!(review.reviewer.as_deref(), Some("Jane Doe"));
+    }
+
+    #[test]
+    fn album_page_keeps_native_rating_scale() {
+        let html = r#"<script type="application/ld+json">{"@type":"MusicAlbum","byArtist":[{"name":"Radiohead"}],"aggregateRating":{"ratingValue":"4","bestRating":"5"}}</script>"#;
+        let review = parse_album_page("https://www.allmusic.com/album/x-mw1", html, "Radiohead")
+            .expect("rated album should produce a review");
+        assert_eq!(review.rating, Some(8.0));
+        assert_eq!(review.rating_raw, Some(4.0));
+        assert_eq!(review.rating_scale, Some(5.0));
+    }
 }
@@ -1,16 +1,6 @@
-mod allmusic;
+pub mod allmusic;
 
-use editorial_common::{wrap_review, AlbumReviewInput};
-use extism_pdk::*;
+/// Review fields this source can populate.
+pub const CAPABILITIES: &[&str] = &["rating", "rating_count", "excerpt", "reviewer", "image"];
 
-#[plugin_fn]
-pub fn riff_health_check(_input: String) -> FnResult<String> {
-    Ok("ok".to_string())
-}
-
-#[plugin_fn]
-pub fn riff_get_album_reviews(input: String) -> FnResult<String> {
-    let params: AlbumReviewInput = serde_json::from_str(&input)?;
-    let review = allmusic::fetch_review(&params.artist, &params.title);
-    Ok(wrap_review("allmusic", review))
-}
+editorial_common::editorial_plugin!(allmusic, "allmusic", CAPABILITIES);
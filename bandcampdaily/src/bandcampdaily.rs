@@ -0,0 +1,264 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, fetch_page, find_json_ld_node,
+    json_ld_name, match_confidence, meta_content, normalize_date, paragraph_text,
+    release_ids_conflict, search_terms, site_links, slugify, truncate_at_sentence, url_encode,
+    AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query, SearchCandidate, SearchResult,
+    SiteReview, MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `bandcampdaily_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://daily.bandcamp.com";
+const BASE_URL_CONFIG: &str = "bandcampdaily_base_url";
+
+/// Section of the daily album pick, the one feature that reviews a single
+/// album.
+const ALBUM_OF_THE_DAY: &str = "album-of-the-day";
+
+/// Sections whose features cover albums: the daily pick, the monthly genre
+/// roundups ("/best-ambient/...") and the long-form features and lists.
+/// Interviews, scene reports and label profiles are left out.
+const FEATURE_SECTIONS: &[&str] = &[ALBUM_OF_THE_DAY, "best-", "features", "lists"];
+
+/// Class of the element holding a feature's text.
+const BODY_MARKER: &str = "class=\"article-body";
+
+/// The site root, honoring the `bandcampdaily_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch Bandcamp Daily coverage of the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Bandcamp Daily doesn't score albums: resolve the feature search would pick,
+/// without fetching it, and return it with no rating.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the feature at a known Bandcamp Daily URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_feature_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let feature_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&feature_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+
+    let mut review = parse_feature_page(&feature_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&feature_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let feature_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&feature_url, input);
+    Ok(SiteReview::builder(feature_url)
+        .match_confidence(confidence)
+        .build())
+}
+
+/// How well the feature's URL slug matches the album.
+fn review_confidence(feature_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    feature_slug(feature_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the features Bandcamp Daily's search offers for an album and the one
+/// `fetch_review` would pick, without fetching the feature itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Bandcamp Daily for "artist title" and keep every album feature it
+/// lists along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/search?q={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+    Ok(parse_search_page(&html, &base, &album))
+}
+
+/// The album features linked from a search results page, and the pick.
+fn parse_search_page(html: &str, base: &str, album: &Query) -> SearchResult {
+    let urls: Vec<String> = site_links(html, base, "")
+        .into_iter()
+        .filter(|url| feature_slug(url).is_some())
+        .collect();
+    let mut result = SearchResult {
+        chosen: pick_feature_url(&urls, album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = feature_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    result
+}
+
+/// The best-scoring feature for the album (see `best_candidate`), with an
+/// Album of the Day winning a tie.
+fn pick_feature_url(urls: &[String], query: &Query) -> Option<String> {
+    let mut ordered: Vec<&String> = urls.iter().collect();
+    ordered.sort_by_key(|url| !is_album_of_the_day(url));
+    let best = best_candidate(query, &ordered, |url| feature_slug(url).map(Candidate::new));
+    best.map(|url| url.to_string())
+}
+
+/// The slug of a feature URL, without the "-review" suffix Album of the Day
+/// posts carry: "/album-of-the-day/wet-leg-moisturizer-review" ->
+/// "wet-leg-moisturizer". None outside the album feature sections and for
+/// the section pages themselves.
+fn feature_slug(url: &str) -> Option<String> {
+    let path = url.split_once("://")?.1;
+    let mut segments = path.split(['?', '#']).next()?.split('/').skip(1);
+    let section = segments.next()?;
+    if !FEATURE_SECTIONS.iter().any(|s| section.starts_with(s)) {
+        return None;
+    }
+
+    let slug = slugify(segments.next()?);
+    let slug = slug.strip_suffix("-review").unwrap_or(&slug);
+    Some(slug.to_string()).filter(|slug| !slug.is_empty())
+}
+
+fn is_album_of_the_day(url: &str) -> bool {
+    url.contains(&format!("/{}/", ALBUM_OF_THE_DAY))
+}
+
+/// Parse a Bandcamp Daily feature for its text, byline and publish date.
+/// There is no score; Album of the Day picks are reported as an accolade.
+fn parse_feature_page(url: &str, html: &str) -> Option<SiteReview> {
+    let excerpt = parse_feature_body(html).or_else(|| meta_content(html, "og:description"))?;
+    let article = find_json_ld_node(html, &["NewsArticle", "Article"]);
+    let field = |key: &str| article.as_ref().and_then(|node| node.get(key));
+
+    let reviewer = meta_content(html, "author").or_else(|| field("author").and_then(json_ld_name));
+    let review_date = meta_content(html, "article:published_time")
+        .or_else(|| field("datePublished")?.as_str().map(str::to_string))
+        .and_then(|date| normalize_date(&date));
+    let accolade = is_album_of_the_day(url).then(|| "Album of the Day".to_string());
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(Some(excerpt))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .accolade(accolade)
+            .build(),
+    )
+}
+
+/// The feature's paragraphs, cut to ~2000 chars at a sentence boundary.
+/// Only `<p>` elements count, which skips the embedded players and buy
+/// buttons between them.
+fn parse_feature_body(html: &str) -> Option<String> {
+    let start = html.find(BODY_MARKER)?;
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |end| start + end);
+    let text = paragraph_text(&html[start..end]);
+    Some(truncate_at_sentence(&text, MAX_EXCERPT_CHARS)).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://daily.bandcamp.com";
+
+    #[test]
+    fn only_album_feature_sections_yield_slugs() {
+        let slug = |path: &str| feature_slug(&format!("{}{}", BASE, path));
+        let aotd = slug("/album-of-the-day/wet-leg-moisturizer-review?from=search");
+        assert_eq!(aotd.as_deref(), Some("wet-leg-moisturizer"));
+        let roundup = slug("/best-rock/the-best-rock-on-bandcamp-july-2025");
+        assert_eq!(
+            roundup.as_deref(),
+            Some("the-best-rock-on-bandcamp-july-2025")
+        );
+        let list = slug("/lists/wet-leg-moisturizer-list");
+        assert_eq!(list.as_deref(), Some("wet-leg-moisturizer-list"));
+        assert_eq!(slug("/album-of-the-day"), None);
+        assert_eq!(slug("/album-of-the-day/"), None);
+        assert_eq!(slug("/interviews/wet-leg-moisturizer-interview"), None);
+        assert_eq!(slug("/label-profile/domino"), None);
+    }
+
+    #[test]
+    fn search_page_prefers_the_album_of_the_day() {
+        let html = r#"<a href="/album-of-the-day">Album of the Day</a>
+            <a href="/interviews/wet-leg-moisturizer-interview">Interview</a>
+            <a href="/lists/wet-leg-moisturizer">List</a>
+            <a href="https://wetleg.bandcamp.com/album/moisturizer">Buy</a>
+            <a href="/album-of-the-day/wet-leg-moisturizer-review">AOTD</a>"#;
+        let album = Query::new("wet-leg", "moisturizer");
+        let result = parse_search_page(html, BASE, &album);
+        let slugs: Vec<&str> = result.candidates.iter().map(|c| c.slug.as_str()).collect();
+        assert_eq!(slugs, ["wet-leg-moisturizer", "wet-leg-moisturizer"]);
+        let aotd = format!("{}/album-of-the-day/wet-leg-moisturizer-review", BASE);
+        assert_eq!(result.chosen, Some(aotd));
+
+        let without_aotd = html.replace("/album-of-the-day/", "/interviews/");
+        let result = parse_search_page(&without_aotd, BASE, &album);
+        let list = format!("{}/lists/wet-leg-moisturizer", BASE);
+        assert_eq!(result.chosen, Some(list));
+    }
+
+    #[test]
+    fn feature_text_without_rating() {
+        let html = r#"<head><meta name="author" content="J. Edward Keyes">
+            <meta property="article:published_time" content="2025-07-11T13:00:00Z"></head>
+            <article><div class="article-body"><p>Wet Leg&rsquo;s second album is louder.</p>
+            <div class="player"><iframe src="x"></iframe></div><p>It <em>works</em>.</p>
+            </div></article><p>More from Bandcamp Daily</p>"#;
+        let url = format!("{}/album-of-the-day/wet-leg-moisturizer-review", BASE);
+        let review = parse_feature_page(&url, html).unwrap();
+        assert_eq!(review.rating, None);
+        let excerpt = review.excerpt.unwrap();
+        assert_eq!(
+            excerpt,
+            "Wet Leg\u{2019}s second album is louder.\n\nIt works."
+        );
+        assert_eq!(review.reviewer.as_deref(), Some("J. Edward Keyes"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-11"));
+        assert_eq!(review.accolade.as_deref(), Some("Album of the Day"));
+
+        // Lists carry no accolade, and older pages only JSON-LD and og tags
+        let older = r#"<meta property="og:description" content="A louder Wet Leg.">
+            <script type="application/ld+json">{"@type":"NewsArticle",
+            "author":{"@type":"Person","name":"Marcus J. Moore"},
+            "datePublished":"2025-07-30T09:00:00Z"}</script>"#;
+        let list = format!("{}/lists/wet-leg-moisturizer-list", BASE);
+        let review = parse_feature_page(&list, older).unwrap();
+        assert_eq!(review.excerpt.as_deref(), Some("A louder Wet Leg."));
+        assert_eq!(review.reviewer.as_deref(), Some("Marcus J. Moore"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-30"));
+        assert_eq!(review.accolade, None);
+    }
+}
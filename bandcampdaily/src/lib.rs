@@ -0,0 +1,6 @@
+pub mod bandcampdaily;
+
+/// Review fields this source can populate.
+pub const CAPABILITIES: &[&str] = &["excerpt", "reviewer", "date", "accolade"];
+
+editorial_common::editorial_plugin!(bandcampdaily, "bandcampdaily", CAPABILITIES);
@@ -0,0 +1,274 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, decode_html_entities,
+    element_text, extract_json_ld, fetch_page, json_ld_name, json_ld_rating, match_confidence,
+    normalize_date, release_ids_conflict, search_terms, site_links, slugify, strip_html_tags,
+    truncate_at_sentence, url_encode, AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query,
+    SearchCandidate, SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+use serde::Deserialize;
+
+/// Production site root; override with the `beatsperminute_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://beatsperminute.com";
+const BASE_URL_CONFIG: &str = "beatsperminute_base_url";
+
+/// Beats Per Minute scores albums out of ten.
+const SCORE_SCALE: f64 = 10.0;
+
+/// Class of the element showing the score, for pages whose JSON-LD carries
+/// no `reviewRating`.
+const SCORE_MARKER: &str = "class=\"review-score";
+
+/// The site root, honoring the `beatsperminute_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch a Beats Per Minute review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its score, skipping the review text,
+/// author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known Beats Per Minute URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let json_ld = parse_json_ld(&body);
+    let score = parse_score(json_ld.as_ref(), &body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((score, SCORE_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the review URLs Beats Per Minute's search offers for an album and
+/// the one `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Beats Per Minute for "artist title" and keep every album review
+/// it lists along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/?s={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+    Ok(parse_search_page(&html, &base, &album))
+}
+
+/// The album reviews linked from a search results page, and the pick.
+/// Posts live at the site root, so every on-site link is a candidate and
+/// the slug decides which are album reviews.
+fn parse_search_page(html: &str, base: &str, album: &Query) -> SearchResult {
+    let urls: Vec<String> = site_links(html, base, "")
+        .into_iter()
+        .filter(|url| review_slug(url).is_some())
+        .collect();
+    let mut result = SearchResult {
+        chosen: pick_review_url(&urls, album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    result
+}
+
+/// The best-scoring review for the album; see `best_candidate`.
+fn pick_review_url(urls: &[String], query: &Query) -> Option<String> {
+    best_candidate(query, urls, |url| review_slug(url).map(Candidate::new)).cloned()
+}
+
+/// The album part of a review post's slug, which Beats Per Minute marks
+/// with a leading "album-review-" or, on older posts, a trailing "-review":
+/// "/album-review-wet-leg-moisturizer/" -> "wet-leg-moisturizer". None for
+/// any other post and for the "/category/album-review/" listing.
+fn review_slug(url: &str) -> Option<String> {
+    let path = url.split_once("://")?.1;
+    let path = path.split(['?', '#']).next()?.trim_end_matches('/');
+    let slug = slugify(path.rsplit_once('/')?.1);
+    if slug == "album-review" {
+        return None;
+    }
+    let album = slug
+        .strip_prefix("album-review-")
+        .or_else(|| slug.strip_suffix("-album-review"))
+        .or_else(|| slug.strip_suffix("-review"))?;
+    Some(album.to_string()).filter(|album| !album.is_empty())
+}
+
+/// JSON-LD schema for Beats Per Minute review pages.
+#[derive(Deserialize)]
+struct JsonLdReview {
+    #[serde(rename = "reviewBody")]
+    review_body: Option<String>,
+    description: Option<String>,
+    author: Option<serde_json::Value>,
+    #[serde(rename = "datePublished")]
+    date_published: Option<String>,
+    #[serde(rename = "reviewRating")]
+    review_rating: Option<serde_json::Value>,
+}
+
+fn parse_json_ld(html: &str) -> Option<JsonLdReview> {
+    serde_json::from_str(&extract_json_ld(html)?).ok()
+}
+
+/// Parse a Beats Per Minute review page for the score (out of 10) and the
+/// review's text, author and date from its JSON-LD.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let json_ld = parse_json_ld(html);
+    let score = parse_score(json_ld.as_ref(), html);
+
+    let (excerpt, reviewer, review_date) = match json_ld {
+        Some(review) => {
+            let excerpt = review
+                .review_body
+                .or(review.description)
+                .map(|body| decode_html_entities(&strip_html_tags(&body)))
+                .map(|body| truncate_at_sentence(body.trim(), MAX_EXCERPT_CHARS))
+                .filter(|body| !body.is_empty());
+            let reviewer = review.author.as_ref().and_then(json_ld_name);
+            let date = review.date_published.as_deref().and_then(normalize_date);
+            (excerpt, reviewer, date)
+        }
+        None => (None, None, None),
+    };
+
+    if score.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(score.map(|s| (s, SCORE_SCALE)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The score, from the JSON-LD `reviewRating` or else the score element's
+/// text ("7.8" or "7.8/10"). Anything outside 0-10 is rejected.
+fn parse_score(json_ld: Option<&JsonLdReview>, html: &str) -> Option<f64> {
+    let from_json_ld = json_ld
+        .and_then(|review| review.review_rating.as_ref())
+        .and_then(|rating| json_ld_rating(rating, SCORE_SCALE));
+    let score = from_json_ld.or_else(|| {
+        let text = element_text(html, SCORE_MARKER)?;
+        text.split('/').next()?.trim().parse().ok()
+    })?;
+    Some(score).filter(|score| (0.0..=SCORE_SCALE).contains(score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://beatsperminute.com";
+
+    #[test]
+    fn album_review_marker_read_from_either_end_of_the_slug() {
+        let slug = |path: &str| review_slug(&format!("{}{}", BASE, path));
+        let current = slug("/album-review-wet-leg-moisturizer/");
+        assert_eq!(current.as_deref(), Some("wet-leg-moisturizer"));
+        let suffixed = slug("/wet-leg-moisturizer-album-review/?utm_source=rss#comments");
+        assert_eq!(suffixed.as_deref(), Some("wet-leg-moisturizer"));
+        let older = slug("/wet-leg-wet-leg-review/");
+        assert_eq!(older.as_deref(), Some("wet-leg-wet-leg"));
+        assert_eq!(slug("/category/album-review/"), None);
+        assert_eq!(slug("/album-review-/"), None);
+        assert_eq!(slug("/track-premiere-wet-leg-catch-these-fists/"), None);
+    }
+
+    #[test]
+    fn search_page_keeps_root_level_album_reviews() {
+        let html = r#"<nav><a href="/">Home</a>
+            <a href="/category/album-review/">Album Reviews</a>
+            <a href="/category/features/">Features</a></nav>
+            <article><a href="/wet-leg-interview-moisturizer/">Interview</a></article>
+            <article><a href="/album-review-wet-leg-wet-leg/">Wet Leg</a></article>
+            <article><a href="https://beatsperminute.com/album-review-wet-leg-moisturizer/">
+            Moisturizer</a><a href="/album-review-wet-leg-moisturizer/">Read more</a></article>
+            <a href="https://twitter.com/album-review-wet-leg-moisturizer/">Share</a>"#;
+        let album = Query::new("wet-leg", "moisturizer");
+        let result = parse_search_page(html, BASE, &album);
+        let slugs: Vec<&str> = result.candidates.iter().map(|c| c.slug.as_str()).collect();
+        assert_eq!(slugs, ["wet-leg-wet-leg", "wet-leg-moisturizer"]);
+        let chosen = format!("{}/album-review-wet-leg-moisturizer/", BASE);
+        assert_eq!(result.chosen, Some(chosen));
+    }
+
+    #[test]
+    fn decimal_score_read_from_the_score_badge() {
+        let html = r#"<script type="application/ld+json">{"@type":"Review",
+            "author":{"@type":"Person","name":"Ray Finlayson"},
+            "datePublished":"2025-07-14T12:00:00+00:00",
+            "reviewBody":"<p>Wet Leg&#8217;s second album is bigger.</p>"}</script>
+            <div class="review-score score-high"><span>7.8</span>/10</div>"#;
+        let url = format!("{}/album-review-wet-leg-moisturizer/", BASE);
+        let review = parse_review_page(&url, html).unwrap();
+        assert_eq!(review.rating, Some(7.8));
+        assert_eq!(review.reviewer.as_deref(), Some("Ray Finlayson"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-14"));
+        let excerpt = review.excerpt.unwrap();
+        assert_eq!(excerpt, "Wet Leg\u{2019}s second album is bigger.");
+
+        let bare = r#"<div class="review-score">8.2</div>"#;
+        assert_eq!(parse_score(None, bare), Some(8.2));
+        let percent = r#"<div class="review-score">82</div>"#;
+        assert_eq!(parse_score(None, percent), None);
+    }
+}
@@ -0,0 +1,235 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, decode_html_entities,
+    fetch_page, find_json_ld_node, json_ld_name, json_ld_rating, match_confidence,
+    release_ids_conflict, search_terms, site_links, slugify, strip_html_tags, truncate_at_sentence,
+    url_encode, AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query, SearchCandidate,
+    SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `clashmusic_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://www.clashmusic.com";
+const BASE_URL_CONFIG: &str = "clashmusic_base_url";
+
+/// Clash scores albums out of ten.
+const SCORE_SCALE: f64 = 10.0;
+
+/// Class of the element showing the score, for pages whose JSON-LD carries
+/// no `reviewRating`.
+const SCORE_MARKER: &str = "class=\"review-rating";
+
+/// The site root, honoring the `clashmusic_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch a Clash review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its score, skipping the review text,
+/// author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known Clash URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let review = find_json_ld_node(&body, &["Review"]);
+    let score = parse_score(review.as_ref(), &body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((score, SCORE_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the review URLs Clash's search offers for an album and the one
+/// `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Clash for "artist title" and keep every review URL it lists along
+/// with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/?s={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+
+    let urls = site_links(&html, &base, "reviews");
+    let mut result = SearchResult {
+        chosen: pick_review_url(&urls, &album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_slug(&url).unwrap_or_default().to_string();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    Ok(result)
+}
+
+/// The best-scoring review for the album; see `best_candidate`.
+fn pick_review_url(urls: &[String], query: &Query) -> Option<String> {
+    best_candidate(query, urls, |url| review_slug(url).map(Candidate::new)).cloned()
+}
+
+/// The album slug of a review URL: its last path segment, without an
+/// optional numeric prefix ("/reviews/12345-wet-leg-moisturizer" ->
+/// "wet-leg-moisturizer"). None for section pages and bare numeric IDs.
+fn review_slug(url: &str) -> Option<&str> {
+    let path = url.split_once("/reviews/")?.1;
+    let path = path.split(['?', '#']).next()?.trim_end_matches('/');
+    let slug = path.rsplit('/').next()?;
+    let slug = match slug.split_once('-') {
+        Some((prefix, rest)) if prefix.chars().all(|c| c.is_ascii_digit()) => rest,
+        _ => slug,
+    };
+    if path.starts_with("page/") || slug.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(slug)
+}
+
+/// Parse a Clash review page for the score (out of 10) and the review's
+/// text, author and date from its Review JSON-LD.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let review = find_json_ld_node(html, &["Review"]);
+    let field = |key: &str| review.as_ref().and_then(|node| node.get(key));
+
+    let score = parse_score(review.as_ref(), html);
+    let excerpt = field("reviewBody")
+        .or_else(|| field("description"))
+        .and_then(|body| body.as_str())
+        .map(|body| decode_html_entities(&strip_html_tags(body)))
+        .map(|body| truncate_at_sentence(body.trim(), MAX_EXCERPT_CHARS))
+        .filter(|body| !body.is_empty());
+
+    if score.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    let review_date = field("datePublished")
+        .and_then(|date| date.as_str())
+        .map(str::to_string);
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(score.map(|s| (s, SCORE_SCALE)))
+            .reviewer(field("author").and_then(json_ld_name))
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The score, from the Review JSON-LD's `reviewRating` or else the score
+/// element's text ("8" or "8/10"). JSON-LD ratings on another scale are
+/// converted; anything outside 0-10 is rejected.
+fn parse_score(review: Option<&serde_json::Value>, html: &str) -> Option<f64> {
+    let from_json_ld = review
+        .and_then(|review| review.get("reviewRating"))
+        .and_then(|rating| json_ld_rating(rating, SCORE_SCALE));
+    let score = from_json_ld.or_else(|| {
+        let start = html.find(SCORE_MARKER)?;
+        let content = &html[start + html[start..].find('>')? + 1..];
+        let text = strip_html_tags(&content[..content.find("</")?]);
+        text.trim().split('/').next()?.trim().parse().ok()
+    })?;
+    Some(score).filter(|score| (0.0..=SCORE_SCALE).contains(score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://www.clashmusic.com";
+
+    #[test]
+    fn numeric_prefixes_dropped_from_review_slugs() {
+        let html = r#"<a href="/reviews/">Reviews</a>
+            <a href="/reviews/page/2/">Next</a>
+            <a href="/reviews/12345-wet-leg-moisturizer/">Moisturizer</a>
+            <a href="https://www.clashmusic.com/reviews/wet-leg-wet-leg/">Wet Leg</a>
+            <a href="/reviews/98765/">Bare ID</a>"#;
+        let urls = site_links(html, BASE, "reviews");
+        let slugs: Vec<Option<&str>> = urls.iter().map(|url| review_slug(url)).collect();
+        assert_eq!(slugs[..2], [None, None]);
+        assert_eq!(slugs[2], Some("wet-leg-moisturizer"));
+        assert_eq!(slugs[3], Some("wet-leg-wet-leg"));
+        assert_eq!(slugs[4], None);
+
+        let chosen = pick_review_url(&urls, &Query::new("wet-leg", "moisturizer"));
+        assert_eq!(chosen.as_ref(), Some(&urls[2]));
+        let self_titled = pick_review_url(&urls, &Query::new("wet-leg", "wet-leg"));
+        assert_eq!(self_titled.as_ref(), Some(&urls[3]));
+    }
+
+    #[test]
+    fn score_from_json_ld_or_markup() {
+        let html = r#"<script type="application/ld+json">{"@context":"https://schema.org",
+            "@graph":[{"@type":"WebPage","name":"Wet Leg"},{"@type":"Review",
+            "author":{"@type":"Person","name":"Robin Murray"},"datePublished":"2025-07-10",
+            "description":"Wet Leg return &#8211; louder.",
+            "reviewRating":{"@type":"Rating","ratingValue":"8","bestRating":"10"}}]}</script>"#;
+        let review = parse_review_page("https://www.clashmusic.com/reviews/x/", html).unwrap();
+        assert_eq!(review.rating, Some(8.0));
+        assert_eq!(review.rating_scale, Some(10.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Robin Murray"));
+        let excerpt = review.excerpt.unwrap();
+        assert_eq!(excerpt, "Wet Leg return \u{2013} louder.");
+
+        let markup = r#"<div class="review-rating"><span>7</span>/10</div>"#;
+        assert_eq!(parse_score(None, markup), Some(7.0));
+        let slashed = r#"<div class="review-rating large">9/10</div>"#;
+        assert_eq!(parse_score(None, slashed), Some(9.0));
+        let out_of_range = r#"<div class="review-rating">11</div>"#;
+        assert_eq!(parse_score(None, out_of_range), None);
+    }
+}
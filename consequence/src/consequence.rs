@@ -0,0 +1,279 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, decode_html_entities,
+    fetch_page, find_json_ld_node, json_ld_name, match_confidence, release_ids_conflict,
+    search_terms, site_links, slugify, strip_html_tags, truncate_at_sentence, url_encode,
+    AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query, SearchCandidate, SearchResult,
+    SiteReview, MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `consequence_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://consequence.net";
+const BASE_URL_CONFIG: &str = "consequence_base_url";
+
+/// Letter grades and their 0-10 values: half-point steps from A+ down to
+/// D-, with F well below the rest.
+const GRADES: &[(&str, f64)] = &[
+    ("A+", 10.0),
+    ("A", 9.5),
+    ("A-", 9.0),
+    ("B+", 8.5),
+    ("B", 8.0),
+    ("B-", 7.5),
+    ("C+", 7.0),
+    ("C", 6.5),
+    ("C-", 6.0),
+    ("D+", 5.5),
+    ("D", 5.0),
+    ("D-", 4.5),
+    ("F", 1.0),
+];
+
+/// Where a review page states its grade in the visible text.
+const GRADE_MARKER: &str = "Grade:";
+
+/// JSON-LD types a Consequence review page may describe itself as.
+const ARTICLE_TYPES: &[&str] = &["Review", "NewsArticle", "Article"];
+
+/// The site root, honoring the `consequence_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch a Consequence review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its letter grade, skipping the review
+/// text, author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known Consequence URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let article = find_json_ld_node(&body, ARTICLE_TYPES);
+    let grade = parse_grade(article.as_ref(), &body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(grade_to_rating(&grade).map(|r| (r, 10.0)))
+        .rating_grade(Some(grade))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the review URLs Consequence's search offers for an album and the
+/// one `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Consequence for "artist title review" and keep every review URL it
+/// lists along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = format!("{} review", search_terms(&input.artist, cleaned));
+    let search_url = format!("{}/?s={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+
+    // Articles live under date paths ("/2024/05/..."), so every on-site link
+    // is a candidate and the slug decides which are reviews
+    let urls: Vec<String> = site_links(&html, &base, "")
+        .into_iter()
+        .filter(|url| review_slug(url).is_some())
+        .collect();
+    let mut result = SearchResult {
+        chosen: pick_review_url(&urls, &album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    Ok(result)
+}
+
+/// The best-scoring review for the album; see `best_candidate`.
+fn pick_review_url(urls: &[String], query: &Query) -> Option<String> {
+    best_candidate(query, urls, |url| review_slug(url).map(Candidate::new)).cloned()
+}
+
+/// The album part of a review URL's slug, which Consequence ends with
+/// "-review" or "-album-review":
+/// "/2024/05/billie-eilish-hit-me-hard-and-soft-album-review/" ->
+/// "billie-eilish-hit-me-hard-and-soft". None for any other article.
+fn review_slug(url: &str) -> Option<String> {
+    let path = url.split_once("://")?.1;
+    let path = path.split(['?', '#']).next()?.trim_end_matches('/');
+    let slug = slugify(path.rsplit_once('/')?.1);
+    let album = slug
+        .strip_suffix("-album-review")
+        .or_else(|| slug.strip_suffix("-review"))?;
+    Some(album.to_string()).filter(|album| !album.is_empty())
+}
+
+/// The 0-10 value of a letter grade ("A+" through "F"), ignoring case and
+/// surrounding whitespace. Unicode minus signs and dashes count as "-".
+fn grade_to_rating(grade: &str) -> Option<f64> {
+    let grade = normalize_grade(grade);
+    GRADES
+        .iter()
+        .find(|(letter, _)| *letter == grade)
+        .map(|(_, rating)| *rating)
+}
+
+/// A grade as it appears in `GRADES`: trimmed, uppercase, ASCII minus.
+fn normalize_grade(grade: &str) -> String {
+    grade
+        .trim()
+        .replace(['\u{2212}', '\u{2013}', '\u{2014}'], "-")
+        .to_ascii_uppercase()
+}
+
+/// The letter grade, from the JSON-LD `reviewRating` or else the "Grade:"
+/// line in the page text. Only grades in `GRADES` are accepted.
+fn parse_grade(article: Option<&serde_json::Value>, html: &str) -> Option<String> {
+    let from_json_ld = article
+        .and_then(|node| node.get("reviewRating"))
+        .and_then(|rating| rating.get("ratingValue"))
+        .and_then(|value| value.as_str())
+        .map(str::to_string);
+    let grade = from_json_ld.or_else(|| {
+        let text = decode_html_entities(&strip_html_tags(html));
+        let rest = &text[text.find(GRADE_MARKER)? + GRADE_MARKER.len()..];
+        rest.split_whitespace().next().map(str::to_string)
+    })?;
+    let grade = normalize_grade(&grade);
+    grade_to_rating(&grade).map(|_| grade)
+}
+
+/// Parse a Consequence review page for the letter grade and the review's
+/// text, author and date from its article JSON-LD.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let article = find_json_ld_node(html, ARTICLE_TYPES);
+    let field = |key: &str| article.as_ref().and_then(|node| node.get(key));
+
+    let grade = parse_grade(article.as_ref(), html);
+    let excerpt = field("reviewBody")
+        .or_else(|| field("articleBody"))
+        .or_else(|| field("description"))
+        .and_then(|body| body.as_str())
+        .map(|body| decode_html_entities(&strip_html_tags(body)))
+        .map(|body| truncate_at_sentence(body.trim(), MAX_EXCERPT_CHARS))
+        .filter(|body| !body.is_empty());
+
+    if grade.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    let rating = grade.as_deref().and_then(grade_to_rating);
+    let review_date = field("datePublished")
+        .and_then(|date| date.as_str())
+        .map(str::to_string);
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(rating.map(|r| (r, 10.0)))
+            .rating_grade(grade)
+            .reviewer(field("author").and_then(json_ld_name))
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letter_grades_map_to_ten_point_scale() {
+        assert_eq!(grade_to_rating("A+"), Some(10.0));
+        assert_eq!(grade_to_rating("A"), Some(9.5));
+        assert_eq!(grade_to_rating(" b- "), Some(7.5));
+        assert_eq!(grade_to_rating("C\u{2212}"), Some(6.0));
+        assert_eq!(grade_to_rating("D-"), Some(4.5));
+        assert_eq!(grade_to_rating("F"), Some(1.0));
+        assert_eq!(grade_to_rating("F+"), None);
+        assert_eq!(grade_to_rating("E"), None);
+        assert_eq!(grade_to_rating(""), None);
+
+        let values: Vec<f64> = GRADES.iter().map(|(_, rating)| *rating).collect();
+        assert!(values.windows(2).all(|pair| pair[0] > pair[1]));
+    }
+
+    #[test]
+    fn review_slug_needs_review_suffix() {
+        let slug = |path: &str| review_slug(&format!("https://consequence.net{}", path));
+        let album = slug("/2024/05/billie-eilish-hit-me-hard-and-soft-album-review/");
+        assert_eq!(album.as_deref(), Some("billie-eilish-hit-me-hard-and-soft"));
+        let older = slug("/2019/03/billie-eilish-when-we-fall-asleep-review/");
+        assert_eq!(older.as_deref(), Some("billie-eilish-when-we-fall-asleep"));
+        assert_eq!(slug("/2024/05/billie-eilish-tour/"), None);
+        assert_eq!(slug("/category/review/"), None);
+    }
+
+    #[test]
+    fn grade_kept_alongside_its_rating() {
+        let html = r#"<script type="application/ld+json">{"@type":"NewsArticle",
+            "author":[{"@type":"Person","name":"Mary Siroky"}],"datePublished":"2024-05-17",
+            "description":"Billie Eilish&#8217;s third album is her most assured."}</script>
+            <p><strong>Grade:</strong> A-</p>"#;
+        let url = "https://consequence.net/2024/05/billie-eilish-album-review/";
+        let review = parse_review_page(url, html).unwrap();
+        assert_eq!(review.rating, Some(9.0));
+        assert_eq!(review.rating_grade.as_deref(), Some("A-"));
+        assert_eq!(review.reviewer.as_deref(), Some("Mary Siroky"));
+        let excerpt = review.excerpt.unwrap();
+        assert!(excerpt.starts_with("Billie Eilish\u{2019}s third album"));
+
+        assert_eq!(parse_grade(None, "<p>Grade: Q</p>"), None);
+        let rated = serde_json::json!({"reviewRating": {"ratingValue": "b+"}});
+        assert_eq!(parse_grade(Some(&rated), "").as_deref(), Some("B+"));
+    }
+}
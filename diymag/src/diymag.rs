@@ -0,0 +1,236 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, decode_html_entities,
+    fetch_page, find_json_ld_node, json_ld_name, json_ld_rating, match_confidence,
+    parse_star_rating, release_ids_conflict, search_terms, site_links, slugify, strip_html_tags,
+    truncate_at_sentence, url_encode, AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query,
+    SearchCandidate, SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `diymag_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://diymag.com";
+const BASE_URL_CONFIG: &str = "diymag_base_url";
+
+/// DIY scores albums out of five stars.
+const STAR_SCALE: f64 = 5.0;
+
+/// Attribute on the star widget holding the score, for pages whose JSON-LD
+/// carries no `reviewRating`.
+const STAR_RATING_MARKER: &str = "data-rating=\"";
+
+/// The site root, honoring the `diymag_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch a DIY review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its star rating, skipping the review
+/// text, author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known DIY URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let review = find_json_ld_node(&body, &["Review"]);
+    let rating = parse_stars(review.as_ref(), &body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((rating, STAR_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the review URLs DIY's search offers for an album and the one
+/// `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search DIY for "artist title" and keep every review URL it lists along
+/// with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/search?q={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+
+    let urls = site_links(&html, &base, "reviews");
+    let mut result = SearchResult {
+        chosen: pick_review_url(&urls, &album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    Ok(result)
+}
+
+/// The best-scoring review for the album; see `best_candidate`.
+fn pick_review_url(urls: &[String], query: &Query) -> Option<String> {
+    best_candidate(query, urls, |url| review_slug(url).map(Candidate::new)).cloned()
+}
+
+/// The slug of a review URL: its last path segment, e.g.
+/// "/reviews/album/wet-leg-moisturizer" -> "wet-leg-moisturizer". None for
+/// section pages like "/reviews/album".
+fn review_slug(url: &str) -> Option<String> {
+    let path = url.split_once("/reviews/")?.1;
+    let path = path.split(['?', '#']).next()?.trim_end_matches('/');
+    let (section, slug) = path.rsplit_once('/')?;
+    if section.is_empty() {
+        return None;
+    }
+    Some(slugify(slug)).filter(|slug| !slug.is_empty())
+}
+
+/// Parse a DIY review page for the star rating (out of 5) and the review's
+/// text, author and date, preferring the page's Review JSON-LD.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let review = find_json_ld_node(html, &["Review"]);
+    let field = |key: &str| review.as_ref().and_then(|node| node.get(key));
+
+    let rating = parse_stars(review.as_ref(), html);
+    let excerpt = field("reviewBody")
+        .or_else(|| field("description"))
+        .and_then(|body| body.as_str())
+        .map(|body| decode_html_entities(&strip_html_tags(body)))
+        .map(|body| truncate_at_sentence(body.trim(), MAX_EXCERPT_CHARS))
+        .filter(|body| !body.is_empty());
+
+    if rating.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    let reviewer = field("author").and_then(json_ld_name);
+    let review_date = field("datePublished")
+        .and_then(|date| date.as_str())
+        .map(str::to_string);
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(rating.map(|r| (r, STAR_SCALE)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The star rating, from the Review JSON-LD's `reviewRating` or else the
+/// star widget's `data-rating` attribute. JSON-LD ratings on another scale
+/// are converted to stars; anything outside 0-5 is rejected.
+fn parse_stars(review: Option<&serde_json::Value>, html: &str) -> Option<f64> {
+    let from_json_ld = review
+        .and_then(|review| review.get("reviewRating"))
+        .and_then(|rating| json_ld_rating(rating, STAR_SCALE));
+    from_json_ld.or_else(|| {
+        let start = html.find(STAR_RATING_MARKER)?;
+        let end = html[start..]
+            .find('>')
+            .map_or(html.len(), |end| start + end);
+        parse_star_rating(&html[start..end], STAR_SCALE)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://diymag.com";
+
+    #[test]
+    fn review_picked_by_artist_and_title() {
+        let html = r#"<a href="/reviews/album">Albums</a>
+            <a href="/reviews/album/wet-leg-moisturizer-deluxe">Deluxe</a>
+            <a href="https://diymag.com/reviews/album/wet-leg-moisturizer">Moisturizer</a>
+            <a href="/reviews/live/wet-leg-brixton">Live</a>"#;
+        let urls = site_links(html, BASE, "reviews");
+        assert_eq!(review_slug(&urls[0]), None);
+
+        let chosen = pick_review_url(&urls, &Query::new("wet-leg", "moisturizer"));
+        // The standard edition's review outscores the deluxe one listed first
+        let standard = format!("{}/reviews/album/wet-leg-moisturizer", BASE);
+        assert_eq!(chosen, Some(standard));
+        let brixton = pick_review_url(&urls, &Query::new("the-wet-leg", "brixton"));
+        let live = format!("{}/reviews/live/wet-leg-brixton", BASE);
+        assert_eq!(brixton, Some(live));
+        let unreviewed = Query::new("wet-leg", "ok-computer");
+        assert_eq!(pick_review_url(&urls, &unreviewed), None);
+    }
+
+    #[test]
+    fn star_rating_scaled_and_range_checked() {
+        let html = r#"<script type="application/ld+json">{"@type":"Review",
+            "author":{"@type":"Person","name":"Emma Swann"},"datePublished":"2025-07-11",
+            "reviewBody":"A sharper, stranger second album. It lands.",
+            "reviewRating":{"@type":"Rating","ratingValue":"4","bestRating":"5"}}</script>"#;
+        let review = parse_review_page("https://diymag.com/reviews/album/x", html).unwrap();
+        assert_eq!(review.rating, Some(8.0));
+        assert_eq!(review.rating_raw, Some(4.0));
+        assert_eq!(review.rating_scale, Some(5.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Emma Swann"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-11"));
+        assert!(review.excerpt.unwrap().starts_with("A sharper"));
+
+        let stars = |html: &str| {
+            let review = find_json_ld_node(html, &["Review"]);
+            parse_stars(review.as_ref(), html)
+        };
+        assert_eq!(stars(r#"<div data-rating="3.5">"#), Some(3.5));
+        assert_eq!(stars(r#"<div data-rating="8">"#), None);
+        let out_of_ten = html.replace("\"bestRating\":\"5\"", "\"bestRating\":\"10\"");
+        assert_eq!(stars(&out_of_ten), Some(2.0));
+        let overrated = html.replace("\"ratingValue\":\"4\"", "\"ratingValue\":\"7\"");
+        assert_eq!(stars(&overrated), None);
+    }
+}
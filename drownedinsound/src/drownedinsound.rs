@@ -0,0 +1,292 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, decode_html_entities,
+    element_text, fetch_archived_page, meta_content, normalize_date, paragraph_text,
+    release_ids_conflict, same_site, search_terms, slugify, strip_html_tags, truncate_at_sentence,
+    url_encode, AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query, SearchCandidate,
+    SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `drownedinsound_base_url` config
+/// value (e.g. a Wayback Machine snapshot prefix or a local mirror).
+const DEFAULT_BASE_URL: &str = "https://drownedinsound.com";
+const BASE_URL_CONFIG: &str = "drownedinsound_base_url";
+
+/// Drowned in Sound scored albums out of ten.
+const SCORE_SCALE: f64 = 10.0;
+
+/// Class of the element showing the score.
+const SCORE_MARKER: &str = "class=\"rating";
+
+/// Class of the "by <reviewer>" line.
+const BYLINE_MARKER: &str = "class=\"byline";
+
+/// Class of the element holding the publish date ("June 3rd, 2008").
+const DATE_MARKER: &str = "class=\"date";
+
+/// Class of the element holding the review text.
+const BODY_MARKER: &str = "class=\"editorial";
+
+/// Path prefix the Wayback Machine puts in front of archived URLs
+/// ("/web/20200101000000/https://drownedinsound.com/...").
+const ARCHIVE_PREFIX: &str = "/web/";
+
+/// The site root, honoring the `drownedinsound_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch a Drowned in Sound review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its score, skipping the review text,
+/// author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known Drowned in Sound URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_archived_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (chosen, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&chosen.url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = Some(chosen.score);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (chosen, body) = fetch_review_page(input)?;
+    let score = parse_score(&body).ok_or(FetchError::NotFound)?;
+    Ok(SiteReview::builder(chosen.url)
+        .native_rating(Some((score, SCORE_SCALE)))
+        .match_confidence(Some(chosen.score))
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input. Review URLs carry only numeric
+/// IDs, so the chosen candidate (scored on its link text) comes back too.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(SearchCandidate, String), FetchError> {
+    let result = search_candidates(input)?;
+    let chosen = result
+        .candidates
+        .into_iter()
+        .find(|candidate| result.chosen.as_ref() == Some(&candidate.url))
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_archived_page(&build_request(&chosen.url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((chosen, body))
+}
+
+/// List the review links Drowned in Sound's search offers for an album and
+/// the one `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Drowned in Sound for "artist title" and keep every review link it
+/// lists along with the pick. The site is dormant, so the search page may
+/// be served from the Wayback Machine after a redirect; archived links are
+/// mapped back to their original URLs.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/search?q={}", base, url_encode(&query));
+    let html = fetch_archived_page(&build_request(&search_url, "text/html"))?;
+
+    let mut result = SearchResult::default();
+    for (url, slug) in review_links(&html, &base) {
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    result.chosen = pick_review(&result.candidates, &album);
+    Ok(result)
+}
+
+/// The review whose link text best fits the album; see `best_candidate`.
+fn pick_review(candidates: &[SearchCandidate], album: &Query) -> Option<String> {
+    let best = best_candidate(album, candidates, |c| Some(Candidate::new(c.slug.as_str())));
+    best.map(|candidate| candidate.url.clone())
+}
+
+/// Review links in a page as `(url, slug of the link text)`, in page order
+/// and without duplicates. Review URLs ("/releases/14203/reviews/4142843")
+/// are resolved against `base`, after unwrapping Wayback Machine links.
+fn review_links(html: &str, base: &str) -> Vec<(String, String)> {
+    let mut links: Vec<(String, String)> = Vec::new();
+    for chunk in html.split("<a ").skip(1) {
+        let Some(tag_end) = chunk.find('>') else {
+            continue;
+        };
+        let Some(path) = review_path(&chunk[..tag_end], base) else {
+            continue;
+        };
+        let text_end = chunk.find("</a>").unwrap_or(chunk.len()).max(tag_end);
+        let text = decode_html_entities(&strip_html_tags(&chunk[tag_end + 1..text_end]));
+        let url = format!("{}{}", base, path);
+        let slug = slugify(&text);
+        if !slug.is_empty() && !links.iter().any(|(seen, _)| *seen == url) {
+            links.push((url, slug));
+        }
+    }
+    links
+}
+
+/// The "/releases/{id}/reviews/{id}" path an `<a>` tag's `href` points to,
+/// either on the site itself or through a Wayback Machine snapshot of it.
+fn review_path<'a>(tag: &'a str, base: &str) -> Option<&'a str> {
+    let marker = "href=\"";
+    let start = tag.find(marker)? + marker.len();
+    let href = &tag[start..start + tag[start..].find('"')?];
+    let href = original_url(href);
+
+    let path = if href.starts_with('/') {
+        href
+    } else if same_site(href, base) || same_site(href, DEFAULT_BASE_URL) {
+        let rest = href.split_once("://")?.1;
+        &rest[rest.find('/')?..]
+    } else {
+        return None;
+    };
+    let path = path.split(['?', '#']).next()?.trim_end_matches('/');
+
+    let mut segments = path.split('/').skip(1);
+    let is_id = |segment: Option<&str>| {
+        segment.is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+    };
+    let shape = segments.next() == Some("releases")
+        && is_id(segments.next())
+        && segments.next() == Some("reviews")
+        && is_id(segments.next());
+    shape.then_some(path)
+}
+
+/// The original URL inside a Wayback Machine link
+/// ("/web/20200101000000/https://drownedinsound.com/x" or the same on
+/// "https://web.archive.org"); any other link is returned unchanged.
+fn original_url(href: &str) -> &str {
+    let Some(start) = href.find(ARCHIVE_PREFIX) else {
+        return href;
+    };
+    let rest = &href[start + ARCHIVE_PREFIX.len()..];
+    match rest.split_once('/') {
+        Some((timestamp, original)) if timestamp.starts_with(|c: char| c.is_ascii_digit()) => {
+            original
+        }
+        _ => href,
+    }
+}
+
+/// Parse a Drowned in Sound review page for the score (out of 10) and the
+/// review's text, author and date.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let score = parse_score(html);
+    let excerpt = parse_review_body(html);
+    if score.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    let reviewer = element_text(html, BYLINE_MARKER)
+        .map(|byline| {
+            let name = byline.trim_start_matches("by ").trim_start_matches("By ");
+            name.trim().to_string()
+        })
+        .filter(|name| !name.is_empty())
+        .or_else(|| meta_content(html, "author"));
+    let review_date = element_text(html, DATE_MARKER).and_then(|text| normalize_date(&text));
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(score.map(|s| (s, SCORE_SCALE)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The score in the rating element ("8" or "8/10"), accepted only within
+/// 0-10.
+fn parse_score(html: &str) -> Option<f64> {
+    let text = element_text(html, SCORE_MARKER)?;
+    let score: f64 = text.split('/').next()?.trim().parse().ok()?;
+    Some(score).filter(|score| (0.0..=SCORE_SCALE).contains(score))
+}
+
+/// The review's paragraphs, cut to ~2000 chars at a sentence boundary.
+fn parse_review_body(html: &str) -> Option<String> {
+    let start = html.find(BODY_MARKER)?;
+    let end = html[start..]
+        .find("class=\"comments")
+        .map_or(html.len(), |end| start + end);
+    let text = paragraph_text(&html[start..end]);
+    Some(truncate_at_sentence(&text, MAX_EXCERPT_CHARS)).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://drownedinsound.com";
+
+    #[test]
+    fn archived_links_mapped_to_original_reviews() {
+        let html = r#"<a href="/web/20190301000000/http://drownedinsound.com/releases/14203/reviews/4142843">
+            Los Campesinos! - <em>Hold On Now, Youngster&hellip;</em></a>
+            <a href="https://web.archive.org/web/20190301000000/http://drownedinsound.com/releases/14203">Release</a>
+            <a href="/releases/9999/reviews/4100000" class="more">Los Campesinos! - Sticking Fingers</a>
+            <a href="/news/4142000">Los Campesinos! announce tour</a>"#;
+        let links = review_links(html, BASE);
+        assert_eq!(links.len(), 2);
+        let (url, slug) = &links[0];
+        assert_eq!(*url, format!("{}/releases/14203/reviews/4142843", BASE));
+        assert_eq!(slug, "los-campesinos-hold-on-now-youngster");
+
+        let (artist, title) = ("los-campesinos", "hold-on-now-youngster");
+        let mut result = SearchResult::default();
+        for (url, slug) in links {
+            result.add(SearchCandidate::new(url, slug, artist, title));
+        }
+        let chosen = pick_review(&result.candidates, &Query::new(artist, title));
+        assert_eq!(chosen.as_ref(), Some(&result.candidates[0].url));
+        assert_eq!(original_url("/web/about"), "/web/about");
+    }
+
+    #[test]
+    fn score_byline_and_ordinal_date() {
+        let html = r#"<div class="rating">8</div>
+            <p class="byline">by <a href="/users/mikediver">Mike Diver</a></p>
+            <span class="date">February 25th, 2008</span>
+            <div class="editorial"><p>Fizzing, funny &amp; far too clever.</p>
+            <p>It is brilliant.</p></div><div class="comments"><p>First!</p></div>"#;
+        let url = format!("{}/releases/14203/reviews/4142843", BASE);
+        let review = parse_review_page(&url, html).unwrap();
+        assert_eq!(review.rating, Some(8.0));
+        assert_eq!(review.rating_scale, Some(10.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Mike Diver"));
+        assert_eq!(review.review_date.as_deref(), Some("2008-02-25"));
+        let excerpt = review.excerpt.unwrap();
+        assert!(excerpt.starts_with("Fizzing, funny & far too clever."));
+        assert!(!excerpt.contains("First!"));
+
+        assert_eq!(parse_score(r#"<div class="rating">11/10</div>"#), None);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::sources::SOURCES;
+use editorial_common::plugin::lookup_albums;
+use editorial_common::{
+    unix_now, wrap_aggregate, AlbumReviewInput, EditorialReview, FetchOutcome, ReviewOptions,
+    SiteReview,
+};
+
+/// Seconds a request may spend asking sources before the rest are skipped.
+/// A source already being asked finishes; each fetch has its own timeout.
+const TIME_BUDGET_SECS: u64 = 20;
+
+/// What one source found for an album: its outcome and any other reviews of
+/// the album, or None if the time budget ran out before it was asked.
+type Answer = Option<(FetchOutcome, Vec<SiteReview>)>;
+
+/// Ask every source for each album, through the same miss cache and hooks
+/// as its own plugin, and combine what they return per album. Each source
+/// gets every album at once, so batch lookups warm up once.
+pub fn album_reviews(albums: &[AlbumReviewInput]) -> Vec<String> {
+    let started = unix_now();
+    let mut answers: Vec<Vec<(&str, Answer)>> = albums.iter().map(|_| Vec::new()).collect();
+    for scraper in SOURCES {
+        let in_time = unix_now().saturating_sub(started) < TIME_BUDGET_SECS;
+        let found: Vec<Answer> = if in_time {
+            lookup_albums(scraper, albums)
+                .into_iter()
+                .map(Some)
+                .collect()
+        } else {
+            albums.iter().map(|_| None).collect()
+        };
+        for (album, answer) in answers.iter_mut().zip(found) {
+            album.push((scraper.source, answer));
+        }
+    }
+    albums
+        .iter()
+        .zip(answers)
+        .map(|(params, answers)| combine(answers, &params.options))
+        .collect()
+}
+
+/// Every review found, with the caller's options applied, one
+/// "source: reason" error per source that failed or was skipped
+/// ("timeout"), and the aggregate of the reviews' ratings. Sources that
+/// found nothing are left out entirely.
+fn combine(answers: Vec<(&str, Answer)>, options: &ReviewOptions) -> String {
+    let mut reviews = Vec::new();
+    let mut errors = Vec::new();
+    for (source, answer) in answers {
+        let Some((outcome, others)) = answer else {
+            errors.push(format!("{}: timeout", source));
+            continue;
+        };
+        if let Some(reason) = outcome.error_reason() {
+            errors.push(format!("{}: {}", source, reason));
+        }
+        for review in outcome.into_review().into_iter().chain(others) {
+            reviews.push(EditorialReview::from_site(source, options.apply(review)));
+        }
+    }
+    wrap_aggregate(reviews, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rated(url: &str, rating: f64, scale: f64) -> SiteReview {
+        SiteReview::builder(url)
+            .native_rating(Some((rating, scale)))
+            .build()
+    }
+
+    fn found(url: &str, rating: f64, scale: f64) -> Answer {
+        let review = rated(url, rating, scale);
+        Some((FetchOutcome::Found(Box::new(review)), Vec::new()))
+    }
+
+    fn missing(outcome: FetchOutcome) -> Answer {
+        Some((outcome, Vec::new()))
+    }
+
+    #[test]
+    fn reviews_combined_with_aggregate() {
+        let outcomes = vec![
+            ("pitchfork", found("https://pitchfork.com/a", 8.0, 10.0)),
+            ("nme", found("https://www.nme.com/b", 3.0, 5.0)),
+            ("stereogum", missing(FetchOutcome::NotFound)),
+            ("metacritic", missing(FetchOutcome::Blocked)),
+        ];
+        let json = combine(outcomes, &ReviewOptions::default());
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let reviews = value["reviews"].as_array().unwrap();
+        assert_eq!(reviews.len(), 2);
+        assert_eq!(reviews[1]["source"], "nme");
+        assert_eq!(reviews[1]["rating"], 6.0);
+        assert_eq!(value["aggregate"]["rating"], 7.0);
+        assert_eq!(value["aggregate"]["source_count"], 2);
+        assert_eq!(value["errors"], json!(["metacritic: blocked"]));
+
+        let unrated = ReviewOptions {
+            include_rating: false,
+            ..ReviewOptions::default()
+        };
+        let outcomes = vec![("pitchfork", found("https://pitchfork.com/a", 8.0, 10.0))];
+        let value: serde_json::Value = serde_json::from_str(&combine(outcomes, &unrated)).unwrap();
+        assert!(value.get("aggregate").is_none());
+    }
+
+    #[test]
+    fn no_reviews_leaves_aggregate_out() {
+        let outcomes = vec![
+            ("pitchfork", missing(FetchOutcome::NotFound)),
+            ("guardian", missing(FetchOutcome::Error)),
+            ("loudandquiet", None),
+        ];
+        let json = combine(outcomes, &ReviewOptions::default());
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["reviews"], json!([]));
+        assert!(value.get("aggregate").is_none());
+        let errors = json!(["guardian: fetch_failed", "loudandquiet: timeout"]);
+        assert_eq!(value["errors"], errors);
+    }
+
+    #[test]
+    fn other_editions_follow_without_recounting_the_source() {
+        let sunday = rated("https://pitchfork.com/sunday", 10.0, 10.0);
+        let original = rated("https://pitchfork.com/original", 6.0, 10.0);
+        let outcomes = vec![
+            (
+                "pitchfork",
+                Some((FetchOutcome::Found(Box::new(original)), vec![sunday])),
+            ),
+            ("nme", found("https://www.nme.com/b", 4.0, 5.0)),
+        ];
+        let json = combine(outcomes, &ReviewOptions::default());
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let urls: Vec<&str> = value["reviews"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|review| review["source_url"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            urls[..2],
+            [
+                "https://pitchfork.com/original",
+                "https://pitchfork.com/sunday"
+            ]
+        );
+        assert_eq!(value["aggregate"]["rating"], 7.0);
+        assert_eq!(value["aggregate"]["source_count"], 2);
+    }
+}
@@ -0,0 +1,46 @@
+mod aggregator;
+mod sources;
+
+use editorial_common::{wrap_batch, AlbumBatchInput, AlbumReviewInput, PluginInfo};
+use extism_pdk::*;
+
+/// Review fields the aggregated sources can populate between them.
+const CAPABILITIES: &[&str] = &[
+    "rating",
+    "rating_count",
+    "excerpt",
+    "reviewer",
+    "date",
+    "accolade",
+    "image",
+    "aggregate",
+];
+
+#[plugin_fn]
+pub fn riff_plugin_info() -> FnResult<String> {
+    let info = PluginInfo {
+        source: "aggregator",
+        version: env!("CARGO_PKG_VERSION"),
+        capabilities: CAPABILITIES,
+    };
+    Ok(info.to_json())
+}
+
+#[plugin_fn]
+pub fn riff_health_check(_input: String) -> FnResult<String> {
+    // Each source's own plugin probes its site
+    Ok("ok".to_string())
+}
+
+#[plugin_fn]
+pub fn riff_get_album_reviews(input: String) -> FnResult<String> {
+    let params: AlbumReviewInput = serde_json::from_str(&input)?;
+    let mut results = aggregator::album_reviews(std::slice::from_ref(&params));
+    Ok(results.remove(0))
+}
+
+#[plugin_fn]
+pub fn riff_get_album_reviews_batch(input: String) -> FnResult<String> {
+    let batch: AlbumBatchInput = serde_json::from_str(&input)?;
+    Ok(wrap_batch(&aggregator::album_reviews(&batch.albums)))
+}
@@ -0,0 +1,34 @@
+//! The site scrapers, linked from their plugin crates. editorial-common's
+//! `embedded` feature keeps their Extism exports out of the aggregator.
+
+use editorial_common::Scraper;
+
+/// Every site scraper in the workspace, asked in this order.
+pub static SOURCES: &[&Scraper] = &[
+    &riff_plugin_pitchfork::SCRAPER,
+    &riff_plugin_allmusic::SCRAPER,
+    &riff_plugin_northern_transmissions::SCRAPER,
+    &riff_plugin_thelineofbestfit::SCRAPER,
+    &riff_plugin_sputnikmusic::SCRAPER,
+    &riff_plugin_metacritic::SCRAPER,
+    &riff_plugin_diymag::SCRAPER,
+    &riff_plugin_nme::SCRAPER,
+    &riff_plugin_consequence::SCRAPER,
+    &riff_plugin_stereogum::SCRAPER,
+    &riff_plugin_clashmusic::SCRAPER,
+    &riff_plugin_pastemagazine::SCRAPER,
+    &riff_plugin_exclaim::SCRAPER,
+    &riff_plugin_undertheradar::SCRAPER,
+    &riff_plugin_musicomh::SCRAPER,
+    &riff_plugin_drownedinsound::SCRAPER,
+    &riff_plugin_beatsperminute::SCRAPER,
+    &riff_plugin_residentadvisor::SCRAPER,
+    &riff_plugin_bandcampdaily::SCRAPER,
+    &riff_plugin_guardian::SCRAPER,
+    &riff_plugin_slantmagazine::SCRAPER,
+    &riff_plugin_noripcord::SCRAPER,
+    &riff_plugin_gigwise::SCRAPER,
+    &riff_plugin_spectrumculture::SCRAPER,
+    &riff_plugin_tinymixtapes::SCRAPER,
+    &riff_plugin_loudandquiet::SCRAPER,
+];
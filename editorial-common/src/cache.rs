@@ -0,0 +1,394 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use extism_pdk::{config, var};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ClearCacheInput, ClearCacheReport};
+use crate::util::{clean_title, slugify, unix_now};
+
+/// How long a "not found" result is trusted before the site is searched again.
+/// Short enough that newly published reviews are picked up within a day.
+const MISS_TTL_SECS: u64 = 24 * 60 * 60;
+/// Upper bound on remembered misses per source; the oldest are evicted first.
+const MAX_MISSES: usize = 500;
+/// How long a resolved review URL is reused before the site is searched
+/// again, so a review that moves or is renamed is eventually found anew.
+const RESOLVED_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+/// Upper bound on remembered review URLs per source; the oldest are evicted
+/// first.
+const MAX_RESOLVED: usize = 500;
+/// Extism config key enabling the response cache (TTL in seconds, 0 = off).
+const RESPONSE_TTL_CONFIG: &str = "http_cache_ttl_secs";
+/// Extism config key bounding the total cached body size in bytes.
+const RESPONSE_MAX_BYTES_CONFIG: &str = "http_cache_max_bytes";
+/// Longest configurable response TTL (one week).
+pub(crate) const MAX_RESPONSE_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+/// Default total size of cached bodies. Extism vars share a host-side size
+/// limit (1 MiB by default), so this stays well under it.
+const DEFAULT_RESPONSE_MAX_BYTES: usize = 256 * 1024;
+const RESPONSE_INDEX_VAR: &str = "http_cache_index";
+
+/// Negative-result cache stored in an Extism var per source.
+/// Maps a normalized `artist|title` key to the Unix time of the miss.
+#[derive(Serialize, Deserialize, Default)]
+struct MissCache {
+    entries: HashMap<String, u64>,
+}
+
+impl MissCache {
+    /// Whether `key` missed less than `MISS_TTL_SECS` before `now`.
+    fn contains(&self, key: &str, now: u64) -> bool {
+        self.entries
+            .get(key)
+            .is_some_and(|&at| now.saturating_sub(at) < MISS_TTL_SECS)
+    }
+
+    /// Record a miss for `key` at `now`, dropping expired misses and then
+    /// the oldest beyond `MAX_MISSES`.
+    fn record(&mut self, key: String, now: u64) {
+        self.entries
+            .retain(|_, at| now.saturating_sub(*at) < MISS_TTL_SECS);
+        self.entries.insert(key, now);
+        evict_oldest(&mut self.entries, MAX_MISSES, |at| *at);
+    }
+}
+
+/// Review URLs resolved by earlier searches, stored in an Extism var per
+/// source. Maps a normalized `artist|title` key to the URL and the Unix time
+/// it was found.
+#[derive(Serialize, Deserialize, Default)]
+struct ResolvedCache {
+    entries: HashMap<String, (String, u64)>,
+}
+
+/// Check whether this album was recently looked up on `source` and not found.
+pub fn is_cached_miss(source: &str, artist: &str, title: &str) -> bool {
+    load_misses(source).contains(&miss_key(artist, title), unix_now())
+}
+
+/// Remember that this album was not found on `source`.
+pub fn record_miss(source: &str, artist: &str, title: &str) {
+    let mut cache = load_misses(source);
+    cache.record(miss_key(artist, title), unix_now());
+
+    if let Ok(bytes) = serde_json::to_vec(&cache) {
+        let _ = var::set(miss_var(source), &bytes);
+    }
+}
+
+/// The review URL an earlier search on `source` resolved this album to, if
+/// it was found within the last week.
+pub fn cached_review_url(source: &str, artist: &str, title: &str) -> Option<String> {
+    let mut cache = load_resolved(source);
+    let (url, at) = cache.entries.remove(&miss_key(artist, title))?;
+    Some(url).filter(|_| unix_now().saturating_sub(at) < RESOLVED_TTL_SECS)
+}
+
+/// Remember the review URL a search on `source` resolved this album to.
+pub fn record_review_url(source: &str, artist: &str, title: &str, url: &str) {
+    let mut cache = load_resolved(source);
+    let now = unix_now();
+
+    cache
+        .entries
+        .retain(|_, (_, at)| now.saturating_sub(*at) < RESOLVED_TTL_SECS);
+    let entry = (url.to_string(), now);
+    cache.entries.insert(miss_key(artist, title), entry);
+    evict_oldest(&mut cache.entries, MAX_RESOLVED, |(_, at)| *at);
+
+    if let Ok(bytes) = serde_json::to_vec(&cache) {
+        let _ = var::set(resolved_var(source), &bytes);
+    }
+}
+
+/// Drop a review URL that no longer leads to the album's review, so the
+/// next lookup searches again.
+pub fn forget_review_url(source: &str, artist: &str, title: &str) {
+    let mut cache = load_resolved(source);
+    if cache.entries.remove(&miss_key(artist, title)).is_some() {
+        if let Ok(bytes) = serde_json::to_vec(&cache) {
+            let _ = var::set(resolved_var(source), &bytes);
+        }
+    }
+}
+
+/// Remove the oldest entries, by the time `at` reads from each, until at
+/// most `max` remain.
+fn evict_oldest<V>(entries: &mut HashMap<String, V>, max: usize, at: impl Fn(&V) -> u64) {
+    while entries.len() > max {
+        let Some(oldest) = entries
+            .iter()
+            .min_by_key(|(_, value)| at(value))
+            .map(|(k, _)| k.clone())
+        else {
+            break;
+        };
+        entries.remove(&oldest);
+    }
+}
+
+/// Clear the shared caches selected by `input` (`misses` and `resolved` for
+/// `source`, and `responses`). Clearing an empty cache is a no-op, so this always succeeds.
+pub fn clear_caches(source: &str, input: &ClearCacheInput) -> ClearCacheReport {
+    let mut cleared = Vec::new();
+    if input.includes("misses") {
+        let _ = var::remove(miss_var(source));
+        cleared.push("misses");
+    }
+    if input.includes("resolved") {
+        let _ = var::remove(resolved_var(source));
+        cleared.push("resolved");
+    }
+    if input.includes("responses") {
+        clear_responses();
+        cleared.push("responses");
+    }
+    ClearCacheReport { cleared }
+}
+
+/// Normalized cache key so trivially different inputs share an entry.
+fn miss_key(artist: &str, title: &str) -> String {
+    format!("{}|{}", slugify(artist), slugify(clean_title(title)))
+}
+
+fn miss_var(source: &str) -> String {
+    format!("{}_miss_cache", source)
+}
+
+fn resolved_var(source: &str) -> String {
+    format!("{}_resolved_cache", source)
+}
+
+fn load_resolved(source: &str) -> ResolvedCache {
+    let bytes: Option<Vec<u8>> = var::get(resolved_var(source)).ok().flatten();
+    bytes
+        .and_then(|b| serde_json::from_slice(&b).ok())
+        .unwrap_or_default()
+}
+
+fn load_misses(source: &str) -> MissCache {
+    let bytes: Option<Vec<u8>> = var::get(miss_var(source)).ok().flatten();
+    bytes
+        .and_then(|b| serde_json::from_slice(&b).ok())
+        .unwrap_or_default()
+}
+
+/// Index of cached response bodies, oldest first. Bodies live in their own
+/// vars so a lookup only deserializes the one it needs.
+#[derive(Serialize, Deserialize, Default)]
+struct ResponseIndex {
+    entries: Vec<ResponseEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ResponseEntry {
+    url: String,
+    stored_at: u64,
+    bytes: usize,
+    /// The TTL the body was stored with, so a week-long archive entry isn't
+    /// expired by a store under the shorter `http_cache_ttl_secs`. Entries
+    /// written before it was recorded read as already expired.
+    #[serde(default)]
+    ttl: u64,
+}
+
+impl ResponseEntry {
+    /// Whether the entry is younger than both its own TTL and `ttl`.
+    fn fresh(&self, ttl: u64, now: u64) -> bool {
+        now.saturating_sub(self.stored_at) < self.ttl.min(ttl)
+    }
+}
+
+impl ResponseIndex {
+    /// The entry for `url`, if it is fresh at `now` for a caller accepting
+    /// bodies up to `ttl` seconds old.
+    fn fresh(&self, url: &str, ttl: u64, now: u64) -> Option<&ResponseEntry> {
+        let entry = self.entries.iter().find(|e| e.url == url)?;
+        Some(entry).filter(|entry| entry.fresh(ttl, now))
+    }
+
+    /// Make room for a `bytes`-long body for `url`: drop its earlier entry
+    /// and every entry past its own TTL, then the oldest until the total
+    /// fits in `max_bytes`. Returns the URLs whose bodies are no longer
+    /// indexed.
+    fn evict(&mut self, url: &str, bytes: usize, now: u64, max_bytes: usize) -> Vec<String> {
+        let mut evicted = Vec::new();
+        self.entries.retain(|e| {
+            let keep = e.url != url && e.fresh(e.ttl, now);
+            if !keep {
+                evicted.push(e.url.clone());
+            }
+            keep
+        });
+
+        let mut total: usize = self.entries.iter().map(|e| e.bytes).sum::<usize>() + bytes;
+        while total > max_bytes && !self.entries.is_empty() {
+            let oldest = self.entries.remove(0);
+            total -= oldest.bytes;
+            evicted.push(oldest.url);
+        }
+        evicted
+    }
+}
+
+/// Return the cached body for `url` if `ttl` is non-zero and the entry is
+/// younger than it (and than the TTL it was stored with).
+pub(crate) fn cached_response(url: &str, ttl: u64) -> Option<String> {
+    if ttl == 0 {
+        return None;
+    }
+    load_response_index().fresh(url, ttl, unix_now())?;
+    var::get::<String>(response_var(url)).ok().flatten()
+}
+
+/// Store a response body for `ttl` seconds (unless `ttl` is zero), evicting
+/// entries past their own TTLs and then the oldest so the total stays under
+/// the configured size bound.
+pub(crate) fn store_response(url: &str, body: &str, ttl: u64) {
+    let max_bytes = response_max_bytes();
+    if ttl == 0 || body.len() > max_bytes {
+        return;
+    }
+
+    let mut index = load_response_index();
+    let now = unix_now();
+    for old in index.evict(url, body.len(), now, max_bytes) {
+        if old != url {
+            let _ = var::remove(response_var(&old));
+        }
+    }
+
+    if var::set(response_var(url), body).is_err() {
+        return;
+    }
+    index.entries.push(ResponseEntry {
+        url: url.to_string(),
+        stored_at: now,
+        bytes: body.len(),
+        ttl,
+    });
+    if let Ok(bytes) = serde_json::to_vec(&index) {
+        let _ = var::set(RESPONSE_INDEX_VAR, &bytes);
+    }
+}
+
+/// Drop every cached response body and the index pointing at them.
+fn clear_responses() {
+    for entry in load_response_index().entries {
+        let _ = var::remove(response_var(&entry.url));
+    }
+    let _ = var::remove(RESPONSE_INDEX_VAR);
+}
+
+fn response_var(url: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    format!("http_cache_{:016x}", hasher.finish())
+}
+
+fn load_response_index() -> ResponseIndex {
+    let bytes: Option<Vec<u8>> = var::get(RESPONSE_INDEX_VAR).ok().flatten();
+    bytes
+        .and_then(|b| serde_json::from_slice(&b).ok())
+        .unwrap_or_default()
+}
+
+/// Configured response TTL in seconds (0 disables the cache).
+pub(crate) fn response_ttl() -> u64 {
+    config::get(RESPONSE_TTL_CONFIG)
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map_or(0, |ttl| ttl.min(MAX_RESPONSE_TTL_SECS))
+}
+
+fn response_max_bytes() -> usize {
+    config::get(RESPONSE_MAX_BYTES_CONFIG)
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_RESPONSE_MAX_BYTES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn misses_expire_after_a_day() {
+        let mut cache = MissCache::default();
+        cache.record("wet-leg|moisturizer".to_string(), 1_000);
+        assert!(cache.contains("wet-leg|moisturizer", 1_000 + MISS_TTL_SECS - 1));
+        assert!(!cache.contains("wet-leg|moisturizer", 1_000 + MISS_TTL_SECS));
+        assert!(!cache.contains("wet-leg|wet-leg", 1_000));
+
+        // An expired miss is dropped the next time one is recorded
+        cache.record("idles|tangk".to_string(), 1_000 + MISS_TTL_SECS);
+        assert_eq!(cache.entries.len(), 1);
+    }
+
+    #[test]
+    fn oldest_misses_evicted_past_the_cap() {
+        let mut entries: HashMap<String, u64> = (0..5).map(|at| (at.to_string(), at)).collect();
+        evict_oldest(&mut entries, 3, |at| *at);
+        let mut kept: Vec<&String> = entries.keys().collect();
+        kept.sort();
+        assert_eq!(kept, ["2", "3", "4"]);
+
+        let mut cache = MissCache::default();
+        for at in 0..=MAX_MISSES as u64 {
+            cache.record(format!("artist|album-{}", at), at);
+        }
+        assert_eq!(cache.entries.len(), MAX_MISSES);
+        assert!(!cache.contains("artist|album-0", MAX_MISSES as u64));
+        assert!(cache.contains("artist|album-1", MAX_MISSES as u64));
+    }
+
+    fn entry(url: &str, stored_at: u64, bytes: usize, ttl: u64) -> ResponseEntry {
+        let url = url.to_string();
+        ResponseEntry {
+            url,
+            stored_at,
+            bytes,
+            ttl,
+        }
+    }
+
+    #[test]
+    fn responses_expire_against_their_own_ttl() {
+        let week = MAX_RESPONSE_TTL_SECS;
+        let mut index = ResponseIndex {
+            entries: vec![entry("archive", 0, 10, week), entry("page", 0, 10, 3_600)],
+        };
+        assert!(index.fresh("page", week, 3_599).is_some());
+        assert!(index.fresh("page", week, 3_600).is_none());
+        // A caller accepting only an hour doesn't get the day-old archive copy
+        assert!(index.fresh("archive", 3_600, 86_400).is_none());
+        assert!(index.fresh("archive", week, 86_400).is_some());
+        assert!(index.fresh("missing", week, 0).is_none());
+
+        // An hour-long store a day later keeps the week-long entry
+        let evicted = index.evict("other", 10, 86_400, 1_000);
+        assert_eq!(evicted, ["page"]);
+        let urls: Vec<&str> = index.entries.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(urls, ["archive"]);
+    }
+
+    #[test]
+    fn oldest_responses_evicted_to_fit() {
+        let mut index = ResponseIndex {
+            entries: vec![
+                entry("a", 0, 40, 3_600),
+                entry("b", 1, 40, 3_600),
+                entry("c", 2, 40, 3_600),
+            ],
+        };
+        // Re-storing "c" replaces its entry; "a" goes to make room
+        let evicted = index.evict("c", 50, 10, 100);
+        assert_eq!(evicted, ["c", "a"]);
+        let urls: Vec<&str> = index.entries.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(urls, ["b"]);
+    }
+}
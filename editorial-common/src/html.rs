@@ -1,3 +1,5 @@
+use crate::util::same_site;
+
 /// Extract the first JSON-LD block from HTML that contains a Review.
 pub fn extract_json_ld(html: &str) -> Option<String> {
     let marker = "application/ld+json";
@@ -14,9 +16,7 @@ pub fn extract_json_ld(html: &str) -> Option<String> {
         if json_str.contains("\"Review\"") || json_str.contains("\"reviewBody\"") {
             // Handle JSON arrays
             if json_str.starts_with('[') {
-                if let Ok(arr) =
-                    serde_json::from_str::<Vec<serde_json::Value>>(json_str)
-                {
+                if let Ok(arr) = serde_json::from_str::<Vec<serde_json::Value>>(json_str) {
                     for item in &arr {
                         let s = item.to_string();
                         if s.contains("\"Review\"") || s.contains("\"reviewBody\"") {
@@ -64,3 +64,631 @@ pub fn extract_script_content<'a>(html: &'a str, marker: &str) -> Option<&'a str
 
     None
 }
+
+/// Whether a page's JSON-LD identifies a different release than the one
+/// requested. MusicBrainz IDs are read from `musicbrainz.org` links (e.g. in
+/// `sameAs`) and barcodes from `gtin*` properties. A page carrying no
+/// identifiers of a kind never conflicts on it.
+pub fn release_ids_conflict(html: &str, mbid: Option<&str>, barcode: Option<&str>) -> bool {
+    let blocks = json_ld_blocks(html);
+
+    if let Some(mbid) = mbid.map(str::trim).filter(|m| !m.is_empty()) {
+        let found: Vec<&str> = blocks.iter().flat_map(|b| musicbrainz_ids(b)).collect();
+        if !found.is_empty() && !found.iter().any(|id| id.eq_ignore_ascii_case(mbid)) {
+            return true;
+        }
+    }
+
+    if let Some(barcode) = barcode.map(normalize_barcode).filter(|b| !b.is_empty()) {
+        let found: Vec<String> = blocks.iter().flat_map(|b| gtin_values(b)).collect();
+        if !found.is_empty() && !found.contains(&barcode) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// The contents of every `application/ld+json` script block.
+fn json_ld_blocks(html: &str) -> Vec<&str> {
+    let marker = "application/ld+json";
+    let mut blocks = Vec::new();
+    let mut search_from = 0;
+
+    while let Some(pos) = html[search_from..].find(marker) {
+        let abs_pos = search_from + pos;
+        let Some(content_start) = html[abs_pos..].find('>').map(|p| abs_pos + p + 1) else {
+            break;
+        };
+        let Some(content_end) = html[content_start..]
+            .find("</script>")
+            .map(|p| content_start + p)
+        else {
+            break;
+        };
+        blocks.push(&html[content_start..content_end]);
+        search_from = content_end;
+    }
+
+    blocks
+}
+
+/// MusicBrainz UUIDs linked from a JSON-LD block
+/// (`musicbrainz.org/release-group/<id>` or `/release/<id>`).
+fn musicbrainz_ids(block: &str) -> Vec<&str> {
+    let mut ids = Vec::new();
+    for (pos, _) in block.match_indices("musicbrainz.org/") {
+        let rest = &block[pos + "musicbrainz.org/".len()..];
+        let Some(slash) = rest.find('/') else {
+            continue;
+        };
+        let id = &rest[slash + 1..];
+        let len = id
+            .find(|c: char| !(c.is_ascii_hexdigit() || c == '-'))
+            .unwrap_or(id.len());
+        if len == 36 {
+            ids.push(&id[..len]);
+        }
+    }
+    ids
+}
+
+/// Barcode values from `gtin`, `gtin12`, `gtin13`, etc. properties.
+fn gtin_values(block: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    for (pos, _) in block.match_indices("\"gtin") {
+        let rest = &block[pos..];
+        let Some(colon) = rest.find(':') else {
+            continue;
+        };
+        let value: String = rest[colon + 1..]
+            .trim_start()
+            .trim_start_matches('"')
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        let value = normalize_barcode(&value);
+        if !value.is_empty() {
+            values.push(value);
+        }
+    }
+    values
+}
+
+/// Digits only, without leading zeros, so UPC-A and EAN-13 forms compare equal.
+fn normalize_barcode(barcode: &str) -> String {
+    barcode
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .skip_while(|c| *c == '0')
+        .collect()
+}
+
+/// Markers that only appear on bot-challenge interstitials, not on real pages.
+/// Deliberately excludes `/cdn-cgi/challenge-platform/`, which Cloudflare also
+/// injects into ordinary pages.
+const CHALLENGE_MARKERS: &[&str] = &[
+    "cf-browser-verification",
+    "cf_chl_opt",
+    "<title>Just a moment...</title>",
+    "Attention Required! | Cloudflare",
+    "Checking your browser before accessing",
+    "Enable JavaScript and cookies to continue",
+];
+
+/// Whether a 200 response body is a Cloudflare-style "checking your browser"
+/// challenge rather than the requested page.
+pub fn is_challenge_page(html: &str) -> bool {
+    CHALLENGE_MARKERS.iter().any(|marker| html.contains(marker))
+}
+
+/// Every JSON-LD object on a page, in page order, with arrays and `@graph`
+/// members flattened so nested nodes can be found by type.
+pub fn json_ld_nodes(html: &str) -> Vec<serde_json::Value> {
+    json_ld_blocks(html)
+        .into_iter()
+        .flat_map(block_nodes)
+        .collect()
+}
+
+/// The JSON-LD objects in one script block, flattened as in `json_ld_nodes`.
+/// A block that isn't valid JSON has none.
+fn block_nodes(block: &str) -> Vec<serde_json::Value> {
+    fn flatten(value: serde_json::Value, nodes: &mut Vec<serde_json::Value>) {
+        match value {
+            serde_json::Value::Array(items) => {
+                items.into_iter().for_each(|item| flatten(item, nodes));
+            }
+            serde_json::Value::Object(mut object) => {
+                if let Some(graph) = object.remove("@graph") {
+                    flatten(graph, nodes);
+                }
+                if !object.is_empty() {
+                    nodes.push(serde_json::Value::Object(object));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut nodes = Vec::new();
+    if let Ok(value) = serde_json::from_str(block.trim()) {
+        flatten(value, &mut nodes);
+    }
+    nodes
+}
+
+/// The first JSON-LD object on a page whose `@type` (a string or a list of
+/// strings) is one of `types`.
+pub fn find_json_ld_node(html: &str, types: &[&str]) -> Option<serde_json::Value> {
+    find_json_ld_node_by(html, |node| json_ld_is(node, types))
+}
+
+/// The first JSON-LD object on a page that `matches` accepts. Blocks are
+/// parsed in page order and the scan stops at the first block holding a
+/// match, so the blocks after it are never parsed.
+pub fn find_json_ld_node_by(
+    html: &str,
+    matches: impl Fn(&serde_json::Value) -> bool,
+) -> Option<serde_json::Value> {
+    json_ld_blocks(html)
+        .into_iter()
+        .find_map(|block| block_nodes(block).into_iter().find(&matches))
+}
+
+/// Whether a JSON-LD object's `@type` (a string or a list of strings) is one
+/// of `types`.
+pub fn json_ld_is(node: &serde_json::Value, types: &[&str]) -> bool {
+    let has_type = |kind: &serde_json::Value| kind.as_str().is_some_and(|k| types.contains(&k));
+    match node.get("@type") {
+        Some(serde_json::Value::Array(kinds)) => kinds.iter().any(has_type),
+        Some(kind) => has_type(kind),
+        None => false,
+    }
+}
+
+/// The name in a JSON-LD person/organization property, which may be a plain
+/// string, an object with a `name`, or a list of either (the first is used).
+pub fn json_ld_name(value: &serde_json::Value) -> Option<String> {
+    let name = match value {
+        serde_json::Value::String(name) => name.as_str(),
+        serde_json::Value::Object(object) => object.get("name")?.as_str()?,
+        serde_json::Value::Array(items) => return items.iter().find_map(json_ld_name),
+        _ => return None,
+    };
+    Some(name.trim().to_string()).filter(|name| !name.is_empty())
+}
+
+/// The URL in a JSON-LD `image` property, which may be a plain URL, an
+/// `ImageObject` with a `url` or `contentUrl`, or a list of either (the
+/// first is used).
+pub fn json_ld_image(value: &serde_json::Value) -> Option<String> {
+    let url = match value {
+        serde_json::Value::String(url) => url.as_str(),
+        serde_json::Value::Object(object) => {
+            let url = object.get("url").or_else(|| object.get("contentUrl"));
+            url?.as_str()?
+        }
+        serde_json::Value::Array(items) => return items.iter().find_map(json_ld_image),
+        _ => return None,
+    };
+    Some(url.trim().to_string()).filter(|url| !url.is_empty())
+}
+
+/// Read a JSON-LD numeric property, which sites publish either as a number
+/// or as a numeric string (`4` or `"4.5"`).
+pub fn json_ld_number(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// The value of a JSON-LD `Rating` on a `scale`-point scale. A rating
+/// published out of a different `bestRating` is converted; a missing or zero
+/// `bestRating` means the value is already on `scale`. None when there is no
+/// numeric `ratingValue` or it falls outside 0 to `scale`.
+pub fn json_ld_rating(rating: &serde_json::Value, scale: f64) -> Option<f64> {
+    let value = rating.get("ratingValue").and_then(json_ld_number)?;
+    let best = rating
+        .get("bestRating")
+        .and_then(json_ld_number)
+        .filter(|best| *best > 0.0)
+        .unwrap_or(scale);
+    Some(value * scale / best).filter(|value| (0.0..=scale).contains(value))
+}
+
+/// An N-star rating read from a site's rating markup, on its own 0 to
+/// `max_stars` scale. Understands, in order:
+/// - a `data-rating` attribute ("3.5")
+/// - star icons: a class naming a star ("fa-star", "td-icon-star",
+///   "star-full") is one star, one also naming a half ("fa-star-half-o") half
+///   a star, and one naming an empty or outline star ("fa-star-o",
+///   "star-empty") none
+/// - star glyphs in the text: "★" is one star and "½" half of one
+///
+/// None when there is no star markup, so an unrated page doesn't read as
+/// zero stars, or when the rating falls outside 0 to `max_stars`.
+pub fn parse_star_rating(html: &str, max_stars: f64) -> Option<f64> {
+    let stars = data_rating(html)
+        .or_else(|| count_star_icons(html))
+        .or_else(|| count_star_glyphs(&decode_html_entities(&strip_html_tags(html))))?;
+    Some(stars).filter(|stars| (0.0..=max_stars).contains(stars))
+}
+
+fn data_rating(html: &str) -> Option<f64> {
+    let marker = "data-rating=\"";
+    let start = html.find(marker)? + marker.len();
+    let end = html[start..].find('"')? + start;
+    html[start..end].trim().parse().ok()
+}
+
+fn count_star_icons(html: &str) -> Option<f64> {
+    let mut stars = 0.0;
+    let mut icons = 0;
+    for chunk in html.split("class=\"").skip(1) {
+        let class = &chunk[..chunk.find('"').unwrap_or(chunk.len())];
+        // "star star-half" is a half star: the last star class is the most specific
+        if let Some(value) = class.split_whitespace().rev().find_map(star_icon_value) {
+            icons += 1;
+            stars += value;
+        }
+    }
+    (icons > 0).then_some(stars)
+}
+
+/// What a star icon's class is worth: "fa-star" or "star-full" one star,
+/// "fa-star-half-o" half a star, "fa-star-o" or "star-empty" none. None for
+/// classes that aren't star icons, like the "star-rating" container.
+fn star_icon_value(class: &str) -> Option<f64> {
+    let (_, rest) = class.rsplit_once("star")?;
+    match rest {
+        "" | "-full" | "-filled" | "-fill" => Some(1.0),
+        "-o" | "-empty" | "-outline" | "-off" => Some(0.0),
+        _ if rest.starts_with("-half") => Some(0.5),
+        _ => None,
+    }
+}
+
+fn count_star_glyphs(text: &str) -> Option<f64> {
+    let full = text.matches('\u{2605}').count() as f64;
+    let half = text.matches('\u{bd}').count() as f64;
+    (full + half > 0.0).then_some(full + half / 2.0)
+}
+
+/// Absolute URLs of the on-site links under `/{section}/`, in page order
+/// and without duplicates. An empty `section` takes every on-site link, for
+/// sites whose articles live under date paths.
+pub fn site_links(html: &str, base: &str, section: &str) -> Vec<String> {
+    let prefix = if section.is_empty() {
+        "/".to_string()
+    } else {
+        format!("/{}/", section)
+    };
+    let mut urls = Vec::new();
+
+    for chunk in html.split("href=\"").skip(1) {
+        let Some(end) = chunk.find('"') else {
+            continue;
+        };
+        let href = &chunk[..end];
+        let url = if href.starts_with('/') {
+            format!("{}{}", base, href)
+        } else if same_site(href, base) {
+            href.to_string()
+        } else {
+            continue;
+        };
+        let on_path = url
+            .split_once("://")
+            .and_then(|(_, rest)| rest.find('/').map(|slash| &rest[slash..]))
+            .is_some_and(|path| path.starts_with(&prefix));
+        if on_path && !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+
+    urls
+}
+
+/// Named entities decoded by `decode_html_entities`.
+const NAMED_ENTITIES: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", ' '),
+    ("ndash", '\u{2013}'),
+    ("mdash", '\u{2014}'),
+    ("lsquo", '\u{2018}'),
+    ("rsquo", '\u{2019}'),
+    ("ldquo", '\u{201c}'),
+    ("rdquo", '\u{201d}'),
+    ("hellip", '\u{2026}'),
+    ("bull", '\u{2022}'),
+];
+
+/// Strip HTML tags from a string, keeping only text content.
+pub fn strip_html_tags(html: &str) -> String {
+    let mut result = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for ch in html.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => result.push(ch),
+            _ => {}
+        }
+    }
+    result
+}
+
+/// Readable text from an HTML fragment: line breaks and paragraph ends
+/// become paragraph breaks, tags are dropped, entities decoded, and runs of
+/// whitespace collapsed. Paragraphs are separated by a blank line.
+pub fn html_to_text(html: &str) -> String {
+    let raw = html
+        .replace("<br>", "\n")
+        .replace("<br/>", "\n")
+        .replace("<br />", "\n")
+        .replace("</p>", "\n");
+    let paragraphs: Vec<String> = decode_html_entities(&strip_html_tags(&raw))
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect();
+    paragraphs.join("\n\n")
+}
+
+/// The text of the `<p>` elements in an HTML fragment, one paragraph each
+/// (as `html_to_text` joins them). Embeds, captions and widgets outside
+/// paragraphs are skipped.
+pub fn paragraph_text(html: &str) -> String {
+    let mut paragraphs = String::new();
+    for chunk in html.split("<p").skip(1) {
+        // "<p>" or "<p class=...>", not "<picture>" or "<path>"
+        if !chunk.starts_with(['>', ' ']) {
+            continue;
+        }
+        let Some(open_end) = chunk.find('>') else {
+            continue;
+        };
+        let Some(close) = chunk.find("</p>") else {
+            continue;
+        };
+        if open_end < close {
+            paragraphs.push_str(&chunk[open_end + 1..close]);
+            paragraphs.push_str("</p>");
+        }
+    }
+    html_to_text(&paragraphs)
+}
+
+/// The `content` of the first `<meta>` tag whose `property` or `name` is
+/// `key` (e.g. "og:description" or "author"), entities decoded.
+pub fn meta_content(html: &str, key: &str) -> Option<String> {
+    let names = [format!("property=\"{}\"", key), format!("name=\"{}\"", key)];
+    html.split("<meta ").skip(1).find_map(|chunk| {
+        let tag = &chunk[..chunk.find('>')?];
+        if !names.iter().any(|name| tag.contains(name.as_str())) {
+            return None;
+        }
+        let marker = "content=\"";
+        let start = tag.find(marker)? + marker.len();
+        let end = tag[start..].find('"')? + start;
+        let content = decode_html_entities(tag[start..end].trim());
+        Some(content).filter(|content| !content.is_empty())
+    })
+}
+
+/// The text of the element whose opening tag contains `marker` (e.g.
+/// `class="byline`), up to the first block-level closing tag so inline
+/// children are kept. Tags are stripped, entities decoded and whitespace
+/// collapsed.
+pub fn element_text(html: &str, marker: &str) -> Option<String> {
+    let start = html.find(marker)?;
+    let content_start = start + html[start..].find('>')? + 1;
+    let rest = &html[content_start..];
+    // Skip past inline children ("<span>8</span>") to the element's own close
+    let end = ["</div>", "</p>", "</time>", "</h2>"]
+        .iter()
+        .filter_map(|close| rest.find(close))
+        .min()?;
+    let text = decode_html_entities(&strip_html_tags(&rest[..end]));
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    Some(text).filter(|text| !text.is_empty())
+}
+
+/// Decode HTML character references: the common named entities and any
+/// decimal or hex numeric reference. Unknown references are left as-is, and
+/// non-breaking spaces become plain spaces.
+pub fn decode_html_entities(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        result.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let decoded = after
+            .split_once(';')
+            .and_then(|(name, _)| Some((name.len(), decode_entity(name)?)));
+        match decoded {
+            Some((len, ch)) => {
+                result.push(if ch == '\u{a0}' { ' ' } else { ch });
+                rest = &after[len + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// The character an entity name (without `&` and `;`) stands for.
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    NAMED_ENTITIES
+        .iter()
+        .find(|(entity, _)| *entity == name)
+        .map(|(_, ch)| *ch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_id_verification() {
+        let html = r#"<script type="application/ld+json">{"@type":"MusicAlbum",
+            "sameAs":["https://musicbrainz.org/release-group/b1392450-e666-3926-a536-22c65f834433"],
+            "gtin13":"0724384260910"}</script>"#;
+        let mbid = "b1392450-e666-3926-a536-22c65f834433";
+        let other = "f5093c06-23e3-404f-aeaa-40f72885ee3a";
+
+        assert!(!release_ids_conflict(html, Some(mbid), None));
+        assert!(release_ids_conflict(html, Some(other), None));
+        assert!(!release_ids_conflict(html, None, Some("724384260910")));
+        assert!(release_ids_conflict(html, None, Some("5099902987422")));
+        // No identifiers on the page: nothing to contradict
+        let bare = r#"<script type="application/ld+json">{"@type":"MusicAlbum"}</script>"#;
+        let barcode = Some("5099902987422");
+        assert!(!release_ids_conflict(bare, Some(other), barcode));
+    }
+
+    #[test]
+    fn json_ld_nodes_flatten_graphs() {
+        let html = r#"<script type="application/ld+json">[{"@type":"WebPage"},
+            {"@graph":[{"@type":["Review","CreativeWork"],"author":[{"name":"Jane Doe"}],
+            "reviewRating":{"ratingValue":"4.5"}}]}]</script>
+            <script type="application/ld+json">{"@type":"MusicAlbum","byArtist":"Radiohead"}</script>"#;
+        assert_eq!(json_ld_nodes(html).len(), 3);
+
+        let review = find_json_ld_node(html, &["Review"]).unwrap();
+        assert_eq!(json_ld_name(&review["author"]).as_deref(), Some("Jane Doe"));
+        let rating = &review["reviewRating"]["ratingValue"];
+        assert_eq!(json_ld_number(rating), Some(4.5));
+
+        let album = find_json_ld_node(html, &["MusicAlbum"]).unwrap();
+        let artist = json_ld_name(&album["byArtist"]);
+        assert_eq!(artist.as_deref(), Some("Radiohead"));
+        let cover = "https://cdn.example.com/ok-computer.jpg";
+        let image = serde_json::json!([{"@type": "ImageObject", "url": cover}]);
+        assert_eq!(json_ld_image(&image).as_deref(), Some(cover));
+        assert!(find_json_ld_node(html, &["Person"]).is_none());
+
+        // The second block is never looked at once the first holds a match
+        let seen = std::cell::Cell::new(0);
+        let found = find_json_ld_node_by(html, |node| {
+            seen.set(seen.get() + 1);
+            node.get("reviewRating").is_some()
+        });
+        assert!(found.is_some());
+        assert_eq!(seen.get(), 2);
+    }
+
+    #[test]
+    fn star_ratings_from_markup() {
+        let icons = r#"<div class="star-rating"><i class="fa fa-star"></i><i class="fa fa-star"></i>
+            <i class="fa fa-star-half-o"></i><i class="fa fa-star-o"></i></div>"#;
+        assert_eq!(parse_star_rating(icons, 5.0), Some(2.5));
+        let spans = r#"<span class="star star-full"></span><span class="star star-half"></span>
+            <span class="td-icon-star-empty"></span>"#;
+        assert_eq!(parse_star_rating(spans, 5.0), Some(1.5));
+        let empty = r#"<i class="fa fa-star-o"></i>"#;
+        assert_eq!(parse_star_rating(empty, 5.0), Some(0.0));
+
+        let decimal = r#"<div class="rating" data-rating="3.7"><i class="fa fa-star"></i></div>"#;
+        assert_eq!(parse_star_rating(decimal, 5.0), Some(3.7));
+        let glyphs = "<span>&#9733;&#9733;&#9733;&#189;</span>&#9734;";
+        assert_eq!(parse_star_rating(glyphs, 5.0), Some(3.5));
+
+        let too_many = r#"<div data-rating="8"></div>"#;
+        assert_eq!(parse_star_rating(too_many, 5.0), None);
+        assert_eq!(parse_star_rating(&"\u{2605}".repeat(6), 5.0), None);
+        assert_eq!(parse_star_rating("<p>Unrated</p>", 5.0), None);
+        let container = r#"<div class="stars">4</div>"#;
+        assert_eq!(parse_star_rating(container, 5.0), None);
+    }
+
+    #[test]
+    fn ratings_converted_to_scale() {
+        let stars = serde_json::json!({"ratingValue": "4.5", "bestRating": "5"});
+        assert_eq!(json_ld_rating(&stars, 5.0), Some(4.5));
+        assert_eq!(json_ld_rating(&stars, 10.0), Some(9.0));
+        let unscaled = serde_json::json!({"ratingValue": 8, "bestRating": 0});
+        assert_eq!(json_ld_rating(&unscaled, 10.0), Some(8.0));
+        assert_eq!(json_ld_rating(&unscaled, 5.0), None);
+        let grade = serde_json::json!({"ratingValue": "A-"});
+        assert_eq!(json_ld_rating(&grade, 10.0), None);
+    }
+
+    #[test]
+    fn entities_decoded_after_stripping() {
+        let html = "<p>Rock &amp; roll&#8217;s <b>best</b>&nbsp;&mdash; &#x2014; &bogus; & so</p>";
+        let text = decode_html_entities(&strip_html_tags(html));
+        let expected = "Rock & roll\u{2019}s best \u{2014} \u{2014} &bogus; & so";
+        assert_eq!(text, expected);
+
+        let invalid = "A&#160;B &#xZZ; &#1114112;";
+        assert_eq!(decode_html_entities(invalid), "A B &#xZZ; &#1114112;");
+    }
+
+    #[test]
+    fn text_and_meta_from_markup() {
+        let html = "<div><p>First  line<br/>wraps.</p>\n<p>Tom &amp; Jerry</p><p> </p></div>";
+        assert_eq!(html_to_text(html), "First line\n\nwraps.\n\nTom & Jerry");
+        let embed = "<p class=\"lead\">One.</p><picture>x</picture><div>Share</div><p>Two.</p>";
+        assert_eq!(paragraph_text(embed), "One.\n\nTwo.");
+
+        let head = r#"<meta property="og:title" content="Title">
+            <meta content="Either attribute order" name="description">
+            <meta name="author" content="Tom Breihan">"#;
+        assert_eq!(meta_content(head, "author").as_deref(), Some("Tom Breihan"));
+        assert_eq!(meta_content(head, "og:title").as_deref(), Some("Title"));
+        let description = meta_content(head, "description");
+        assert_eq!(description.as_deref(), Some("Either attribute order"));
+        assert_eq!(meta_content(head, "og:image"), None);
+
+        let byline = r#"<p class="byline large">By <a href="/a/1">Jo&nbsp;Doe</a></p><p>x</p>"#;
+        let text = element_text(byline, "class=\"byline");
+        assert_eq!(text.as_deref(), Some("By Jo Doe"));
+        assert_eq!(element_text(byline, "class=\"rating"), None);
+    }
+
+    #[test]
+    fn site_links_by_section() {
+        let base = "https://consequence.net";
+        let html = r#"<a href="/2024/05/billie-eilish-review/">Review</a>
+            <a href="/tag/billie-eilish/">Tag</a>
+            <a href="https://www.consequence.net/2024/05/billie-eilish-review/">Again</a>
+            <a href="https://elsewhere.example/2024/05/other/">Elsewhere</a>"#;
+        let tags = site_links(html, base, "tag");
+        assert_eq!(tags, [format!("{}/tag/billie-eilish/", base)]);
+        let all = site_links(html, base, "");
+        assert_eq!(all.len(), 3);
+        assert!(all.iter().all(|url| same_site(url, base)));
+    }
+
+    #[test]
+    fn challenge_page_markers() {
+        let interstitial = r#"<!DOCTYPE html><html><head><title>Just a moment...</title>
+            <script>window._cf_chl_opt={cvId: '3'};</script></head></html>"#;
+        assert!(is_challenge_page(interstitial));
+
+        let real = r#"<html><head><title>Album Review</title>
+            <script src="/cdn-cgi/challenge-platform/scripts/jsd/main.js"></script>
+            </head><body>Just a moment of silence.</body></html>"#;
+        assert!(!is_challenge_page(real));
+    }
+}
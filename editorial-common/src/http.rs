@@ -0,0 +1,679 @@
+use std::io::Read;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use extism_pdk::{config, http, var, HttpRequest, HttpResponse};
+
+use crate::cache::{cached_response, response_ttl, store_response, MAX_RESPONSE_TTL_SECS};
+use crate::html::is_challenge_page;
+use crate::types::{FetchError, HealthReport};
+use crate::util::url_host;
+
+/// User-Agent sent when the host doesn't configure one. Several sites answer
+/// UA-less clients with 403s or bot-challenge pages.
+pub const DEFAULT_USER_AGENT: &str =
+    "Mozilla/5.0 (compatible; riff-editorial/0.1; +https://github.com/alexmaslar/riff)";
+/// Extism config key overriding the User-Agent for all outbound requests.
+const USER_AGENT_CONFIG: &str = "user_agent";
+/// Extism config key overriding the Accept-Language sent with every request.
+const ACCEPT_LANGUAGE_CONFIG: &str = "accept_language";
+/// Accept-Language used when the host doesn't configure one.
+const DEFAULT_ACCEPT_LANGUAGE: &str = "en";
+/// Extism config key for the number of retries on 429/5xx responses.
+const MAX_RETRIES_CONFIG: &str = "http_max_retries";
+/// Retries used when the host doesn't configure any.
+const DEFAULT_MAX_RETRIES: u32 = 2;
+/// Hard ceiling on retries regardless of configuration.
+const RETRY_LIMIT: u32 = 5;
+/// Delay before the first retry; doubled on each subsequent attempt.
+const BACKOFF_BASE_MS: u64 = 500;
+/// Longest `Retry-After` delay honored; longer requests fall back to this so a
+/// single call can't stall the plugin indefinitely.
+const MAX_RETRY_AFTER_SECS: u64 = 30;
+/// Maximum redirect hops followed for a single request.
+const MAX_REDIRECTS: usize = 5;
+/// Extism config key for the minimum interval between requests to one host.
+const MIN_INTERVAL_CONFIG: &str = "http_min_interval_ms";
+/// Per-host spacing used when the host doesn't configure one.
+const DEFAULT_MIN_INTERVAL_MS: u64 = 250;
+/// Ceiling on the configurable per-host interval.
+const MAX_MIN_INTERVAL_MS: u64 = 10_000;
+
+/// Build a GET request with the shared header policy (User-Agent,
+/// Accept-Language) plus the given Accept header. Extra per-request headers
+/// can be chained on.
+pub fn build_request(url: &str, accept: &str) -> HttpRequest {
+    HttpRequest::new(url)
+        .with_header("User-Agent", user_agent())
+        .with_header("Accept", accept)
+        .with_header("Accept-Language", accept_language())
+}
+
+/// Resolve a scraper's base URL: the Extism config value under `config_key`
+/// if set (e.g. a local fixture server or caching proxy), otherwise the
+/// production default. Trailing slashes are trimmed.
+pub fn configured_base_url(config_key: &str, default: &str) -> String {
+    config::get(config_key)
+        .ok()
+        .flatten()
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| url.contains("://"))
+        .unwrap_or_else(|| default.to_string())
+}
+
+/// The configured User-Agent, or `DEFAULT_USER_AGENT`.
+fn user_agent() -> String {
+    config::get(USER_AGENT_CONFIG)
+        .ok()
+        .flatten()
+        .map(|ua| ua.trim().to_string())
+        .filter(|ua| !ua.is_empty())
+        .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string())
+}
+
+/// The configured Accept-Language, or `DEFAULT_ACCEPT_LANGUAGE`.
+fn accept_language() -> String {
+    config::get(ACCEPT_LANGUAGE_CONFIG)
+        .ok()
+        .flatten()
+        .map(|lang| lang.trim().to_string())
+        .filter(|lang| !lang.is_empty())
+        .unwrap_or_else(|| DEFAULT_ACCEPT_LANGUAGE.to_string())
+}
+
+/// Send a request, following 3xx redirects and retrying 429 and 5xx responses
+/// with exponential backoff. Other statuses (including non-429 4xx) are
+/// returned immediately. After the final attempt the last response is
+/// returned so callers can still inspect its status.
+pub fn http_get_with_retry(req: &HttpRequest) -> Result<HttpResponse, extism_pdk::Error> {
+    follow_redirects(req, send_with_retry, |resp| {
+        redirect_location(resp.status_code(), response_header(resp, "Location"))
+    })
+}
+
+/// Re-send the request to each redirect target (resolved against the current
+/// URL) until a non-redirect response, `MAX_REDIRECTS` hops, or a loop back to
+/// an already-visited URL; in the latter two cases the redirect response
+/// itself is returned.
+fn follow_redirects<R>(
+    req: &HttpRequest,
+    mut send: impl FnMut(&HttpRequest) -> Result<R, extism_pdk::Error>,
+    location: impl Fn(&R) -> Option<String>,
+) -> Result<R, extism_pdk::Error> {
+    let mut current = req.clone();
+    let mut visited = vec![current.url.clone()];
+
+    loop {
+        let resp = send(&current)?;
+        let Some(target) = location(&resp) else {
+            return Ok(resp);
+        };
+        let next_url = resolve_url(&current.url, &target);
+        if visited.len() > MAX_REDIRECTS || visited.contains(&next_url) {
+            return Ok(resp);
+        }
+        visited.push(next_url.clone());
+        current.url = next_url;
+    }
+}
+
+/// The redirect target for a 3xx response that carries a `Location`.
+fn redirect_location(status: u16, location: Option<&str>) -> Option<String> {
+    match status {
+        301 | 302 | 303 | 307 | 308 => location
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Resolve a `Location` value against the URL that produced it.
+/// Handles absolute, scheme-relative, root-relative, and path-relative forms.
+fn resolve_url(base: &str, location: &str) -> String {
+    if location.contains("://") {
+        return location.to_string();
+    }
+    let Some((scheme, rest)) = base.split_once("://") else {
+        return location.to_string();
+    };
+    if let Some(stripped) = location.strip_prefix("//") {
+        return format!("{}://{}", scheme, stripped);
+    }
+
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let authority = &rest[..authority_end];
+    if location.starts_with('/') {
+        return format!("{}://{}{}", scheme, authority, location);
+    }
+
+    // Path-relative: replace everything after the last '/' of the base path
+    let path = rest[authority_end..].split(['?', '#']).next().unwrap_or("");
+    let dir = match path.rfind('/') {
+        Some(pos) => &path[..=pos],
+        None => "/",
+    };
+    format!("{}://{}{}{}", scheme, authority, dir, location)
+}
+
+/// Send a request, retrying 429 and 5xx responses with exponential backoff.
+fn send_with_retry(req: &HttpRequest) -> Result<HttpResponse, extism_pdk::Error> {
+    let max_retries = max_retries();
+    let mut attempt = 0;
+
+    loop {
+        throttle(&req.url);
+        let resp = http::request::<()>(req, None)?;
+        let status = resp.status_code();
+        if !is_retryable(status) || attempt >= max_retries {
+            return Ok(resp);
+        }
+
+        // 429/503 may tell us how long to back off; otherwise use the default
+        let delay = if status == 429 || status == 503 {
+            response_header(&resp, "Retry-After").and_then(parse_retry_after)
+        } else {
+            None
+        };
+        wait(delay.unwrap_or_else(|| backoff_delay(attempt)));
+        attempt += 1;
+    }
+}
+
+/// Whether a status is worth retrying: rate limiting and server errors.
+fn is_retryable(status: u16) -> bool {
+    status == 429 || (500..=599).contains(&status)
+}
+
+/// Exponential backoff for the given zero-based retry attempt.
+fn backoff_delay(attempt: u32) -> Duration {
+    Duration::from_millis(BACKOFF_BASE_MS << attempt.min(RETRY_LIMIT))
+}
+
+/// Block for the given duration. WASM has no native sleep instruction; on
+/// wasm32-wasip1 `std::thread::sleep` becomes a WASI `poll_oneoff` clock
+/// subscription, so the delay is realized by the host's clock.
+fn wait(delay: Duration) {
+    std::thread::sleep(delay.min(Duration::from_secs(MAX_RETRY_AFTER_SECS)));
+}
+
+/// Space out requests to the same host by at least the configured interval.
+/// The last request time per host is kept in an Extism var so the spacing
+/// also holds across plugin calls (e.g. successive TLOBF crawl batches).
+fn throttle(url: &str) {
+    let Some(host) = url_host(url) else {
+        return;
+    };
+    let interval = min_interval_ms();
+    if interval == 0 {
+        return;
+    }
+
+    let key = format!("throttle_{}", host);
+    let last: Option<u64> = var::get::<String>(&key)
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse().ok());
+    if let Some(last) = last {
+        let elapsed = now_millis().saturating_sub(last);
+        if elapsed < interval {
+            wait(Duration::from_millis(interval - elapsed));
+        }
+    }
+    let _ = var::set(&key, now_millis().to_string());
+}
+
+/// The configured per-host interval in milliseconds (clamped), or the default.
+fn min_interval_ms() -> u64 {
+    config::get(MIN_INTERVAL_CONFIG)
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map_or(DEFAULT_MIN_INTERVAL_MS, |ms| ms.min(MAX_MIN_INTERVAL_MS))
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Probe a site root for the deep health check: one HEAD request (retried as
+/// a GET if the server rejects HEAD), following redirects but not retrying.
+/// Any 2xx/3xx final status counts as healthy.
+pub fn probe_health(base_url: &str) -> HealthReport {
+    let head = build_request(base_url, "text/html").with_method("HEAD");
+    let mut status = probe_status(&head);
+    if matches!(status, Some(405 | 501)) {
+        status = probe_status(&build_request(base_url, "text/html"));
+    }
+
+    let healthy = status.is_some_and(|code| (200..400).contains(&code));
+    HealthReport {
+        status: if healthy { "ok" } else { "degraded" },
+        http: status,
+    }
+}
+
+fn probe_status(req: &HttpRequest) -> Option<u16> {
+    let resp = follow_redirects(
+        req,
+        |r| {
+            throttle(&r.url);
+            http::request::<()>(r, None)
+        },
+        |resp| redirect_location(resp.status_code(), response_header(resp, "Location")),
+    )
+    .ok()?;
+    Some(resp.status_code())
+}
+
+/// Fetch a page's text: GET with redirects/retries, require a 200, and decode
+/// the body. Failures are classified so callers can tell a missing page from
+/// a blocked or broken one; a 200 carrying a bot-challenge interstitial counts
+/// as blocked. When the response cache is enabled
+/// (`http_cache_ttl_secs`), a fresh cached copy is returned without touching
+/// the network.
+pub fn fetch_page(req: &HttpRequest) -> Result<String, FetchError> {
+    fetch_cached_page(req, response_ttl())
+}
+
+/// `fetch_page` for archived sites whose pages no longer change: responses
+/// are cached for a week even when `http_cache_ttl_secs` is shorter or
+/// unset, so repeat lookups don't go back to a slow archive host.
+pub fn fetch_archived_page(req: &HttpRequest) -> Result<String, FetchError> {
+    fetch_cached_page(req, response_ttl().max(MAX_RESPONSE_TTL_SECS))
+}
+
+/// `fetch_page` for JSON APIs that explain a failure in the response body,
+/// such as WordPress's `{"code":"rest_invalid_param",...}` sent with a 400:
+/// a non-200 response's body is kept alongside its classification. The body
+/// is empty when there was no response at all.
+pub fn fetch_api_page(req: &HttpRequest) -> Result<String, (FetchError, String)> {
+    fetch_cached_response(req, response_ttl())
+}
+
+fn fetch_cached_page(req: &HttpRequest, ttl: u64) -> Result<String, FetchError> {
+    fetch_cached_response(req, ttl).map_err(|(err, _)| err)
+}
+
+fn fetch_cached_response(req: &HttpRequest, ttl: u64) -> Result<String, (FetchError, String)> {
+    if let Some(body) = cached_response(&req.url, ttl) {
+        return Ok(body);
+    }
+
+    let resp = http_get_with_retry(req).map_err(|_| (FetchError::Error, String::new()))?;
+    let status = resp.status_code();
+    let body = response_text(&resp);
+    if status != 200 {
+        return Err((FetchError::from_status(status), body));
+    }
+    if is_challenge_page(&body) {
+        return Err((FetchError::Blocked, body));
+    }
+    store_response(&req.url, &body, ttl);
+    Ok(body)
+}
+
+/// Decode a response body to text, transparently decompressing gzip/deflate
+/// content and honoring a Latin-1/Windows-1252 charset. Invalid UTF-8 is
+/// replaced rather than discarding the page.
+pub fn response_text(resp: &HttpResponse) -> String {
+    let body = decompress_body(resp.body(), response_header(resp, "Content-Encoding"));
+    decode_body(body, response_header(resp, "Content-Type"))
+}
+
+/// Decode body bytes using the charset from the `Content-Type` header, else a
+/// `<meta charset>` declaration near the top of the document, else UTF-8.
+/// A leading UTF-8 BOM wins over any declaration and is stripped.
+fn decode_body(mut body: Vec<u8>, content_type: Option<&str>) -> String {
+    let charset = if body.starts_with(UTF8_BOM) {
+        body.drain(..UTF8_BOM.len());
+        None
+    } else {
+        content_type
+            .and_then(charset_param)
+            .or_else(|| sniff_meta_charset(&body))
+    };
+
+    match charset.as_deref() {
+        // Per the HTML spec, Latin-1 and ASCII labels are decoded as Windows-1252
+        Some("iso-8859-1" | "latin1" | "windows-1252" | "cp1252" | "us-ascii") => {
+            decode_windows_1252(&body)
+        }
+        _ => match String::from_utf8(body) {
+            Ok(text) => text,
+            Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
+        },
+    }
+}
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+/// The lowercased `charset=` parameter of a Content-Type-style value.
+fn charset_param(value: &str) -> Option<String> {
+    let lower = value.to_ascii_lowercase();
+    let start = lower.find("charset=")? + "charset=".len();
+    let charset: String = lower[start..]
+        .trim_start_matches(['"', '\''])
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+        .collect();
+    (!charset.is_empty()).then_some(charset)
+}
+
+/// Look for `<meta charset="...">` or an http-equiv Content-Type declaration
+/// in the first 1024 bytes, where the HTML spec requires it to appear.
+fn sniff_meta_charset(body: &[u8]) -> Option<String> {
+    let head = String::from_utf8_lossy(&body[..body.len().min(1024)]).to_ascii_lowercase();
+    let mut search_from = 0;
+    while let Some(pos) = head[search_from..].find("<meta") {
+        let tag_start = search_from + pos;
+        let tag_end = head[tag_start..]
+            .find('>')
+            .map_or(head.len(), |end| tag_start + end);
+        if let Some(charset) = charset_param(&head[tag_start..tag_end]) {
+            return Some(charset);
+        }
+        search_from = tag_end;
+    }
+    None
+}
+
+/// Windows-1252 code points for bytes 0x80-0x9F (undefined bytes map to
+/// their C1 control equivalents, as browsers do).
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+
+fn decode_windows_1252(body: &[u8]) -> String {
+    body.iter()
+        .map(|&b| match b {
+            0x80..=0x9F => WINDOWS_1252_HIGH[(b - 0x80) as usize],
+            _ => b as char,
+        })
+        .collect()
+}
+
+/// Decompress a body according to its `Content-Encoding`. A gzip magic header
+/// is honored even when the header is missing. Bodies that fail to decompress
+/// are returned unchanged.
+fn decompress_body(body: Vec<u8>, content_encoding: Option<&str>) -> Vec<u8> {
+    let encoding = content_encoding.map(|e| e.trim().to_ascii_lowercase());
+    let is_gzip = matches!(encoding.as_deref(), Some("gzip") | Some("x-gzip"))
+        || body.starts_with(&[0x1f, 0x8b]);
+
+    let mut out = Vec::new();
+    let decoded = if is_gzip {
+        flate2::read::GzDecoder::new(body.as_slice()).read_to_end(&mut out)
+    } else if encoding.as_deref() == Some("deflate") {
+        // "deflate" is specified as zlib-wrapped, but some servers send raw deflate
+        flate2::read::ZlibDecoder::new(body.as_slice())
+            .read_to_end(&mut out)
+            .or_else(|_| {
+                out.clear();
+                flate2::read::DeflateDecoder::new(body.as_slice()).read_to_end(&mut out)
+            })
+    } else {
+        return body;
+    };
+
+    match decoded {
+        Ok(_) => out,
+        Err(_) => body,
+    }
+}
+
+/// Look up a response header case-insensitively.
+pub fn response_header<'a>(resp: &'a HttpResponse, name: &str) -> Option<&'a str> {
+    resp.headers()
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+/// Parse a `Retry-After` header value: either delay-seconds (`"120"`) or an
+/// HTTP-date (`"Wed, 21 Oct 2015 07:28:00 GMT"`). Dates in the past yield a
+/// zero delay.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    parse_retry_after_at(value, crate::unix_now())
+}
+
+fn parse_retry_after_at(value: &str, now: u64) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = parse_http_date(value)?;
+    Some(Duration::from_secs(at.saturating_sub(now)))
+}
+
+/// Parse an IMF-fixdate (`"Sun, 06 Nov 1994 08:49:37 GMT"`) into Unix seconds.
+fn parse_http_date(value: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let (_, rest) = value.split_once(", ")?;
+    let parts: Vec<&str> = rest.split_whitespace().collect();
+    let [day, month, year, time, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+
+    let day: u64 = day.parse().ok()?;
+    let month = MONTHS.iter().position(|m| m == month)? as u64 + 1;
+    let year: u64 = year.parse().ok()?;
+    let mut hms = time.split(':').map(|p| p.parse::<u64>().ok());
+    let (h, m, s) = (hms.next()??, hms.next()??, hms.next()??);
+    if !(1..=31).contains(&day) || year < 1970 || h > 23 || m > 59 || s > 60 {
+        return None;
+    }
+
+    Some(days_from_civil(year, month, day) * 86_400 + h * 3600 + m * 60 + s)
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's
+/// `days_from_civil`, restricted to dates after the epoch).
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// The configured retry count (clamped to `RETRY_LIMIT`), or the default.
+fn max_retries() -> u32 {
+    config::get(MAX_RETRIES_CONFIG)
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .map_or(DEFAULT_MAX_RETRIES, |n| n.min(RETRY_LIMIT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retryable_statuses() {
+        assert!(is_retryable(429));
+        assert!(is_retryable(500));
+        assert!(is_retryable(503));
+        assert!(!is_retryable(200));
+        assert!(!is_retryable(404));
+        assert!(!is_retryable(403));
+    }
+
+    #[test]
+    fn retry_after_seconds() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn retry_after_http_date() {
+        // 1994-11-06T08:49:37Z
+        let at = 784_111_777;
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"), Some(at));
+        assert_eq!(
+            parse_retry_after_at("Sun, 06 Nov 1994 08:49:37 GMT", at - 90),
+            Some(Duration::from_secs(90))
+        );
+        // Dates in the past mean "retry now"
+        assert_eq!(
+            parse_retry_after_at("Sun, 06 Nov 1994 08:49:37 GMT", at + 10),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 PST"), None);
+    }
+
+    #[test]
+    fn latin1_body_is_decoded() {
+        // A Latin-1 e-acute and a Windows-1252 em-dash (0x97)
+        let body = b"<p>Caf\xe9 review \x97 great</p>".to_vec();
+        assert_eq!(
+            decode_body(body.clone(), Some("text/html; charset=ISO-8859-1")),
+            "<p>Caf\u{e9} review \u{2014} great</p>"
+        );
+
+        let mut sniffed = b"<html><head><meta charset=\"windows-1252\"></head>".to_vec();
+        sniffed.extend_from_slice(&body);
+        assert!(decode_body(sniffed, Some("text/html")).contains("review \u{2014} great"));
+
+        // Undeclared invalid bytes are replaced rather than dropping the page
+        let lossy = decode_body(body, None);
+        assert!(lossy.contains("review \u{FFFD} great"));
+    }
+
+    #[test]
+    fn stray_invalid_byte_costs_one_character() {
+        let mut body = "<p>Wet Leg\u{2019}s ".as_bytes().to_vec();
+        body.push(0xFF);
+        body.extend_from_slice("second album</p>".as_bytes());
+        let text = decode_body(body, Some("text/html; charset=utf-8"));
+        assert_eq!(text, "<p>Wet Leg\u{2019}s \u{FFFD}second album</p>");
+    }
+
+    #[test]
+    fn utf8_bom_is_stripped() {
+        let mut body = vec![0xEF, 0xBB, 0xBF];
+        body.extend_from_slice("<html>caf\u{e9}</html>".as_bytes());
+        let text = decode_body(body.clone(), None);
+        assert_eq!(text, "<html>caf\u{e9}</html>");
+        assert_eq!(text.find("<html>"), Some(0));
+
+        // The BOM overrides a conflicting header charset
+        assert_eq!(
+            decode_body(body, Some("text/html; charset=iso-8859-1")),
+            "<html>caf\u{e9}</html>"
+        );
+    }
+
+    #[test]
+    fn gzip_body_is_decompressed() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let html = "<html><script type=\"application/ld+json\">{}</script></html>";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(html.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        assert_eq!(
+            decompress_body(gzipped.clone(), Some("gzip")),
+            html.as_bytes()
+        );
+        // Magic bytes are enough even without the header
+        assert_eq!(decompress_body(gzipped, None), html.as_bytes());
+        // Plain bodies pass through untouched
+        assert_eq!(
+            decompress_body(html.as_bytes().to_vec(), None),
+            html.as_bytes()
+        );
+    }
+
+    #[test]
+    fn redirects_follow_two_hops() {
+        let req = HttpRequest::new("https://pitchfork.com/reviews/albums/old-slug");
+        let mut seen = Vec::new();
+        let resp = follow_redirects(
+            &req,
+            |r| {
+                seen.push(r.url.clone());
+                Ok(match r.url.as_str() {
+                    "https://pitchfork.com/reviews/albums/old-slug" => {
+                        (301, Some("/reviews/albums/mid"))
+                    }
+                    "https://pitchfork.com/reviews/albums/mid" => (302, Some("final/")),
+                    _ => (200, None),
+                })
+            },
+            |(status, loc)| redirect_location(*status, *loc),
+        )
+        .unwrap();
+
+        assert_eq!(resp.0, 200);
+        assert_eq!(
+            seen,
+            vec![
+                "https://pitchfork.com/reviews/albums/old-slug",
+                "https://pitchfork.com/reviews/albums/mid",
+                "https://pitchfork.com/reviews/albums/final/",
+            ]
+        );
+    }
+
+    #[test]
+    fn redirect_loop_stops() {
+        let req = HttpRequest::new("https://a.example/x");
+        let mut calls = 0;
+        let resp = follow_redirects(
+            &req,
+            |r| {
+                calls += 1;
+                let to = if r.url.ends_with("/x") { "/y" } else { "/x" };
+                Ok((301, Some(to)))
+            },
+            |(status, loc)| redirect_location(*status, *loc),
+        )
+        .unwrap();
+        assert_eq!(resp.0, 301);
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn resolves_locations() {
+        let base = "https://www.allmusic.com/album/foo-mw1?x=1";
+        assert_eq!(
+            resolve_url(base, "https://other.com/a"),
+            "https://other.com/a"
+        );
+        assert_eq!(
+            resolve_url(base, "//cdn.example/a"),
+            "https://cdn.example/a"
+        );
+        assert_eq!(
+            resolve_url(base, "/album/bar-mw2"),
+            "https://www.allmusic.com/album/bar-mw2"
+        );
+        assert_eq!(
+            resolve_url(base, "bar-mw2"),
+            "https://www.allmusic.com/album/bar-mw2"
+        );
+    }
+
+    #[test]
+    fn backoff_doubles() {
+        assert_eq!(backoff_delay(0), Duration::from_millis(500));
+        assert_eq!(backoff_delay(1), Duration::from_millis(1000));
+        assert_eq!(backoff_delay(2), Duration::from_millis(2000));
+    }
+}
@@ -1,7 +1,37 @@
+mod cache;
 mod html;
+mod http;
+pub mod plugin;
 mod types;
 mod util;
 
-pub use html::{extract_json_ld, extract_script_content};
-pub use types::{AlbumReviewInput, EditorialResult, EditorialReview, SiteReview, wrap_review};
-pub use util::{clean_title, slugify, url_encode};
+pub use cache::{
+    cached_review_url, clear_caches, forget_review_url, is_cached_miss, record_miss,
+    record_review_url,
+};
+pub use html::{
+    decode_html_entities, element_text, extract_json_ld, extract_script_content, find_json_ld_node,
+    find_json_ld_node_by, html_to_text, is_challenge_page, json_ld_image, json_ld_is, json_ld_name,
+    json_ld_nodes, json_ld_number, json_ld_rating, meta_content, paragraph_text, parse_star_rating,
+    release_ids_conflict, site_links, strip_html_tags,
+};
+pub use http::{
+    build_request, configured_base_url, fetch_api_page, fetch_archived_page, fetch_page,
+    http_get_with_retry, parse_retry_after, probe_health, response_header, response_text,
+    DEFAULT_USER_AGENT,
+};
+pub use plugin::{Hooks, Scraper};
+pub use types::{
+    wrap_aggregate, wrap_batch, wrap_rating, wrap_review, wrap_reviews, AggregateRating,
+    AlbumBatchInput, AlbumReviewInput, CacheStats, ClearCacheInput, ClearCacheReport,
+    EditorialResult, EditorialReview, FetchError, FetchOutcome, HealthCheckInput, HealthReport,
+    PluginInfo, PrewarmInput, RatingResult, ReleaseType, ReviewOptions, ReviewUrlInput,
+    SearchCandidate, SearchResult, SiteReview, SiteReviewBuilder,
+};
+pub use util::{
+    artist_aliases, best_candidate, canonicalize_url, clean_title, match_confidence,
+    normalize_date, parse_fractional_rating, path_segment, prefer_release_type, same_site,
+    scale_rating, score_candidate, search_terms, slug_release_type, slugify, truncate_at_sentence,
+    unix_now, url_encode, url_host, Candidate, Query, DEFINITIVE_MATCH_SCORE, MAX_EXCERPT_CHARS,
+    MIN_MATCH_SCORE,
+};
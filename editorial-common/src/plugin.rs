@@ -0,0 +1,435 @@
+//! The `riff_*` plugin exports every scraper shares. `editorial_plugin!`
+//! generates the `#[plugin_fn]` shells; the behaviour behind them lives here.
+
+use crate::cache::{clear_caches, is_cached_miss, record_miss};
+use crate::http::probe_health;
+use crate::types::{
+    wrap_batch, wrap_rating, wrap_review, wrap_reviews, AlbumBatchInput, AlbumReviewInput,
+    CacheStats, ClearCacheInput, FetchOutcome, HealthCheckInput, PluginInfo, ReviewUrlInput,
+    SearchResult, SiteReview,
+};
+use crate::util::same_site;
+
+/// A scraper's entry points, which the plugin exports are built from.
+pub struct Scraper {
+    /// Name reported in `riff_plugin_info` and every review, and the key the
+    /// shared caches are kept under.
+    pub source: &'static str,
+    /// Review fields this source can populate.
+    pub capabilities: &'static [&'static str],
+    pub base_url: fn() -> String,
+    pub fetch_review: fn(&AlbumReviewInput) -> FetchOutcome,
+    pub fetch_rating: fn(&AlbumReviewInput) -> FetchOutcome,
+    pub fetch_review_at: fn(&str) -> FetchOutcome,
+    pub search: fn(&AlbumReviewInput) -> SearchResult,
+    pub hooks: Hooks,
+}
+
+/// Fetch several albums at once, returning their outcomes in order.
+pub type BatchFetch = fn(&[&AlbumReviewInput]) -> Vec<FetchOutcome>;
+
+/// Fetch an album's review along with other reviews of it.
+pub type EditionsFetch = fn(&AlbumReviewInput) -> (FetchOutcome, Vec<SiteReview>);
+
+/// Optional behaviour for scrapers that don't fit the common shape.
+pub struct Hooks {
+    /// Fetch a batch in one go, sharing work across its albums.
+    pub fetch_batch: Option<BatchFetch>,
+    /// Fetch other reviews of the album too, emitted after the chosen one.
+    pub fetch_editions: Option<EditionsFetch>,
+    /// Whether a "not found" is final and may be cached as a miss.
+    pub miss_is_final: fn() -> bool,
+    /// The error code of a failing site API, reported as degraded health.
+    pub api_error: fn() -> Option<String>,
+    /// Progress of a crawl cache, for `riff_cache_stats`.
+    pub cache_stats: fn() -> CacheStats,
+    /// Forget crawled review URLs, cleared as the "urls" cache.
+    pub clear_urls: Option<fn()>,
+}
+
+impl Hooks {
+    pub const DEFAULT: Hooks = Hooks {
+        fetch_batch: None,
+        fetch_editions: None,
+        miss_is_final: || true,
+        api_error: || None,
+        cache_stats: CacheStats::default,
+        clear_urls: None,
+    };
+}
+
+/// Define a scraper crate's `pub static SCRAPER` from its module's
+/// `base_url`, `fetch_review`, `fetch_rating`, `fetch_review_at` and
+/// `search`, plus any `Hooks` fields given after them, and generate the
+/// `riff_*` exports behind it (see `plugin_exports!`).
+///
+/// ```ignore
+/// editorial_common::editorial_plugin!(nme, "nme", &["rating", "excerpt"]);
+/// ```
+#[macro_export]
+macro_rules! editorial_plugin {
+    ($module:ident, $source:expr, $capabilities:expr $(, $hook:ident: $value:expr)* $(,)?) => {
+        pub static SCRAPER: $crate::Scraper = $crate::Scraper {
+            source: $source,
+            capabilities: $capabilities,
+            base_url: $module::base_url,
+            fetch_review: $module::fetch_review,
+            fetch_rating: $module::fetch_rating,
+            fetch_review_at: $module::fetch_review_at,
+            search: $module::search,
+            hooks: $crate::Hooks {
+                $($hook: $value,)*
+                ..$crate::Hooks::DEFAULT
+            },
+        };
+
+        $crate::plugin_exports! {
+            #[::extism_pdk::plugin_fn]
+            pub fn riff_plugin_info() -> ::extism_pdk::FnResult<String> {
+                Ok($crate::plugin::info(&SCRAPER, env!("CARGO_PKG_VERSION")))
+            }
+
+            #[::extism_pdk::plugin_fn]
+            pub fn riff_health_check(input: String) -> ::extism_pdk::FnResult<String> {
+                Ok($crate::plugin::health_check(&SCRAPER, &input))
+            }
+
+            #[::extism_pdk::plugin_fn]
+            pub fn riff_clear_cache(input: String) -> ::extism_pdk::FnResult<String> {
+                Ok($crate::plugin::clear_cache(&SCRAPER, &input))
+            }
+
+            #[::extism_pdk::plugin_fn]
+            pub fn riff_cache_stats() -> ::extism_pdk::FnResult<String> {
+                Ok((SCRAPER.hooks.cache_stats)().to_json())
+            }
+
+            #[::extism_pdk::plugin_fn]
+            pub fn riff_get_album_reviews(input: String) -> ::extism_pdk::FnResult<String> {
+                Ok($crate::plugin::album_reviews(&SCRAPER, &input)?)
+            }
+
+            #[::extism_pdk::plugin_fn]
+            pub fn riff_get_album_reviews_batch(input: String) -> ::extism_pdk::FnResult<String> {
+                Ok($crate::plugin::album_reviews_batch(&SCRAPER, &input)?)
+            }
+
+            #[::extism_pdk::plugin_fn]
+            pub fn riff_get_rating(input: String) -> ::extism_pdk::FnResult<String> {
+                Ok($crate::plugin::rating(&SCRAPER, &input)?)
+            }
+
+            #[::extism_pdk::plugin_fn]
+            pub fn riff_parse_review_url(input: String) -> ::extism_pdk::FnResult<String> {
+                Ok($crate::plugin::parse_review_url(&SCRAPER, &input)?)
+            }
+
+            #[::extism_pdk::plugin_fn]
+            pub fn riff_search_albums(input: String) -> ::extism_pdk::FnResult<String> {
+                Ok($crate::plugin::search(&SCRAPER, &input)?)
+            }
+        }
+    };
+}
+
+/// Emit Extism exports in a scraper crate built with its `plugin` feature.
+/// With editorial-common's `embedded` feature, set by the aggregator that
+/// links every scraper into one plugin, nothing is emitted: each scraper's
+/// `riff_*` exports would collide with the aggregator's own, even when a
+/// workspace build turns `plugin` on everywhere.
+#[cfg(not(feature = "embedded"))]
+#[macro_export]
+macro_rules! plugin_exports {
+    ($($item:item)*) => {
+        $(#[cfg(feature = "plugin")] $item)*
+    };
+}
+
+#[cfg(feature = "embedded")]
+#[macro_export]
+macro_rules! plugin_exports {
+    ($($item:item)*) => {};
+}
+
+/// `riff_plugin_info`: the source, plugin version and capabilities.
+pub fn info(scraper: &Scraper, version: &'static str) -> String {
+    let info = PluginInfo {
+        source: scraper.source,
+        version,
+        capabilities: scraper.capabilities,
+    };
+    info.to_json()
+}
+
+/// `riff_health_check`: "ok", or a deep probe of the site root. A failing
+/// site API makes the plugin degraded even when the site is reachable.
+pub fn health_check(scraper: &Scraper, input: &str) -> String {
+    let api_error = (scraper.hooks.api_error)();
+    if HealthCheckInput::parse(input).deep {
+        let mut report = probe_health(&(scraper.base_url)());
+        if api_error.is_some() {
+            report.status = "degraded";
+        }
+        return report.to_json();
+    }
+    match api_error {
+        Some(code) => format!("degraded: {}", code),
+        None => "ok".to_string(),
+    }
+}
+
+/// `riff_clear_cache`: the shared caches, plus crawled URLs if the scraper
+/// keeps them.
+pub fn clear_cache(scraper: &Scraper, input: &str) -> String {
+    let request = ClearCacheInput::parse(input);
+    let mut report = clear_caches(scraper.source, &request);
+    if let Some(clear_urls) = scraper.hooks.clear_urls {
+        if request.includes("urls") {
+            clear_urls();
+            report.cleared.push("urls");
+        }
+    }
+    report.to_json()
+}
+
+/// `riff_get_album_reviews`.
+pub fn album_reviews(scraper: &Scraper, input: &str) -> Result<String, serde_json::Error> {
+    let params: AlbumReviewInput = serde_json::from_str(input)?;
+    let mut results = reviews_for(scraper, std::slice::from_ref(&params));
+    Ok(results.remove(0))
+}
+
+/// `riff_get_album_reviews_batch`.
+pub fn album_reviews_batch(scraper: &Scraper, input: &str) -> Result<String, serde_json::Error> {
+    let batch: AlbumBatchInput = serde_json::from_str(input)?;
+    Ok(wrap_batch(&reviews_for(scraper, &batch.albums)))
+}
+
+/// Albums a source is known to have no review of.
+trait MissLog {
+    fn contains(&self, source: &str, artist: &str, title: &str) -> bool;
+    fn record(&mut self, source: &str, artist: &str, title: &str);
+}
+
+/// The miss cache kept in Extism vars.
+struct VarMisses;
+
+impl MissLog for VarMisses {
+    fn contains(&self, source: &str, artist: &str, title: &str) -> bool {
+        is_cached_miss(source, artist, title)
+    }
+
+    fn record(&mut self, source: &str, artist: &str, title: &str) {
+        record_miss(source, artist, title);
+    }
+}
+
+/// Review each album, skipping cached misses. Only a definitive "not found"
+/// is cached; blocks and errors may clear up.
+fn reviews_for(scraper: &Scraper, albums: &[AlbumReviewInput]) -> Vec<String> {
+    reviews_with(scraper, albums, &mut VarMisses)
+}
+
+fn reviews_with(
+    scraper: &Scraper,
+    albums: &[AlbumReviewInput],
+    misses: &mut impl MissLog,
+) -> Vec<String> {
+    let source = scraper.source;
+    let found = lookup_with(scraper, albums, misses);
+    albums
+        .iter()
+        .zip(found)
+        .map(|(params, (outcome, others))| {
+            let error = outcome.error_reason();
+            // The chosen review leads; other reviews of the album follow it
+            let reviews = outcome
+                .into_review()
+                .into_iter()
+                .chain(others)
+                .map(|review| params.options.apply(review))
+                .collect();
+            wrap_reviews(source, reviews, error)
+        })
+        .collect()
+}
+
+/// Each album's outcome and any other reviews of it, as `riff_get_album_reviews`
+/// finds them: cached misses are not found without a fetch, and definitive
+/// misses are recorded. For the aggregator, which asks every scraper in turn.
+pub fn lookup_albums(
+    scraper: &Scraper,
+    albums: &[AlbumReviewInput],
+) -> Vec<(FetchOutcome, Vec<SiteReview>)> {
+    lookup_with(scraper, albums, &mut VarMisses)
+}
+
+fn lookup_with(
+    scraper: &Scraper,
+    albums: &[AlbumReviewInput],
+    misses: &mut impl MissLog,
+) -> Vec<(FetchOutcome, Vec<SiteReview>)> {
+    let source = scraper.source;
+    let cached_miss: Vec<bool> = albums
+        .iter()
+        .map(|params| misses.contains(source, &params.artist, &params.title))
+        .collect();
+    let pending: Vec<&AlbumReviewInput> = albums
+        .iter()
+        .zip(&cached_miss)
+        .filter(|(_, &miss)| !miss)
+        .map(|(params, _)| params)
+        .collect();
+    let fetched: Vec<(FetchOutcome, Vec<SiteReview>)> =
+        if let Some(fetch_batch) = scraper.hooks.fetch_batch {
+            let outcomes = fetch_batch(&pending).into_iter();
+            outcomes.map(|outcome| (outcome, Vec::new())).collect()
+        } else if let Some(fetch_editions) = scraper.hooks.fetch_editions {
+            pending
+                .iter()
+                .map(|params| fetch_editions(params))
+                .collect()
+        } else {
+            let fetch = |params: &&AlbumReviewInput| ((scraper.fetch_review)(params), Vec::new());
+            pending.iter().map(fetch).collect()
+        };
+    let mut fetched = fetched.into_iter();
+    let miss_is_final = (scraper.hooks.miss_is_final)();
+
+    albums
+        .iter()
+        .zip(cached_miss)
+        .map(|(params, miss)| {
+            if miss {
+                return (FetchOutcome::NotFound, Vec::new());
+            }
+            let Some((outcome, others)) = fetched.next() else {
+                return (FetchOutcome::NotFound, Vec::new());
+            };
+            if outcome.is_not_found() && miss_is_final {
+                misses.record(source, &params.artist, &params.title);
+            }
+            (outcome, others)
+        })
+        .collect()
+}
+
+/// `riff_get_rating`.
+pub fn rating(scraper: &Scraper, input: &str) -> Result<String, serde_json::Error> {
+    let params: AlbumReviewInput = serde_json::from_str(input)?;
+    Ok(rating_with(scraper, &params, &VarMisses))
+}
+
+/// A known miss answers without fetching, but a rating "not found" isn't
+/// recorded: it may only mean the review carries no score, and must not
+/// hide the review from `riff_get_album_reviews`.
+fn rating_with(scraper: &Scraper, params: &AlbumReviewInput, misses: &impl MissLog) -> String {
+    let source = scraper.source;
+    if misses.contains(source, &params.artist, &params.title) {
+        return wrap_rating(source, None, None);
+    }
+
+    let outcome = (scraper.fetch_rating)(params);
+    let error = outcome.error_reason();
+    wrap_rating(source, outcome.into_review(), error)
+}
+
+/// `riff_parse_review_url`: the review at a URL on the scraper's site.
+pub fn parse_review_url(scraper: &Scraper, input: &str) -> Result<String, serde_json::Error> {
+    let params: ReviewUrlInput = serde_json::from_str(input)?;
+    if !same_site(&params.url, &(scraper.base_url)()) {
+        return Ok(wrap_review(scraper.source, None, Some("invalid_url")));
+    }
+
+    let outcome = (scraper.fetch_review_at)(&params.url);
+    let error = outcome.error_reason();
+    Ok(wrap_review(scraper.source, outcome.into_review(), error))
+}
+
+/// `riff_search_albums`.
+pub fn search(scraper: &Scraper, input: &str) -> Result<String, serde_json::Error> {
+    let params: AlbumReviewInput = serde_json::from_str(input)?;
+    Ok((scraper.search)(&params).to_json())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[derive(Default)]
+    struct MemoryMisses(HashSet<String>);
+
+    impl MissLog for MemoryMisses {
+        fn contains(&self, source: &str, artist: &str, title: &str) -> bool {
+            self.0.contains(&format!("{}|{}|{}", source, artist, title))
+        }
+
+        fn record(&mut self, source: &str, artist: &str, title: &str) {
+            self.0.insert(format!("{}|{}|{}", source, artist, title));
+        }
+    }
+
+    fn found(url: &str) -> FetchOutcome {
+        FetchOutcome::Found(Box::new(SiteReview::builder(url).build()))
+    }
+
+    const SCRAPER: Scraper = Scraper {
+        source: "test",
+        capabilities: &[],
+        base_url: || "https://example.com".to_string(),
+        fetch_review: |_| found("https://example.com/review"),
+        fetch_rating: |_| FetchOutcome::NotFound,
+        fetch_review_at: |_| FetchOutcome::NotFound,
+        search: |_| SearchResult::default(),
+        hooks: Hooks::DEFAULT,
+    };
+
+    fn album() -> AlbumReviewInput {
+        serde_json::from_str(r#"{"artist":"Radiohead","title":"Kid A"}"#).unwrap()
+    }
+
+    #[test]
+    fn rating_miss_leaves_reviews_uncached() {
+        let mut misses = MemoryMisses::default();
+        let rating = rating_with(&SCRAPER, &album(), &misses);
+        assert!(rating.contains(r#""rating":null"#));
+        assert!(misses.0.is_empty());
+
+        let reviews = reviews_with(&SCRAPER, &[album()], &mut misses);
+        assert!(reviews[0].contains("https://example.com/review"));
+    }
+
+    #[test]
+    fn unscored_rating_then_reviews() {
+        // Sources without scores (Bandcamp Daily, Stereogum) resolve the
+        // review for a rating call and return it unrated
+        let scraper = Scraper {
+            fetch_rating: |_| found("https://example.com/review"),
+            ..SCRAPER
+        };
+        let mut misses = MemoryMisses::default();
+        let rating = rating_with(&scraper, &album(), &misses);
+        assert!(rating.contains(r#""rating":null"#));
+        assert!(rating.contains(r#""source_url":"https://example.com/review""#));
+
+        let reviews = reviews_with(&scraper, &[album()], &mut misses);
+        assert!(reviews[0].contains("https://example.com/review"));
+        assert!(misses.0.is_empty());
+    }
+
+    #[test]
+    fn review_miss_is_recorded_and_skipped() {
+        let scraper = Scraper {
+            fetch_review: |_| FetchOutcome::NotFound,
+            fetch_rating: |_| found("https://example.com/rated"),
+            ..SCRAPER
+        };
+        let mut misses = MemoryMisses::default();
+        reviews_with(&scraper, &[album()], &mut misses);
+        assert!(misses.contains("test", "Radiohead", "Kid A"));
+
+        let rating = rating_with(&scraper, &album(), &misses);
+        assert!(!rating.contains("https://example.com/rated"));
+    }
+}
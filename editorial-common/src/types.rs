@@ -1,21 +1,121 @@
+use crate::util::{
+    canonicalize_url, match_confidence, scale_rating, score_candidate, truncate_at_sentence,
+    Candidate, Query,
+};
 use serde::{Deserialize, Serialize};
 
 /// Output format matching riff-core's expected editorial result.
 #[derive(Serialize)]
 pub struct EditorialResult {
     pub reviews: Vec<EditorialReview>,
+    /// Short machine-readable reasons a source failed (e.g. "blocked"), so an
+    /// empty `reviews` can be told apart from a genuine miss.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+    /// The combined rating across sources, from the aggregator only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregate: Option<AggregateRating>,
+}
+
+/// A rating combined from several sources' normalized (0-10) ratings.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AggregateRating {
+    pub rating: f64,
+    /// How many sources' ratings went into it.
+    pub source_count: usize,
+}
+
+/// Most a single review can weigh in an aggregate, whatever its `rating_count`.
+const MAX_REVIEW_WEIGHT: f64 = 3.0;
+
+impl AggregateRating {
+    /// The weighted mean of the reviews' ratings. A review counts once, or
+    /// `1 + log10(rating_count)` (at most `MAX_REVIEW_WEIGHT`) when votes
+    /// are behind it, so thousands of user votes on one site can't drown out
+    /// the critics. Only a source's first rated review counts; later ones
+    /// are other editions of the album. None when no review is rated.
+    pub fn of(reviews: &[EditorialReview]) -> Option<Self> {
+        let mut total = 0.0;
+        let mut weight = 0.0;
+        let mut source_count = 0;
+        let mut counted: Vec<&str> = Vec::new();
+        for review in reviews {
+            let Some(rating) = review.rating else {
+                continue;
+            };
+            if counted.contains(&review.source.as_str()) {
+                continue;
+            }
+            counted.push(&review.source);
+            let count = f64::from(review.rating_count.unwrap_or(1).max(1));
+            let review_weight = (1.0 + count.log10()).min(MAX_REVIEW_WEIGHT);
+            total += rating * review_weight;
+            weight += review_weight;
+            source_count += 1;
+        }
+        (source_count > 0).then(|| AggregateRating {
+            rating: total / weight,
+            source_count,
+        })
+    }
 }
 
 /// A single editorial review entry.
-#[derive(Serialize)]
+#[derive(Serialize, Debug, Clone, PartialEq)]
 pub struct EditorialReview {
     pub source: String,
     pub source_url: String,
     pub excerpt: Option<String>,
     pub rating: Option<f64>,
+    /// The rating's maximum on the site's own scale (e.g. 5.0 or 10.0).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating_scale: Option<f64>,
+    /// The rating as published, before normalization to 0-10.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating_raw: Option<f64>,
+    /// The letter grade as published, for sources that grade rather than
+    /// score (`rating_raw` then holds the grade's 0-10 value).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating_grade: Option<String>,
     pub rating_count: Option<u32>,
     pub reviewer: Option<String>,
     pub review_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// A distinction the source gave the album, e.g. "Universal Acclaim".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accolade: Option<String>,
+    /// The album's cover art, as the review page shows it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
+    /// How closely the matched page's slug fits the request (1.0 = exact).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_confidence: Option<f64>,
+}
+
+impl EditorialReview {
+    /// The output entry for a site-specific review from `source_name`.
+    pub fn from_site(source_name: &str, review: SiteReview) -> Self {
+        EditorialReview {
+            source: source_name.to_string(),
+            source_url: review.source_url,
+            excerpt: review.excerpt,
+            rating: review.rating,
+            rating_scale: review.rating_scale,
+            rating_raw: review.rating_raw,
+            rating_grade: review.rating_grade,
+            rating_count: review.rating_count,
+            reviewer: review.reviewer,
+            review_date: review.review_date,
+            genre: review.genre,
+            label: review.label,
+            accolade: review.accolade,
+            image_url: review.image_url,
+            match_confidence: review.match_confidence,
+        }
+    }
 }
 
 /// Input passed from the server to the plugin.
@@ -25,34 +125,733 @@ pub struct AlbumReviewInput {
     pub artist: String,
     #[serde(default)]
     pub year: Option<i32>,
+    /// MusicBrainz release-group ID, used to reject mismatched candidates.
+    #[serde(default)]
+    pub mbid: Option<String>,
+    /// Release barcode (UPC/EAN), used the same way.
+    #[serde(default)]
+    pub barcode: Option<String>,
+    /// Kind of release wanted; search candidates of other kinds are only
+    /// picked when nothing else matches.
+    #[serde(default)]
+    pub release_type: ReleaseType,
+    #[serde(default)]
+    pub options: ReviewOptions,
+}
+
+/// Kind of release a lookup is after, as told apart by slug markers like
+/// "live" or "greatest-hits".
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseType {
+    #[default]
+    Studio,
+    Live,
+    Compilation,
+    /// A later reissue, where a site reviews one separately from the
+    /// original.
+    Reissue,
+    /// Any kind of release; candidates are taken in the scraper's own order.
+    Any,
+}
+
+/// Per-call trade-offs between detail and latency. Absent fields keep the
+/// default behavior (everything included, excerpts at the scraper's own cap).
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ReviewOptions {
+    /// Maximum excerpt length in characters, cut at a sentence break.
+    pub max_excerpt: Option<usize>,
+    /// Fetch and return the review text; `false` skips body requests/parsing.
+    pub include_body: bool,
+    /// Return the rating (and its scale and count).
+    pub include_rating: bool,
+}
+
+impl Default for ReviewOptions {
+    fn default() -> Self {
+        ReviewOptions {
+            max_excerpt: None,
+            include_body: true,
+            include_rating: true,
+        }
+    }
+}
+
+impl ReviewOptions {
+    /// Drop or trim the parts of a review the caller opted out of.
+    pub fn apply(&self, mut review: SiteReview) -> SiteReview {
+        if !self.include_body {
+            review.excerpt = None;
+        }
+        if let (Some(excerpt), Some(max)) = (&review.excerpt, self.max_excerpt) {
+            review.excerpt = Some(truncate_at_sentence(excerpt, max));
+        }
+        if !self.include_rating {
+            review.rating = None;
+            review.rating_scale = None;
+            review.rating_raw = None;
+            review.rating_grade = None;
+            review.rating_count = None;
+        }
+        review
+    }
+}
+
+/// Input to `riff_parse_review_url`: a review page the host already knows.
+#[derive(Deserialize)]
+pub struct ReviewUrlInput {
+    pub url: String,
+}
+
+/// Input to `riff_get_album_reviews_batch`.
+#[derive(Deserialize)]
+pub struct AlbumBatchInput {
+    pub albums: Vec<AlbumReviewInput>,
+}
+
+/// Input to `riff_health_check`. Anything that isn't `{"deep":true}` (including
+/// an empty string) means the shallow liveness check.
+#[derive(Deserialize, Default)]
+pub struct HealthCheckInput {
+    #[serde(default)]
+    pub deep: bool,
+}
+
+impl HealthCheckInput {
+    pub fn parse(input: &str) -> Self {
+        serde_json::from_str(input).unwrap_or_default()
+    }
+}
+
+/// Deep health check result: `{"status":"ok"|"degraded","http":<code>}`.
+/// `http` is null when the site couldn't be reached at all.
+#[derive(Serialize)]
+pub struct HealthReport {
+    pub status: &'static str,
+    pub http: Option<u16>,
+}
+
+impl HealthReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| r#"{"status":"degraded"}"#.to_string())
+    }
+}
+
+/// Input to `riff_clear_cache`. `{"cache":"<name>"}` clears just that cache;
+/// anything else (including an empty string) clears every cache.
+#[derive(Deserialize, Default)]
+pub struct ClearCacheInput {
+    #[serde(default)]
+    pub cache: Option<String>,
+}
+
+impl ClearCacheInput {
+    pub fn parse(input: &str) -> Self {
+        serde_json::from_str(input).unwrap_or_default()
+    }
+
+    /// Whether the cache called `name` should be cleared.
+    pub fn includes(&self, name: &str) -> bool {
+        self.cache.as_deref().is_none_or(|cache| cache == name)
+    }
+}
+
+/// `riff_clear_cache` result: `{"cleared":[<cache names>]}`.
+#[derive(Serialize)]
+pub struct ClearCacheReport {
+    pub cleared: Vec<&'static str>,
+}
+
+impl ClearCacheReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| r#"{"cleared":[]}"#.to_string())
+    }
+}
+
+/// Input to `riff_prewarm_cache`: `{"pages":N}` advances a crawl by up to N
+/// batches. Missing or unparseable input means a single batch.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct PrewarmInput {
+    pub pages: u32,
+}
+
+impl Default for PrewarmInput {
+    fn default() -> Self {
+        PrewarmInput { pages: 1 }
+    }
+}
+
+impl PrewarmInput {
+    /// Upper bound on batches per call, so one call can't crawl indefinitely.
+    pub const MAX_PAGES: u32 = 20;
+
+    pub fn parse(input: &str) -> Self {
+        let parsed: PrewarmInput = serde_json::from_str(input).unwrap_or_default();
+        PrewarmInput {
+            pages: parsed.pages.min(Self::MAX_PAGES),
+        }
+    }
+}
+
+/// `riff_cache_stats` result: crawl progress and size of a plugin's URL
+/// cache. Plugins without a crawl cache report all zeros.
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct CacheStats {
+    pub next_page: u32,
+    /// Last listing page (0 until pagination has been seen).
+    pub max_pages: u32,
+    pub slugs_cached: usize,
+    /// Size of the serialized cache as stored.
+    pub bytes: usize,
+}
+
+impl CacheStats {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// `riff_plugin_info` result: the source a plugin scrapes, its version, and
+/// which review fields it can populate.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PluginInfo {
+    pub source: &'static str,
+    pub version: &'static str,
+    pub capabilities: &'static [&'static str],
+}
+
+impl PluginInfo {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
 }
 
 /// Intermediate result from a site-specific scraper.
+#[derive(Debug, Clone, PartialEq)]
 pub struct SiteReview {
     pub source_url: String,
     pub excerpt: Option<String>,
     pub rating: Option<f64>,
+    pub rating_scale: Option<f64>,
+    pub rating_raw: Option<f64>,
+    pub rating_grade: Option<String>,
     pub rating_count: Option<u32>,
     pub reviewer: Option<String>,
     pub review_date: Option<String>,
+    pub genre: Option<String>,
+    pub label: Option<String>,
+    pub accolade: Option<String>,
+    pub image_url: Option<String>,
+    pub match_confidence: Option<f64>,
+}
+
+impl SiteReview {
+    /// Start a review for `source_url`, without its query string or
+    /// fragment (see `canonicalize_url`); every optional field defaults to
+    /// None.
+    pub fn builder(source_url: impl Into<String>) -> SiteReviewBuilder {
+        SiteReviewBuilder {
+            review: SiteReview {
+                source_url: canonicalize_url(&source_url.into()),
+                excerpt: None,
+                rating: None,
+                rating_scale: None,
+                rating_raw: None,
+                rating_grade: None,
+                rating_count: None,
+                reviewer: None,
+                review_date: None,
+                genre: None,
+                label: None,
+                accolade: None,
+                image_url: None,
+                match_confidence: None,
+            },
+        }
+    }
+}
+
+/// Chainable construction of a `SiteReview`, so new optional fields don't
+/// require touching every scraper.
+pub struct SiteReviewBuilder {
+    review: SiteReview,
 }
 
-/// Wrap an optional site-specific review into the JSON output format.
-pub fn wrap_review(source_name: &str, review: Option<SiteReview>) -> String {
-    let mut reviews = Vec::new();
+impl SiteReviewBuilder {
+    pub fn excerpt(mut self, excerpt: Option<String>) -> Self {
+        self.review.excerpt = excerpt;
+        self
+    }
 
-    if let Some(r) = review {
-        reviews.push(EditorialReview {
-            source: source_name.to_string(),
-            source_url: r.source_url,
-            excerpt: r.excerpt,
-            rating: r.rating,
-            rating_count: r.rating_count,
-            reviewer: r.reviewer,
-            review_date: r.review_date,
-        });
+    /// Set an already-normalized (0-10) rating.
+    pub fn rating(mut self, rating: Option<f64>) -> Self {
+        self.review.rating = rating;
+        self
+    }
+
+    /// Set the rating from its published `(value, scale)`, filling in the
+    /// normalized 0-10 `rating` alongside `rating_raw` and `rating_scale`.
+    pub fn native_rating(mut self, native: Option<(f64, f64)>) -> Self {
+        self.review.rating = native.map(|(value, scale)| scale_rating(value, scale));
+        self.review.rating_raw = native.map(|(value, _)| value);
+        self.review.rating_scale = native.map(|(_, scale)| scale);
+        self
+    }
+
+    /// Set the published letter grade; the numeric rating is set separately.
+    pub fn rating_grade(mut self, rating_grade: Option<String>) -> Self {
+        self.review.rating_grade = rating_grade;
+        self
+    }
+
+    pub fn rating_count(mut self, rating_count: Option<u32>) -> Self {
+        self.review.rating_count = rating_count;
+        self
+    }
+
+    pub fn reviewer(mut self, reviewer: Option<String>) -> Self {
+        self.review.reviewer = reviewer;
+        self
+    }
+
+    pub fn review_date(mut self, review_date: Option<String>) -> Self {
+        self.review.review_date = review_date;
+        self
+    }
+
+    pub fn genre(mut self, genre: Option<String>) -> Self {
+        self.review.genre = genre;
+        self
     }
 
-    let result = EditorialResult { reviews };
+    pub fn label(mut self, label: Option<String>) -> Self {
+        self.review.label = label;
+        self
+    }
+
+    pub fn accolade(mut self, accolade: Option<String>) -> Self {
+        self.review.accolade = accolade;
+        self
+    }
+
+    pub fn image_url(mut self, image_url: Option<String>) -> Self {
+        self.review.image_url = image_url;
+        self
+    }
+
+    pub fn match_confidence(mut self, match_confidence: Option<f64>) -> Self {
+        self.review.match_confidence = match_confidence;
+        self
+    }
+
+    pub fn build(self) -> SiteReview {
+        self.review
+    }
+}
+
+/// Why a fetch produced no usable page or review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchError {
+    /// The page doesn't exist (404/410) or nothing matched the album.
+    NotFound,
+    /// The site refused the request (401/403/429); worth retrying later.
+    Blocked,
+    /// Network failure, server error, or an unreadable response.
+    Error,
+}
+
+impl FetchError {
+    /// Classify a non-200 HTTP status.
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            404 | 410 => FetchError::NotFound,
+            401 | 403 | 429 => FetchError::Blocked,
+            _ => FetchError::Error,
+        }
+    }
+}
+
+/// Result of a scraper lookup. Only `NotFound` is a definitive answer that
+/// should be cached as a miss; `Blocked` and `Error` are transient.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FetchOutcome {
+    Found(Box<SiteReview>),
+    NotFound,
+    Blocked,
+    Error,
+}
+
+impl FetchOutcome {
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, FetchOutcome::NotFound)
+    }
+
+    /// The `errors` entry for a transient failure, if any.
+    pub fn error_reason(&self) -> Option<&'static str> {
+        match self {
+            FetchOutcome::Blocked => Some("blocked"),
+            FetchOutcome::Error => Some("fetch_failed"),
+            FetchOutcome::Found(_) | FetchOutcome::NotFound => None,
+        }
+    }
+
+    /// Collapse to the optional review carried in the plugin output.
+    pub fn into_review(self) -> Option<SiteReview> {
+        match self {
+            FetchOutcome::Found(review) => Some(*review),
+            _ => None,
+        }
+    }
+}
+
+impl From<Result<SiteReview, FetchError>> for FetchOutcome {
+    fn from(result: Result<SiteReview, FetchError>) -> Self {
+        match result {
+            Ok(review) => FetchOutcome::Found(Box::new(review)),
+            Err(FetchError::NotFound) => FetchOutcome::NotFound,
+            Err(FetchError::Blocked) => FetchOutcome::Blocked,
+            Err(FetchError::Error) => FetchOutcome::Error,
+        }
+    }
+}
+
+/// A URL a scraper considered while searching for an album.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SearchCandidate {
+    pub url: String,
+    pub slug: String,
+    /// Slug overlap with the album, as reported in `match_confidence`.
+    pub score: f64,
+}
+
+impl SearchCandidate {
+    /// Score a candidate's slug against the album's artist and title slugs.
+    pub fn new(url: String, slug: String, artist_slug: &str, title_slug: &str) -> Self {
+        let score = match_confidence(&slug, artist_slug, title_slug);
+        SearchCandidate { url, slug, score }
+    }
+
+    /// Score a candidate's slug with `score_candidate`, for scrapers that
+    /// pick with `best_candidate`, so the score shown is the one ranked by.
+    pub fn scored(url: String, slug: String, query: &Query) -> Self {
+        let score = score_candidate(query, &Candidate::new(slug.as_str()));
+        SearchCandidate { url, slug, score }
+    }
+}
+
+/// Output of `riff_search_albums`: every candidate a scraper considered and
+/// the URL it would fetch, without fetching it.
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct SearchResult {
+    pub candidates: Vec<SearchCandidate>,
+    pub chosen: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+impl SearchResult {
+    /// Record a candidate, ignoring URLs already seen by an earlier query.
+    pub fn add(&mut self, candidate: SearchCandidate) {
+        if !self.candidates.iter().any(|c| c.url == candidate.url) {
+            self.candidates.push(candidate);
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let fallback = r#"{"candidates":[],"chosen":null}"#;
+        serde_json::to_string(self).unwrap_or_else(|_| fallback.to_string())
+    }
+}
+
+impl From<Result<SearchResult, FetchError>> for SearchResult {
+    fn from(result: Result<SearchResult, FetchError>) -> Self {
+        match result {
+            Ok(result) => result,
+            Err(err) => {
+                let reason = FetchOutcome::from(Err(err)).error_reason();
+                SearchResult {
+                    errors: reason.map(str::to_string).into_iter().collect(),
+                    ..SearchResult::default()
+                }
+            }
+        }
+    }
+}
+
+/// `riff_get_rating` output: the rating alone, with where it came from.
+#[derive(Serialize)]
+pub struct RatingResult {
+    pub source: String,
+    pub rating: Option<f64>,
+    pub rating_count: Option<u32>,
+    pub source_url: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Wrap the rating of an optional site-specific review (and an optional
+/// failure reason) into the `riff_get_rating` output format.
+pub fn wrap_rating(source_name: &str, review: Option<SiteReview>, error: Option<&str>) -> String {
+    let result = RatingResult {
+        source: source_name.to_string(),
+        rating: review.as_ref().and_then(|r| r.rating),
+        rating_count: review.as_ref().and_then(|r| r.rating_count),
+        source_url: review.map(|r| r.source_url),
+        errors: error.map(str::to_string).into_iter().collect(),
+    };
+    serde_json::to_string(&result).unwrap_or_else(|_| r#"{"rating":null}"#.to_string())
+}
+
+/// Wrap an optional site-specific review (and an optional failure reason)
+/// into the JSON output format.
+pub fn wrap_review(source_name: &str, review: Option<SiteReview>, error: Option<&str>) -> String {
+    wrap_reviews(source_name, review.into_iter().collect(), error)
+}
+
+/// Wrap any number of site-specific reviews (e.g. an original review and a
+/// reissue review of the same album) into the JSON output format.
+pub fn wrap_reviews(source_name: &str, reviews: Vec<SiteReview>, error: Option<&str>) -> String {
+    let reviews = reviews
+        .into_iter()
+        .map(|review| EditorialReview::from_site(source_name, review))
+        .collect();
+
+    let result = EditorialResult {
+        reviews,
+        errors: error.map(str::to_string).into_iter().collect(),
+        aggregate: None,
+    };
+    serde_json::to_string(&result).unwrap_or_else(|_| r#"{"reviews":[]}"#.to_string())
+}
+
+/// Wrap reviews from several sources (and each failing source's reason)
+/// into the JSON output format, with their ratings combined into the
+/// `aggregate`.
+pub fn wrap_aggregate(reviews: Vec<EditorialReview>, errors: Vec<String>) -> String {
+    let result = EditorialResult {
+        aggregate: AggregateRating::of(&reviews),
+        reviews,
+        errors,
+    };
     serde_json::to_string(&result).unwrap_or_else(|_| r#"{"reviews":[]}"#.to_string())
 }
+
+/// Join per-album outputs (each an `EditorialResult` JSON document, in input
+/// order) into the batch JSON array.
+pub fn wrap_batch(results: &[String]) -> String {
+    format!("[{}]", results.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_classification() {
+        assert_eq!(FetchError::from_status(404), FetchError::NotFound);
+        assert_eq!(FetchError::from_status(410), FetchError::NotFound);
+        assert_eq!(FetchError::from_status(403), FetchError::Blocked);
+        assert_eq!(FetchError::from_status(429), FetchError::Blocked);
+        assert_eq!(FetchError::from_status(503), FetchError::Error);
+        assert!(FetchOutcome::from(Err(FetchError::NotFound)).is_not_found());
+        assert!(!FetchOutcome::from(Err(FetchError::Blocked)).is_not_found());
+    }
+
+    fn site_review(url: &str) -> SiteReview {
+        SiteReview::builder(url).rating(Some(8.0)).build()
+    }
+
+    #[test]
+    fn wrap_reviews_emits_each_review() {
+        let json = wrap_reviews(
+            "site",
+            vec![site_review("https://a"), site_review("https://b")],
+            None,
+        );
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let reviews = value["reviews"].as_array().unwrap();
+        assert_eq!(reviews.len(), 2);
+        assert_eq!(reviews[1]["source_url"], "https://b");
+        assert_eq!(reviews[1]["source"], "site");
+        assert!(reviews[1].get("genre").is_none());
+        assert!(reviews[1].get("accolade").is_none());
+        assert!(reviews[1].get("image_url").is_none());
+        assert!(reviews[1].get("rating_grade").is_none());
+
+        assert_eq!(wrap_review("site", None, None), r#"{"reviews":[]}"#);
+        assert_eq!(
+            wrap_review("site", None, Some("blocked")),
+            r#"{"reviews":[],"errors":["blocked"]}"#
+        );
+    }
+
+    #[test]
+    fn aggregate_weight_grows_slowly_with_rating_count() {
+        let rated = |source: &str, rating: f64, count: Option<u32>| {
+            let review = SiteReview::builder("https://a")
+                .rating(Some(rating))
+                .rating_count(count)
+                .build();
+            EditorialReview::from_site(source, review)
+        };
+        let unrated = EditorialReview::from_site("c", SiteReview::builder("https://c").build());
+        let reviews = vec![rated("a", 8.0, None), unrated, rated("b", 6.0, Some(100))];
+        let aggregate = AggregateRating::of(&reviews).unwrap();
+        assert_eq!(aggregate.rating, 6.5);
+        assert_eq!(aggregate.source_count, 2);
+        assert_eq!(AggregateRating::of(&reviews[1..2]), None);
+
+        let json = wrap_aggregate(reviews, vec!["d: blocked".to_string()]);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["reviews"].as_array().unwrap().len(), 3);
+        assert_eq!(value["aggregate"]["rating"], 6.5);
+        assert_eq!(value["errors"][0], "d: blocked");
+
+        // Two critics against a site with thousands of user votes
+        let mixed = vec![
+            rated("critic-a", 9.0, None),
+            rated("critic-b", 8.0, None),
+            rated("users", 4.0, Some(10_000)),
+        ];
+        let aggregate = AggregateRating::of(&mixed).unwrap();
+        assert!((aggregate.rating - 5.8).abs() < 1e-9);
+        assert_eq!(aggregate.source_count, 3);
+    }
+
+    #[test]
+    fn rating_output_omits_review_text() {
+        let review = SiteReview::builder("https://a")
+            .excerpt(Some("Text".to_string()))
+            .native_rating(Some((4.0, 5.0)))
+            .rating_count(Some(12))
+            .build();
+        assert_eq!(
+            wrap_rating("site", Some(review), None),
+            r#"{"source":"site","rating":8.0,"rating_count":12,"source_url":"https://a"}"#
+        );
+        assert_eq!(
+            wrap_rating("site", None, Some("blocked")),
+            r#"{"source":"site","rating":null,"rating_count":null,"source_url":null,"errors":["blocked"]}"#
+        );
+    }
+
+    #[test]
+    fn batch_is_an_array_of_results() {
+        let input: AlbumBatchInput = serde_json::from_str(
+            r#"{"albums":[{"title":"OK Computer","artist":"Radiohead","year":1997},{"title":"Kid A","artist":"Radiohead"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(input.albums.len(), 2);
+        assert_eq!(input.albums[1].year, None);
+
+        let results = vec![
+            wrap_review("site", None, None),
+            wrap_review("site", None, None),
+        ];
+        let value: serde_json::Value = serde_json::from_str(&wrap_batch(&results)).unwrap();
+        assert_eq!(value.as_array().map(Vec::len), Some(2));
+        assert_eq!(wrap_batch(&[]), "[]");
+    }
+
+    #[test]
+    fn search_result_dedupes_candidates() {
+        let mut result = SearchResult::default();
+        for url in ["https://a", "https://a", "https://b"] {
+            let (url, slug) = (url.to_string(), "radiohead-kid-a".to_string());
+            result.add(SearchCandidate::new(url, slug, "radiohead", "kid-a"));
+        }
+        result.chosen = Some("https://a".to_string());
+        assert_eq!(result.candidates.len(), 2);
+        assert_eq!(result.candidates[0].score, 1.0);
+        let value: serde_json::Value = serde_json::from_str(&result.to_json()).unwrap();
+        assert_eq!(value["chosen"], "https://a");
+        assert_eq!(value["candidates"][1]["slug"], "radiohead-kid-a");
+        assert!(value.get("errors").is_none());
+
+        let blocked = SearchResult::from(Err(FetchError::Blocked));
+        assert_eq!(
+            blocked.to_json(),
+            r#"{"candidates":[],"chosen":null,"errors":["blocked"]}"#
+        );
+        let missed = SearchResult::from(Err(FetchError::NotFound));
+        assert_eq!(missed, SearchResult::default());
+    }
+
+    #[test]
+    fn builder_normalizes_native_rating() {
+        let review = SiteReview::builder("https://a")
+            .native_rating(Some((4.0, 5.0)))
+            .build();
+        assert_eq!(review.rating, Some(8.0));
+        assert_eq!(review.rating_raw, Some(4.0));
+        assert_eq!(review.rating_scale, Some(5.0));
+
+        let review = SiteReview::builder("https://a")
+            .native_rating(Some((8.3, 10.0)))
+            .build();
+        assert_eq!(review.rating, Some(8.3));
+        assert_eq!(review.excerpt, None);
+    }
+
+    #[test]
+    fn options_default_to_full_review() {
+        let input: AlbumReviewInput =
+            serde_json::from_str(r#"{"title":"Kid A","artist":"Radiohead"}"#).unwrap();
+        assert_eq!(input.options, ReviewOptions::default());
+        let review = SiteReview::builder("https://a")
+            .excerpt(Some("First. Second sentence here.".to_string()))
+            .native_rating(Some((4.0, 5.0)))
+            .build();
+        assert_eq!(input.options.apply(review.clone()), review);
+
+        let input: AlbumReviewInput = serde_json::from_str(
+            r#"{"title":"Kid A","artist":"Radiohead","options":{"max_excerpt":10,"include_rating":false}}"#,
+        )
+        .unwrap();
+        assert!(input.options.include_body);
+        let trimmed = input.options.apply(review);
+        assert_eq!(trimmed.excerpt.as_deref(), Some("First."));
+        assert_eq!((trimmed.rating, trimmed.rating_scale), (None, None));
+    }
+
+    #[test]
+    fn plugin_info_json() {
+        let info = PluginInfo {
+            source: "site",
+            version: "1.2.3",
+            capabilities: &["rating", "excerpt"],
+        };
+        assert_eq!(
+            info.to_json(),
+            r#"{"source":"site","version":"1.2.3","capabilities":["rating","excerpt"]}"#
+        );
+    }
+
+    #[test]
+    fn clear_cache_input_selects_caches() {
+        let all = ClearCacheInput::parse("");
+        assert!(all.includes("misses") && all.includes("responses"));
+        let one = ClearCacheInput::parse(r#"{"cache":"misses"}"#);
+        assert!(one.includes("misses"));
+        assert!(!one.includes("responses"));
+    }
+
+    #[test]
+    fn prewarm_input_is_bounded() {
+        assert_eq!(PrewarmInput::parse("").pages, 1);
+        assert_eq!(PrewarmInput::parse("{}").pages, 1);
+        assert_eq!(PrewarmInput::parse(r#"{"pages":3}"#).pages, 3);
+        let capped = PrewarmInput::parse(r#"{"pages":500}"#);
+        assert_eq!(capped.pages, PrewarmInput::MAX_PAGES);
+    }
+
+    #[test]
+    fn health_input_defaults_to_shallow() {
+        assert!(HealthCheckInput::parse(r#"{"deep":true}"#).deep);
+        assert!(!HealthCheckInput::parse(r#"{"deep":false}"#).deep);
+        assert!(!HealthCheckInput::parse("").deep);
+        assert!(!HealthCheckInput::parse("ping").deep);
+    }
+}
@@ -1,3 +1,7 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::types::{AlbumReviewInput, ReleaseType};
+
 /// Simple URL encoding for query parameters.
 pub fn url_encode(s: &str) -> String {
     let mut result = String::with_capacity(s.len() * 3);
@@ -17,9 +21,10 @@ pub fn url_encode(s: &str) -> String {
 }
 
 /// Strip trailing parenthetical suffixes like "(Deluxe Edition)", "(Dolby Atmos)", etc.
+/// A title that is nothing but a parenthetical is returned unchanged.
 pub fn clean_title(title: &str) -> &str {
     match title.rfind('(') {
-        Some(pos) if pos > 0 => title[..pos].trim_end(),
+        Some(pos) if !title[..pos].trim().is_empty() => title[..pos].trim_end(),
         _ => title,
     }
 }
@@ -52,3 +57,653 @@ pub fn slugify(s: &str) -> String {
     }
     collapsed.trim_matches('-').to_string()
 }
+
+/// Confidence (0.0-1.0) that a candidate URL slug is the requested album.
+/// The slug is compared against both the title slug and the
+/// `artist-title` slug, since sites differ on whether the artist is included;
+/// an exact match on either scores 1.0, otherwise the best token overlap
+/// (shared hyphen-separated words over all distinct words) is used.
+pub fn match_confidence(candidate_slug: &str, artist_slug: &str, title_slug: &str) -> f64 {
+    let combined = format!("{}-{}", artist_slug, title_slug);
+    [title_slug, combined.as_str()]
+        .iter()
+        .filter(|expected| !expected.is_empty())
+        .map(|expected| token_overlap(candidate_slug, expected))
+        .fold(0.0, f64::max)
+}
+
+/// Slug phrases marking a live recording.
+const LIVE_MARKERS: &[&str] = &["live", "unplugged", "in-concert"];
+
+/// Slug phrases marking a compilation.
+const COMPILATION_MARKERS: &[&str] = &[
+    "greatest-hits",
+    "best-of",
+    "compilation",
+    "anthology",
+    "retrospective",
+    "rarities",
+    "b-sides",
+];
+
+/// Slug phrases marking a reissue of an earlier album.
+const REISSUE_MARKERS: &[&str] = &[
+    "reissue",
+    "remaster",
+    "remastered",
+    "anniversary-edition",
+    "expanded-edition",
+];
+
+/// The kind of release a candidate slug is for, judged by its live,
+/// compilation and reissue markers. Markers that are part of the requested title (e.g.
+/// "live" in "live-through-this") don't count.
+pub fn slug_release_type(candidate_slug: &str, title_slug: &str) -> ReleaseType {
+    let padded = |slug: &str| format!("-{}-", slug);
+    let (candidate, title) = (padded(candidate_slug), padded(title_slug));
+    let marked = |markers: &[&str]| {
+        markers.iter().any(|marker| {
+            let marker = padded(marker);
+            candidate.contains(&marker) && !title.contains(&marker)
+        })
+    };
+    if marked(LIVE_MARKERS) {
+        ReleaseType::Live
+    } else if marked(COMPILATION_MARKERS) {
+        ReleaseType::Compilation
+    } else if marked(REISSUE_MARKERS) {
+        ReleaseType::Reissue
+    } else {
+        ReleaseType::Studio
+    }
+}
+
+/// Move the candidates of the wanted release type ahead of the rest, keeping
+/// each group's order, and return how many lead. Scrapers pick from that
+/// leading group first, so a live album or compilation whose slug happens to
+/// overlap the title more closely only wins when no studio album matches.
+pub fn prefer_release_type<T>(
+    candidates: &mut [T],
+    wanted: ReleaseType,
+    title_slug: &str,
+    slug_of: impl Fn(&T) -> Option<String>,
+) -> usize {
+    if wanted == ReleaseType::Any {
+        return candidates.len();
+    }
+    let is_wanted = |candidate: &T| {
+        let slug = slug_of(candidate).unwrap_or_default();
+        slug_release_type(&slug, title_slug) == wanted
+    };
+    candidates.sort_by_cached_key(|candidate| !is_wanted(candidate));
+    candidates.iter().take_while(|c| is_wanted(c)).count()
+}
+
+/// Lowest `score_candidate` score a search candidate can be picked with:
+/// every word of the title (give or take one of a long title) in a slug not
+/// padded out with unrelated words.
+pub const MIN_MATCH_SCORE: f64 = 0.6;
+
+/// A `score_candidate` score no candidate can beat: every title and artist
+/// word, nothing unrelated, and no year against it. A slug that only
+/// contains the title among other words never reaches it.
+pub const DEFINITIVE_MATCH_SCORE: f64 = 1.0;
+
+/// Part of a candidate's score kept however few of the artist's words, or
+/// however many unrelated words, its slug has.
+const ARTIST_FLOOR: f64 = 0.75;
+const LENGTH_FLOOR: f64 = 0.4;
+
+/// Slug words that say nothing about which album a page is for.
+const FILLER_TOKENS: &[&str] = &["album", "review", "reviews"];
+
+/// What a lookup is after, for ranking search candidates.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Query {
+    pub artist_slug: String,
+    /// Other spellings of the artist's name; see `artist_aliases`.
+    pub artist_aliases: Vec<String>,
+    pub title_slug: String,
+    pub year: Option<i32>,
+    pub release: ReleaseType,
+}
+
+impl Query {
+    /// A query for any year of a studio album, from the artist and the title
+    /// (which is cleaned of edition suffixes first).
+    pub fn new(artist: &str, title: &str) -> Self {
+        Query {
+            artist_slug: slugify(artist),
+            artist_aliases: artist_aliases(artist),
+            title_slug: slugify(clean_title(title)),
+            ..Query::default()
+        }
+    }
+
+    pub fn from_input(input: &AlbumReviewInput) -> Self {
+        Query {
+            year: input.year,
+            release: input.release_type,
+            ..Query::new(&input.artist, &input.title)
+        }
+    }
+
+    /// Whether the album is named after the artist ("Weezer" by Weezer).
+    pub fn self_titled(&self) -> bool {
+        !self.title_slug.is_empty() && self.title_slug == self.artist_slug
+    }
+}
+
+/// Other slugs sites give an artist's name: without a leading "The", and
+/// with "&" spelled out as "and" or left out. "The Beatles" -> ["beatles"];
+/// "Earth, Wind & Fire" -> ["earth-wind-and-fire"]. The artist's own slug
+/// isn't included. No "The" is ever added, as it would let any slug that
+/// starts with one pass for the artist.
+pub fn artist_aliases(artist: &str) -> Vec<String> {
+    let slug = slugify(artist);
+    let spelled = slugify(&artist.replace('&', " and "));
+    let dropped = spelled
+        .split('-')
+        .filter(|token| *token != "and")
+        .collect::<Vec<_>>()
+        .join("-");
+
+    let mut aliases: Vec<String> = Vec::new();
+    for form in [&slug, &spelled, &dropped] {
+        let bare = form.strip_prefix("the-").unwrap_or(form);
+        for alias in [form.as_str(), bare] {
+            if !alias.is_empty() && alias != slug && !aliases.iter().any(|a| a == alias) {
+                aliases.push(alias.to_string());
+            }
+        }
+    }
+    aliases
+}
+
+/// The text to search a site for: "artist title", or just the artist for a
+/// self-titled album, which would otherwise search for the name twice.
+pub fn search_terms(artist: &str, title: &str) -> String {
+    let title_slug = slugify(title);
+    if !title_slug.is_empty() && title_slug == slugify(artist) {
+        artist.to_string()
+    } else {
+        format!("{} {}", artist, title)
+    }
+}
+
+/// A search result as the shared scorer sees it: the slug naming its artist
+/// and album, and its release year where the site lists one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub slug: String,
+    pub year: Option<i32>,
+}
+
+impl Candidate {
+    pub fn new(slug: impl Into<String>) -> Self {
+        Candidate {
+            slug: slug.into(),
+            year: None,
+        }
+    }
+}
+
+/// Score (0.0-1.0) how well a candidate's slug fits the query. The share
+/// of title words the slug contains counts twice over, so a partial title
+/// falls well short; a slug missing the artist keeps three quarters of its
+/// score, and one padded with unrelated words ("deluxe", "the-50-best-...")
+/// loses up to 60%. The artist may go by any of the query's aliases, and the
+/// best-fitting one counts. A year off from the query's scales the score
+/// down further; unknown years are neutral.
+pub fn score_candidate(query: &Query, candidate: &Candidate) -> f64 {
+    let year = match (query.year, candidate.year) {
+        (Some(wanted), Some(year)) => match (wanted - year).abs() {
+            0 => 1.0,
+            1 => 0.9,
+            _ => 0.6,
+        },
+        _ => 1.0,
+    };
+
+    let artists = std::iter::once(&query.artist_slug).chain(&query.artist_aliases);
+    let best = artists
+        .map(|artist_slug| score_slug(query, artist_slug, &candidate.slug))
+        .fold(0.0, f64::max);
+    best * year
+}
+
+/// `score_candidate` for one spelling of the artist, leaving out the year.
+fn score_slug(query: &Query, artist_slug: &str, slug: &str) -> f64 {
+    let title_tokens = distinct_tokens(&query.title_slug);
+    let artist_tokens = distinct_tokens(artist_slug);
+    let tokens = distinct_tokens(slug);
+    if title_tokens.is_empty() {
+        return 0.0;
+    }
+
+    let share = |wanted: &[&str]| {
+        let found = wanted.iter().filter(|t| tokens.contains(t)).count();
+        found as f64 / wanted.len() as f64
+    };
+    let title = share(&title_tokens);
+    let artist = if artist_tokens.is_empty() {
+        1.0
+    } else {
+        share(&artist_tokens)
+    };
+
+    let named = |t: &&str| title_tokens.contains(t) || artist_tokens.contains(t);
+    let relevant = tokens.iter().filter(|t| named(t)).count();
+    let extra = tokens
+        .iter()
+        .filter(|t| !named(t) && !FILLER_TOKENS.contains(t))
+        .count();
+    // A self-titled album's slug is the bare name, so a slug that adds words
+    // to it names another of the artist's albums
+    if query.self_titled() && extra > 0 {
+        return 0.0;
+    }
+    let length = if relevant == 0 {
+        0.0
+    } else {
+        relevant as f64 / (relevant + extra) as f64
+    };
+
+    let artist_factor = ARTIST_FLOOR + (1.0 - ARTIST_FLOOR) * artist;
+    let length_factor = LENGTH_FLOOR + (1.0 - LENGTH_FLOOR) * length;
+    title * title * artist_factor * length_factor
+}
+
+/// The best-scoring item whose candidate clears `MIN_MATCH_SCORE`, the
+/// earliest on a tie. Items of the query's release type rank above all
+/// others, so another kind of release is only picked when nothing else
+/// matches. `candidate_of` returns None for items that aren't candidates.
+/// The scan stops at the first wanted item with `DEFINITIVE_MATCH_SCORE`.
+pub fn best_candidate<'a, T>(
+    query: &Query,
+    items: &'a [T],
+    candidate_of: impl Fn(&T) -> Option<Candidate>,
+) -> Option<&'a T> {
+    let mut best: Option<((bool, f64), &T)> = None;
+    for item in items {
+        let Some(candidate) = candidate_of(item) else {
+            continue;
+        };
+        let score = score_candidate(query, &candidate);
+        if score < MIN_MATCH_SCORE {
+            continue;
+        }
+        let kind = slug_release_type(&candidate.slug, &query.title_slug);
+        let wanted = query.release == ReleaseType::Any || kind == query.release;
+        if wanted && score >= DEFINITIVE_MATCH_SCORE {
+            return Some(item);
+        }
+        let rank = (wanted, score);
+        if best.as_ref().is_none_or(|(best_rank, _)| rank > *best_rank) {
+            best = Some((rank, item));
+        }
+    }
+    best.map(|(_, item)| item)
+}
+
+/// Jaccard similarity of the hyphen-separated tokens of two slugs.
+fn token_overlap(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let a_tokens = distinct_tokens(a);
+    let b_tokens = distinct_tokens(b);
+    let shared = a_tokens.iter().filter(|t| b_tokens.contains(t)).count();
+    let union = a_tokens.len() + b_tokens.len() - shared;
+    if union == 0 {
+        return 0.0;
+    }
+    shared as f64 / union as f64
+}
+
+/// The distinct hyphen-separated tokens of a slug, sorted.
+fn distinct_tokens(slug: &str) -> Vec<&str> {
+    let mut tokens: Vec<&str> = slug.split('-').filter(|t| !t.is_empty()).collect();
+    tokens.sort_unstable();
+    tokens.dedup();
+    tokens
+}
+
+/// Scale a rating published out of `max` to 0-10. Ten-point values are kept
+/// exact rather than round-tripped through division.
+pub fn scale_rating(value: f64, max: f64) -> f64 {
+    if max == 10.0 {
+        value
+    } else {
+        value / max * 10.0
+    }
+}
+
+/// Scale a bare rating ("7") is assumed to be out of.
+const DEFAULT_RATING_MAX: f64 = 10.0;
+
+/// Parse a rating written as "N/M", "N out of M" or a bare "N" (taken as out
+/// of 10) and scale it to 0-10. Numbers are plain digits with at most a
+/// two-digit fraction ("7.5", not "7.", ".5" or "1e1"); a value above its
+/// scale is rejected.
+pub fn parse_fractional_rating(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let (value, max) = match text.split_once('/') {
+        Some((value, max)) => (value, Some(max)),
+        None => match text.to_ascii_lowercase().find(" out of ") {
+            Some(pos) => (&text[..pos], Some(&text[pos + " out of ".len()..])),
+            None => (text, None),
+        },
+    };
+    let value = parse_rating_number(value.trim())?;
+    let max = match max {
+        Some(max) => parse_rating_number(max.trim()).filter(|max| *max > 0.0)?,
+        None => DEFAULT_RATING_MAX,
+    };
+    (value <= max).then(|| scale_rating(value, max))
+}
+
+/// Digits with at most a two-digit fraction, e.g. "8", "7.5", "100".
+fn parse_rating_number(text: &str) -> Option<f64> {
+    let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+    let is_digits = |s: &str, max: usize| s.len() <= max && s.bytes().all(|b| b.is_ascii_digit());
+    if whole.is_empty() || !is_digits(whole, 3) || !is_digits(fraction, 2) || text.ends_with('.') {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Longest excerpt, in characters, a scraper returns.
+pub const MAX_EXCERPT_CHARS: usize = 2000;
+
+/// Cut `text` to at most `max_chars` characters, preferring to end at the last
+/// sentence break (". ") and otherwise appending "...". Counts characters, not
+/// bytes, so multibyte text is never split mid-character.
+pub fn truncate_at_sentence(text: &str, max_chars: usize) -> String {
+    let Some((cut, _)) = text.char_indices().nth(max_chars) else {
+        return text.to_string();
+    };
+    let head = &text[..cut];
+    match head.rfind(". ") {
+        Some(pos) => head[..=pos].to_string(),
+        None => format!("{}...", head),
+    }
+}
+
+/// Current Unix time in seconds (0 if the host clock is unavailable).
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Extract the host (without port) from an absolute URL.
+/// "https://www.allmusic.com/album/x" -> "www.allmusic.com"
+pub fn url_host(url: &str) -> Option<&str> {
+    let (_, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    if host.is_empty() {
+        None
+    } else {
+        Some(host)
+    }
+}
+
+/// Whether `url` is on the same site as `base`, ignoring a leading "www.".
+pub fn same_site(url: &str, base: &str) -> bool {
+    let site = |u| url_host(u).map(|host| host.trim_start_matches("www.").to_ascii_lowercase());
+    match (site(url), site(base)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// A review URL without its query string or fragment, so links picked up
+/// from search results and share buttons ("?utm_source=...", "#comments")
+/// all report the same page. Every source's reviews live at plain paths; a
+/// site that needs a query parameter to reach a review would have to build
+/// its `SiteReview` around that.
+pub fn canonicalize_url(url: &str) -> String {
+    let end = url.find(['?', '#']).unwrap_or(url.len());
+    url[..end].to_string()
+}
+
+/// The `index`th path segment after `/{section}/` in a URL.
+pub fn path_segment<'a>(url: &'a str, section: &str, index: usize) -> Option<&'a str> {
+    let marker = format!("/{}/", section);
+    let rest = &url[url.find(&marker)? + marker.len()..];
+    rest.split(['/', '?', '#'])
+        .nth(index)
+        .filter(|segment| !segment.is_empty())
+}
+
+/// Month name prefixes, January first, as used by `normalize_date`.
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// Normalize a published date to "YYYY-MM-DD". Accepts ISO 8601 dates and
+/// timestamps, and written dates in either order ("Jul 11, 2025",
+/// "11th July 2025"), ignoring surrounding words such as "Published".
+pub fn normalize_date(text: &str) -> Option<String> {
+    let text = text.trim();
+    let iso = text.get(..10).filter(|date| {
+        date.char_indices().all(|(i, c)| match i {
+            4 | 7 => c == '-',
+            _ => c.is_ascii_digit(),
+        })
+    });
+    if let Some(date) = iso {
+        return Some(date.to_string());
+    }
+
+    let (mut year, mut month, mut day) = (None, None, None);
+    for word in text.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let lower = word.to_ascii_lowercase();
+        let digits = ["st", "nd", "rd", "th"]
+            .iter()
+            .find_map(|suffix| lower.strip_suffix(suffix))
+            .filter(|digits| !digits.is_empty())
+            .unwrap_or(&lower);
+        let number: Option<u32> = digits.parse().ok();
+        if let Some(n) = number.filter(|_| digits.len() == 4) {
+            year = year.or(Some(n));
+        } else if let Some(n) = number.filter(|n| (1..=31).contains(n)) {
+            day = day.or(Some(n));
+        } else if word.len() >= 3 {
+            let index = MONTHS.iter().position(|m| lower.starts_with(m));
+            month = month.or(index.map(|i| i as u32 + 1));
+        }
+    }
+    Some(format!("{:04}-{:02}-{:02}", year?, month?, day?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_title_keeps_parenthetical_only_titles() {
+        assert_eq!(clean_title("Blue (Deluxe Edition)"), "Blue");
+        let leading = "(What's the Story) Morning Glory?";
+        assert_eq!(clean_title(leading), leading);
+        assert_eq!(clean_title("(Untitled)"), "(Untitled)");
+        assert_eq!(clean_title("  (Untitled)"), "  (Untitled)");
+        assert_eq!(slugify(clean_title("()")), "");
+    }
+
+    #[test]
+    fn confidence_from_slug_overlap() {
+        let score = |slug| match_confidence(slug, "radiohead", "ok-computer");
+        assert_eq!(score("ok-computer"), 1.0);
+        assert_eq!(score("radiohead-ok-computer"), 1.0);
+        let reissue = score("ok-computer-oknotok-1997-2017");
+        assert!(reissue > 0.0 && reissue < 1.0);
+        assert_eq!(score("kid-a"), 0.0);
+        // Repeating a matched word doesn't raise the score
+        assert_eq!(score("ok-computer-review-ok"), score("ok-computer-review"));
+    }
+
+    #[test]
+    fn live_albums_and_compilations_deprioritized() {
+        let mut slugs = vec![
+            "wet-leg-moisturizer-live-at-brixton".to_string(),
+            "wet-leg-greatest-hits".to_string(),
+            "wet-leg-moisturizer-deluxe-edition-album-review".to_string(),
+        ];
+        // The live album overlaps the title more closely than the studio one
+        let score = |slug: &str| match_confidence(slug, "wet-leg", "moisturizer");
+        assert!(score(&slugs[0]) > score(&slugs[2]));
+
+        let studio = prefer_release_type(&mut slugs, ReleaseType::Studio, "moisturizer", |s| {
+            Some(s.clone())
+        });
+        assert_eq!(studio, 1);
+        assert_eq!(slugs[0], "wet-leg-moisturizer-deluxe-edition-album-review");
+        assert_eq!(slugs[1], "wet-leg-moisturizer-live-at-brixton");
+
+        let live = prefer_release_type(&mut slugs, ReleaseType::Live, "moisturizer", |s| {
+            Some(s.clone())
+        });
+        assert_eq!(live, 1);
+        assert_eq!(slugs[0], "wet-leg-moisturizer-live-at-brixton");
+        let any = prefer_release_type(&mut slugs, ReleaseType::Any, "moisturizer", |s| {
+            Some(s.clone())
+        });
+        assert_eq!(any, 3);
+
+        // A marker in the requested title itself is not held against a candidate
+        let kind = slug_release_type("hole-live-through-this", "live-through-this");
+        assert_eq!(kind, ReleaseType::Studio);
+        let kind = slug_release_type("radiohead-ok-computer-oknotok-reissue", "ok-computer");
+        assert_eq!(kind, ReleaseType::Reissue);
+    }
+
+    #[test]
+    fn candidates_ranked_by_shared_score() {
+        let query = Query::new("Radiohead", "OK Computer");
+        let score = |slug: &str| score_candidate(&query, &Candidate::new(slug));
+        assert_eq!(score("radiohead-ok-computer"), 1.0);
+        assert!(score("radiohead-ok-computer") > score("radiohead-ok-computer-oknotok"));
+        assert!(score("ok-computer") > score("radiohead-kid-a-ok"));
+        assert!(score("radiohead-ok") < MIN_MATCH_SCORE);
+        assert_eq!(score("radiohead-album-review-ok-computer"), 1.0);
+
+        let slugs = [
+            "radiohead-kid-a",
+            "radiohead-ok-computer-oknotok",
+            "radiohead-ok-computer",
+        ];
+        let best = best_candidate(&query, &slugs, |slug| Some(Candidate::new(*slug)));
+        assert_eq!(best, Some(&"radiohead-ok-computer"));
+        let none = best_candidate(&query, &slugs[..1], |slug| Some(Candidate::new(*slug)));
+        assert_eq!(none, None);
+
+        // Nothing is scored past a definitive match
+        let scored = std::cell::Cell::new(0);
+        let slugs = ["radiohead-ok-computer", "radiohead-ok-computer-review"];
+        let first = best_candidate(&query, &slugs, |slug| {
+            scored.set(scored.get() + 1);
+            Some(Candidate::new(*slug))
+        });
+        assert_eq!((first, scored.get()), (Some(&slugs[0]), 1));
+
+        let dated = Query {
+            year: Some(1997),
+            ..query
+        };
+        let reissue = Candidate {
+            year: Some(2017),
+            ..Candidate::new("radiohead-ok-computer")
+        };
+        assert!(score_candidate(&dated, &reissue) < 1.0);
+    }
+
+    #[test]
+    fn artist_aliases_tried_when_scoring() {
+        assert_eq!(artist_aliases("The Beatles"), ["beatles"]);
+        let ampersand = artist_aliases("Earth, Wind & Fire");
+        assert_eq!(ampersand, ["earth-wind-and-fire"]);
+        assert_eq!(artist_aliases("Earth Wind and Fire"), ["earth-wind-fire"]);
+
+        let score = |artist: &str, slug: &str| {
+            score_candidate(&Query::new(artist, "I Am"), &Candidate::new(slug))
+        };
+        assert_eq!(score("The Beatles", "beatles-i-am"), 1.0);
+        assert!(score("Beatles", "the-beatles-i-am") > MIN_MATCH_SCORE);
+        assert_eq!(score("Earth, Wind & Fire", "earth-wind-and-fire-i-am"), 1.0);
+        assert_eq!(score("Earth Wind and Fire", "earth-wind-fire-i-am"), 1.0);
+        assert!(score("The Beatles", "beatles-tribute-i-am") < 1.0);
+    }
+
+    #[test]
+    fn ratings_scaled_to_ten() {
+        assert_eq!(scale_rating(3.5, 5.0), 7.0);
+        assert_eq!(scale_rating(8.3, 10.0), 8.3);
+        assert_eq!(scale_rating(85.0, 100.0), 8.5);
+    }
+
+    #[test]
+    fn fractional_ratings() {
+        assert_eq!(parse_fractional_rating("4/5"), Some(8.0));
+        assert_eq!(parse_fractional_rating("8/10"), Some(8.0));
+        assert_eq!(parse_fractional_rating("8 / 10"), Some(8.0));
+        assert_eq!(parse_fractional_rating("3.5 out of 5"), Some(7.0));
+        assert_eq!(parse_fractional_rating("85/100"), Some(8.5));
+        assert_eq!(parse_fractional_rating("7"), Some(7.0));
+        for text in ["6/5", "4/0", "11", "7.", "1e1", "four/5", "05:32"] {
+            assert_eq!(parse_fractional_rating(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn truncation_prefers_sentence_break() {
+        assert_eq!(truncate_at_sentence("Short.", 10), "Short.");
+        assert_eq!(truncate_at_sentence("One. Two three four.", 12), "One.");
+        let accented = truncate_at_sentence("Caf\u{e9} caf\u{e9} caf\u{e9}", 6);
+        assert_eq!(accented, "Caf\u{e9} c...");
+    }
+
+    #[test]
+    fn host_from_url() {
+        let album = "https://www.allmusic.com/album/x-mw1";
+        assert_eq!(url_host(album), Some("www.allmusic.com"));
+        assert_eq!(url_host("https://pitchfork.com?q=1"), Some("pitchfork.com"));
+        assert_eq!(url_host("http://localhost:8080/albums"), Some("localhost"));
+        assert_eq!(url_host("/albums/foo"), None);
+    }
+
+    #[test]
+    fn dates_normalized_to_iso() {
+        let date = |text| normalize_date(text).unwrap_or_default();
+        assert_eq!(date("2025-07-11T09:00:00-04:00"), "2025-07-11");
+        assert_eq!(date("Published Jul 11, 2025"), "2025-07-11");
+        assert_eq!(date("July 4, 2025"), "2025-07-04");
+        assert_eq!(date(" 3 Sept. 2024 "), "2024-09-03");
+        assert_eq!(date("Tuesday, 3rd June 2008"), "2008-06-03");
+        assert_eq!(date("August 21st, 2007"), "2007-08-21");
+        assert_eq!(date("Jul 2025"), "");
+        assert_eq!(date("yesterday"), "");
+    }
+
+    #[test]
+    fn same_site_ignores_www() {
+        let base = "https://www.allmusic.com";
+        assert!(same_site("https://allmusic.com/album/x-mw1", base));
+        assert!(same_site("https://www.AllMusic.com/album/x-mw1", base));
+        assert!(!same_site("https://pitchfork.com/reviews/albums/x/", base));
+        assert!(!same_site("/album/x-mw1", base));
+    }
+
+    #[test]
+    fn tracking_parameters_and_fragments_dropped() {
+        let url = "https://pitchfork.com/reviews/albums/wet-leg-moisturizer/";
+        let tracked = format!("{}?utm_source=twitter&utm_medium=social", url);
+        assert_eq!(canonicalize_url(&tracked), url);
+        assert_eq!(canonicalize_url(&format!("{}#comments", url)), url);
+        assert_eq!(canonicalize_url(url), url);
+    }
+}
@@ -0,0 +1,235 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, element_text, fetch_page,
+    match_confidence, meta_content, normalize_date, paragraph_text, release_ids_conflict,
+    search_terms, site_links, slugify, truncate_at_sentence, url_encode, AlbumReviewInput,
+    Candidate, FetchError, FetchOutcome, Query, SearchCandidate, SearchResult, SiteReview,
+    MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `exclaim_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://exclaim.ca";
+const BASE_URL_CONFIG: &str = "exclaim_base_url";
+
+/// Exclaim! scores albums out of ten.
+const SCORE_SCALE: f64 = 10.0;
+
+/// Class of the dedicated rating element; Exclaim! publishes no JSON-LD
+/// rating.
+const SCORE_MARKER: &str = "class=\"article-rating";
+
+/// Class of the byline element.
+const AUTHOR_MARKER: &str = "class=\"article-author";
+
+/// Class of the element holding the "Published Jul 11, 2025" line.
+const DATE_MARKER: &str = "class=\"article-published";
+
+/// Class of the element holding the review text.
+const BODY_MARKER: &str = "class=\"article-body";
+
+/// The site root, honoring the `exclaim_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch an Exclaim! review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its score, skipping the review text,
+/// author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known Exclaim! URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let score = parse_score(&body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((score, SCORE_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the review URLs Exclaim!'s search offers for an album and the one
+/// `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Exclaim! for "artist title" and keep every album review it lists
+/// along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/search?query={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+
+    let urls: Vec<String> = site_links(&html, &base, "music")
+        .into_iter()
+        .filter(|url| review_slug(url).is_some())
+        .collect();
+    let mut result = SearchResult {
+        chosen: pick_review_url(&urls, &album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    Ok(result)
+}
+
+/// The best-scoring review for the album; see `best_candidate`.
+fn pick_review_url(urls: &[String], query: &Query) -> Option<String> {
+    best_candidate(query, urls, |url| review_slug(url).map(Candidate::new)).cloned()
+}
+
+/// The album slug of a review article URL, whose slug Exclaim! ends with
+/// "-album-review": "/music/article/wet-leg-moisturizer-album-review" ->
+/// "wet-leg-moisturizer". None for news, interviews and other articles.
+fn review_slug(url: &str) -> Option<String> {
+    let path = url.split_once("/music/article/")?.1;
+    let slug = slugify(path.split(['/', '?', '#']).next()?);
+    let album = slug.strip_suffix("-album-review")?;
+    Some(album.to_string()).filter(|album| !album.is_empty())
+}
+
+/// Parse an Exclaim! review page for the score (out of 10) and the review's
+/// text, author and date from its article markup.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let score = parse_score(html);
+    let excerpt = parse_review_body(html);
+    if score.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    let reviewer = element_text(html, AUTHOR_MARKER)
+        .map(|byline| byline.trim_start_matches("By ").trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| meta_content(html, "author"));
+    let review_date = element_text(html, DATE_MARKER)
+        .and_then(|text| normalize_date(&text))
+        .or_else(|| meta_content(html, "article:published_time").and_then(|d| normalize_date(&d)));
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(score.map(|s| (s, SCORE_SCALE)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The score in the rating element ("8" or "8/10"), accepted only within
+/// 0-10.
+fn parse_score(html: &str) -> Option<f64> {
+    let text = element_text(html, SCORE_MARKER)?;
+    let score: f64 = text.split('/').next()?.trim().parse().ok()?;
+    Some(score).filter(|score| (0.0..=SCORE_SCALE).contains(score))
+}
+
+/// The review's paragraphs, cut to ~2000 chars at a sentence boundary.
+fn parse_review_body(html: &str) -> Option<String> {
+    let start = html.find(BODY_MARKER)?;
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |end| start + end);
+    let text = paragraph_text(&html[start..end]);
+    Some(truncate_at_sentence(&text, MAX_EXCERPT_CHARS)).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://exclaim.ca";
+
+    #[test]
+    fn only_album_reviews_are_candidates() {
+        let html = r#"<a href="/music/article/wet-leg-moisturizer-album-review">Review</a>
+            <a href="/music/article/wet-leg-announce-moisturizer">News</a>
+            <a href="/music/article/wet-leg-interview">Interview</a>
+            <a href="/music/reviews">Reviews</a>"#;
+        let urls: Vec<String> = site_links(html, BASE, "music")
+            .into_iter()
+            .filter(|url| review_slug(url).is_some())
+            .collect();
+        assert_eq!(urls.len(), 1);
+        let slug = review_slug(&urls[0]).unwrap();
+        assert_eq!(slug, "wet-leg-moisturizer");
+        let chosen = pick_review_url(&urls, &Query::new("wet-leg", "moisturizer"));
+        assert_eq!(chosen.as_ref(), Some(&urls[0]));
+    }
+
+    #[test]
+    fn score_from_rating_element() {
+        let html = r#"<div class="article-rating"><span>8</span></div>
+            <div class="article-author">By <a href="/author/kaelen">Kaelen Bell</a></div>
+            <div class="article-published">Published Jul 11, 2025</div>
+            <div class="article-body"><p>Wet Leg&#8217;s second album is louder.</p>
+            <figure><img src="x.jpg"></figure><p>It works.</p></div></article>"#;
+        let url = format!("{}/music/article/wet-leg-moisturizer-album-review", BASE);
+        let review = parse_review_page(&url, html).unwrap();
+        assert_eq!(review.rating, Some(8.0));
+        assert_eq!(review.rating_scale, Some(10.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Kaelen Bell"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-11"));
+        let excerpt = review.excerpt.unwrap();
+        let paragraphs: Vec<&str> = excerpt.split("\n\n").collect();
+        assert_eq!(paragraphs[0], "Wet Leg\u{2019}s second album is louder.");
+        assert_eq!(paragraphs[1..], ["It works."]);
+
+        let out_of_range = r#"<div class="article-rating">12</div>"#;
+        assert_eq!(parse_score(out_of_range), None);
+        let slashed = r#"<div class="article-rating large">7/10</div>"#;
+        assert_eq!(parse_score(slashed), Some(7.0));
+    }
+}
@@ -0,0 +1,6 @@
+pub mod gigwise;
+
+/// Review fields this source can populate.
+pub const CAPABILITIES: &[&str] = &["rating", "excerpt", "reviewer", "date"];
+
+editorial_common::editorial_plugin!(gigwise, "gigwise", CAPABILITIES);
@@ -0,0 +1,332 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, fetch_page, find_json_ld_node,
+    json_ld_name, json_ld_rating, match_confidence, meta_content, normalize_date, paragraph_text,
+    release_ids_conflict, search_terms, slugify, truncate_at_sentence, url_encode,
+    AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query, SearchCandidate, SearchResult,
+    SiteReview, MAX_EXCERPT_CHARS,
+};
+use extism_pdk::config;
+use serde::Deserialize;
+
+/// Production site root; override with the `guardian_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://www.theguardian.com";
+const BASE_URL_CONFIG: &str = "guardian_base_url";
+
+/// Content API root; override with the `guardian_api_url` config value.
+const DEFAULT_API_URL: &str = "https://content.guardianapis.com";
+const API_URL_CONFIG: &str = "guardian_api_url";
+
+/// Extism config key holding a Content API key.
+const API_KEY_CONFIG: &str = "guardian_api_key";
+/// The Content API's shared developer key, rate-limited but enough for
+/// occasional lookups.
+const DEFAULT_API_KEY: &str = "test";
+
+/// Content API tag carried by album reviews and nothing else, which keeps
+/// interviews, features and live reviews out of the results.
+const REVIEW_TAG: &str = "tone/albumreview";
+
+/// Results requested per Content API search.
+const PAGE_SIZE: u32 = 20;
+
+/// The Guardian rates albums out of five stars.
+const STAR_SCALE: f64 = 5.0;
+
+/// Marker of the element holding the review text.
+const BODY_MARKER: &str = "id=\"maincontent\"";
+
+/// The site root, honoring the `guardian_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+fn api_url() -> String {
+    configured_base_url(API_URL_CONFIG, DEFAULT_API_URL)
+}
+
+fn api_key() -> String {
+    config::get(API_KEY_CONFIG)
+        .ok()
+        .flatten()
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+        .unwrap_or_else(|| DEFAULT_API_KEY.to_string())
+}
+
+/// Attempt to fetch a Guardian review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its star rating, skipping the review
+/// text, author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known Guardian URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let review = find_json_ld_node(&body, &["Review"]);
+    let rating = parse_star_rating(review.as_ref()).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((rating, STAR_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the album reviews the Content API offers for an album and the one
+/// `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search the Content API's album reviews for "artist title" and keep every
+/// result along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!(
+        "{}/search?q={}&tag={}&page-size={}&api-key={}",
+        api_url(),
+        url_encode(&query),
+        REVIEW_TAG,
+        PAGE_SIZE,
+        url_encode(&api_key())
+    );
+    let body = fetch_page(&build_request(&search_url, "application/json"))?;
+    let urls = review_urls(&body, &base_url()).ok_or(FetchError::Error)?;
+
+    let mut result = SearchResult {
+        chosen: pick_review_url(&urls, &album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    Ok(result)
+}
+
+#[derive(Deserialize)]
+struct ApiSearch {
+    response: ApiResponse,
+}
+
+#[derive(Deserialize)]
+struct ApiResponse {
+    #[serde(default)]
+    results: Vec<ApiResult>,
+}
+
+#[derive(Deserialize)]
+struct ApiResult {
+    #[serde(rename = "webUrl")]
+    web_url: String,
+}
+
+/// The music review URLs in a Content API search response, moved onto
+/// `base` so an overridden site root is honored. None when the body isn't a
+/// search response.
+fn review_urls(body: &str, base: &str) -> Option<Vec<String>> {
+    let search: ApiSearch = serde_json::from_str(body).ok()?;
+    let urls = search
+        .response
+        .results
+        .into_iter()
+        .filter_map(|result| {
+            let path = result.web_url.split_once("://")?.1;
+            let path = &path[path.find('/')?..];
+            Some(format!("{}{}", base, path))
+        })
+        .filter(|url| review_slug(url).is_some())
+        .collect();
+    Some(urls)
+}
+
+/// The best-scoring review for the album; see `best_candidate`.
+fn pick_review_url(urls: &[String], query: &Query) -> Option<String> {
+    best_candidate(query, urls, |url| review_slug(url).map(Candidate::new)).cloned()
+}
+
+/// The artist and album part of a music review URL, before the "-review"
+/// that ends it or leads into the verdict:
+/// "/music/2025/jul/11/wet-leg-moisturizer-review-louder" ->
+/// "wet-leg-moisturizer". None outside the music section, for articles
+/// that aren't reviews, and for undated series and tag pages such as
+/// "/music/series/album-review".
+fn review_slug(url: &str) -> Option<String> {
+    let path = url.split_once("/music/")?.1;
+    let path = path.split(['?', '#']).next()?.trim_end_matches('/');
+    let segments: Vec<&str> = path.split('/').collect();
+    let [year, _, _, last] = segments[..] else {
+        return None;
+    };
+    if year.len() != 4 || !year.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let slug = slugify(last);
+    let album = match slug.strip_suffix("-review") {
+        Some(album) => album,
+        None => slug.split_once("-review-")?.0,
+    };
+    Some(album.to_string()).filter(|album| !album.is_empty())
+}
+
+/// Parse a Guardian review page for the star rating (out of 5) from its
+/// Review JSON-LD and the review's text, author and date.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let review = find_json_ld_node(html, &["Review"]);
+    let field = |key: &str| review.as_ref().and_then(|node| node.get(key));
+
+    let rating = parse_star_rating(review.as_ref());
+    let excerpt = parse_review_body(html).or_else(|| meta_content(html, "og:description"));
+    if rating.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    let reviewer = field("author")
+        .and_then(json_ld_name)
+        .or_else(|| meta_content(html, "author"));
+    let review_date = field("datePublished")
+        .and_then(|date| date.as_str())
+        .map(str::to_string)
+        .or_else(|| meta_content(html, "article:published_time"))
+        .and_then(|date| normalize_date(&date));
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(rating.map(|r| (r, STAR_SCALE)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The star rating from the JSON-LD `reviewRating`, accepted only within
+/// 0-5.
+fn parse_star_rating(review: Option<&serde_json::Value>) -> Option<f64> {
+    let rating = review?.get("reviewRating")?;
+    json_ld_rating(rating, STAR_SCALE).filter(|stars| (0.0..=STAR_SCALE).contains(stars))
+}
+
+/// The review's paragraphs, cut to ~2000 chars at a sentence boundary.
+fn parse_review_body(html: &str) -> Option<String> {
+    let start = html.find(BODY_MARKER)?;
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |end| start + end);
+    let text = paragraph_text(&html[start..end]);
+    Some(truncate_at_sentence(&text, MAX_EXCERPT_CHARS)).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://www.theguardian.com";
+
+    #[test]
+    fn review_slug_ends_at_review_or_its_verdict() {
+        let slug = |path: &str| review_slug(&format!("{}{}", BASE, path));
+        let verdict = slug("/music/2025/jul/11/wet-leg-moisturizer-review-louder-and-prouder");
+        assert_eq!(verdict.as_deref(), Some("wet-leg-moisturizer"));
+        let plain = slug("/music/2025/jul/11/wet-leg-moisturizer-review/?CMP=share");
+        assert_eq!(plain.as_deref(), Some("wet-leg-moisturizer"));
+        assert_eq!(slug("/music/2025/jul/10/wet-leg-interview"), None);
+        assert_eq!(slug("/music/series/album-review"), None);
+        assert_eq!(slug("/film/2025/jul/11/moisturizer-review"), None);
+    }
+
+    #[test]
+    fn search_results_kept_to_music_reviews() {
+        let body = r#"{"response":{"status":"ok","results":[
+            {"webUrl":"https://www.theguardian.com/music/2025/jul/10/wet-leg-interview"},
+            {"webUrl":"https://www.theguardian.com/film/2025/jul/11/moisturizer-review"},
+            {"webUrl":"https://www.theguardian.com/music/2025/jul/11/wet-leg-moisturizer-review-louder"}]}}"#;
+        let urls = review_urls(body, "http://localhost:8080").unwrap();
+        assert_eq!(urls.len(), 1);
+        assert!(urls[0].starts_with("http://localhost:8080/music/2025/jul/11/"));
+        assert_eq!(review_slug(&urls[0]).unwrap(), "wet-leg-moisturizer");
+        let chosen = pick_review_url(&urls, &Query::new("wet-leg", "moisturizer"));
+        assert_eq!(chosen.as_ref(), Some(&urls[0]));
+
+        // A rejected API key answers with a bare message, not a response
+        assert_eq!(review_urls(r#"{"message":"Unauthorized"}"#, BASE), None);
+        let empty = r#"{"response":{"status":"ok","total":0}}"#;
+        assert_eq!(review_urls(empty, BASE), Some(Vec::new()));
+    }
+
+    #[test]
+    fn star_rating_from_review_json_ld() {
+        let html = r#"<script type="application/ld+json">[{"@type":"NewsArticle"},
+            {"@type":"Review","author":[{"@type":"Person","name":"Alexis Petridis"}],
+            "datePublished":"2025-07-11T07:00:12.000Z",
+            "reviewRating":{"@type":"Rating","worstRating":1,"bestRating":5,"ratingValue":4}}]
+            </script><article><div id="maincontent"><p>Wet Leg&rsquo;s second album.</p>
+            <p>It is louder.</p></div></article>"#;
+        let url = format!("{}/music/2025/jul/11/wet-leg-moisturizer-review", BASE);
+        let review = parse_review_page(&url, html).unwrap();
+        assert_eq!(review.rating, Some(8.0));
+        assert_eq!(review.rating_raw, Some(4.0));
+        assert_eq!(review.rating_scale, Some(5.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Alexis Petridis"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-11"));
+        let excerpt = review.excerpt.unwrap();
+        assert_eq!(excerpt, "Wet Leg\u{2019}s second album.\n\nIt is louder.");
+
+        // Star glyphs in the standfirst aren't read; only the JSON-LD counts
+        let unrated = r#"<meta property="og:description" content="★★★★☆ Louder.">
+            <meta name="author" content="Kitty Empire">
+            <meta property="article:published_time" content="2025-07-13T06:00:00Z">"#;
+        let review = parse_review_page(&url, unrated).unwrap();
+        assert_eq!(review.rating, None);
+        assert_eq!(review.reviewer.as_deref(), Some("Kitty Empire"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-13"));
+    }
+}
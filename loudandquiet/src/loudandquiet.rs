@@ -0,0 +1,289 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, element_text, fetch_page,
+    find_json_ld_node, json_ld_name, json_ld_rating, match_confidence, meta_content,
+    normalize_date, paragraph_text, parse_fractional_rating, release_ids_conflict, search_terms,
+    site_links, slugify, truncate_at_sentence, url_encode, AlbumReviewInput, Candidate, FetchError,
+    FetchOutcome, Query, SearchCandidate, SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `loudandquiet_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://www.loudandquiet.com";
+const BASE_URL_CONFIG: &str = "loudandquiet_base_url";
+
+/// Loud and Quiet scores albums out of ten.
+const SCORE_SCALE: f64 = 10.0;
+
+/// Class of the element showing the score, for pages whose JSON-LD carries
+/// no `reviewRating`.
+const SCORE_MARKER: &str = "class=\"review-score";
+
+/// Class of the element holding the review text.
+const BODY_MARKER: &str = "class=\"entry-content";
+
+/// JSON-LD types a Loud and Quiet review page may describe itself as.
+const ARTICLE_TYPES: &[&str] = &["Review", "Article"];
+
+/// The site root, honoring the `loudandquiet_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch a Loud and Quiet review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its score, skipping the review text,
+/// author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known Loud and Quiet URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let article = find_json_ld_node(&body, ARTICLE_TYPES);
+    let score = parse_score(article.as_ref(), &body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((score, SCORE_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the review URLs Loud and Quiet's search offers for an album and the
+/// one `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Loud and Quiet for "artist title" and keep every album review it
+/// lists along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/?s={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+    Ok(parse_search_page(&html, &base, &album))
+}
+
+/// The album reviews linked from a search results page, and the pick.
+fn parse_search_page(html: &str, base: &str, album: &Query) -> SearchResult {
+    let urls: Vec<String> = site_links(html, base, "reviews")
+        .into_iter()
+        .filter(|url| review_slug(url).is_some())
+        .collect();
+    let mut result = SearchResult {
+        chosen: pick_review_url(&urls, album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    result
+}
+
+/// The best-scoring review for the album; see `best_candidate`.
+fn pick_review_url(urls: &[String], query: &Query) -> Option<String> {
+    best_candidate(query, urls, |url| review_slug(url).map(Candidate::new)).cloned()
+}
+
+/// The artist and album part of a review URL, without the "-review" suffix
+/// or the date some slugs end in:
+/// "/reviews/wet-leg-moisturizer-review-11-07-2025/" -> "wet-leg-moisturizer".
+/// Dated path segments ("/reviews/2025/07/...") are skipped. None for the
+/// section pages.
+fn review_slug(url: &str) -> Option<String> {
+    let path = url.split_once("/reviews/")?.1;
+    let path = path.split(['?', '#']).next()?;
+    let segment = path
+        .split('/')
+        .rfind(|segment| !segment.is_empty() && !is_number(segment))?;
+    let mut tokens: Vec<&str> = segment.split('-').collect();
+    // Only a whole "-dd-mm-yyyy" goes, so titles ending in a number survive
+    if let [.., day, month, year] = tokens[..] {
+        if [day, month, year].iter().all(|token| is_number(token)) && year.len() == 4 {
+            tokens.truncate(tokens.len() - 3);
+        }
+    }
+    let slug = slugify(&tokens.join("-"));
+    let album = slug.strip_suffix("-review").unwrap_or(&slug);
+    Some(album.to_string()).filter(|album| !album.is_empty() && album != "album-reviews")
+}
+
+fn is_number(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Parse a Loud and Quiet review page for the score (out of 10) and the
+/// review's text, author and date.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let article = find_json_ld_node(html, ARTICLE_TYPES);
+    let field = |key: &str| article.as_ref().and_then(|node| node.get(key));
+
+    let score = parse_score(article.as_ref(), html);
+    let excerpt = parse_review_body(html).or_else(|| meta_content(html, "og:description"));
+    if score.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    let reviewer = field("author")
+        .and_then(json_ld_name)
+        .or_else(|| meta_content(html, "author"));
+    let review_date = field("datePublished")
+        .and_then(|date| date.as_str())
+        .map(str::to_string)
+        .or_else(|| meta_content(html, "article:published_time"))
+        .and_then(|date| normalize_date(&date));
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(score.map(|s| (s, SCORE_SCALE)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The score, from the JSON-LD `reviewRating` or else the score element
+/// ("8" or "8/10"). Anything outside 0-10 is rejected.
+fn parse_score(article: Option<&serde_json::Value>, html: &str) -> Option<f64> {
+    let from_json_ld = article
+        .and_then(|node| node.get("reviewRating"))
+        .and_then(|rating| json_ld_rating(rating, SCORE_SCALE));
+    let score =
+        from_json_ld.or_else(|| parse_fractional_rating(&element_text(html, SCORE_MARKER)?))?;
+    Some(score).filter(|score| (0.0..=SCORE_SCALE).contains(score))
+}
+
+/// The review's paragraphs, cut to ~2000 chars at a sentence boundary.
+fn parse_review_body(html: &str) -> Option<String> {
+    let start = html.find(BODY_MARKER)?;
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |end| start + end);
+    let text = paragraph_text(&html[start..end]);
+    Some(truncate_at_sentence(&text, MAX_EXCERPT_CHARS)).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://www.loudandquiet.com";
+
+    #[test]
+    fn review_and_date_segments_stripped_from_slugs() {
+        let slug = |path: &str| review_slug(&format!("{}{}", BASE, path));
+        let dated = slug("/reviews/wet-leg-moisturizer-review-11-07-2025/");
+        assert_eq!(dated.as_deref(), Some("wet-leg-moisturizer"));
+        let nested = slug("/reviews/2025/07/wet-leg-moisturizer/");
+        assert_eq!(nested.as_deref(), Some("wet-leg-moisturizer"));
+        let numbered = slug("/reviews/adele-21/");
+        assert_eq!(numbered.as_deref(), Some("adele-21"));
+        assert_eq!(slug("/reviews/album-reviews/"), None);
+        assert_eq!(slug("/reviews/2025/07/"), None);
+        assert_eq!(slug("/features/wet-leg-interview/"), None);
+    }
+
+    #[test]
+    fn search_page_keeps_reviews_only() {
+        let html = r#"<a href="/reviews/album-reviews/">Albums</a>
+            <a href="/features/wet-leg-interview/">Interview</a>
+            <a href="/reviews/2022/04/wet-leg-wet-leg/">Wet Leg</a>
+            <a href="/reviews/wet-leg-moisturizer-review-11-07-2025/">Moisturizer</a>"#;
+        let album = Query::new("wet-leg", "moisturizer");
+        let result = parse_search_page(html, BASE, &album);
+        let slugs: Vec<&str> = result.candidates.iter().map(|c| c.slug.as_str()).collect();
+        assert_eq!(slugs, ["wet-leg-wet-leg", "wet-leg-moisturizer"]);
+        let chosen = format!("{}/reviews/wet-leg-moisturizer-review-11-07-2025/", BASE);
+        assert_eq!(result.chosen, Some(chosen));
+    }
+
+    #[test]
+    fn score_from_review_json_ld() {
+        let html = r#"<script type="application/ld+json">{"@graph":[{"@type":"WebPage"},
+            {"@type":"Review","author":{"@type":"Person","name":"Reef Younis"},
+            "datePublished":"2025-07-11T09:00:00+00:00",
+            "reviewRating":{"@type":"Rating","ratingValue":"7","bestRating":"10"}}]}</script>
+            <div class="review-score"><span>6</span>/10</div>
+            <article><div class="entry-content"><p>Wet Leg&rsquo;s second album.</p></div></article>"#;
+        let url = format!("{}/reviews/wet-leg-moisturizer-review/", BASE);
+        let review = parse_review_page(&url, html).unwrap();
+        assert_eq!(review.rating, Some(7.0));
+        assert_eq!(review.rating_scale, Some(10.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Reef Younis"));
+
+        let out_of_five = r#"{"@type":"Review",
+            "reviewRating":{"@type":"Rating","ratingValue":"4","bestRating":"5"}}"#;
+        let article: serde_json::Value = serde_json::from_str(out_of_five).unwrap();
+        assert_eq!(parse_score(Some(&article), ""), Some(8.0));
+    }
+
+    #[test]
+    fn score_from_the_score_element() {
+        let html = r#"<script type="application/ld+json">{"@graph":[{"@type":"WebPage"},
+            {"@type":"Article","author":{"@type":"Person","name":"Reef Younis"},
+            "datePublished":"2025-07-11T09:00:00+00:00"}]}</script>
+            <div class="review-score"><span>7</span>/10</div>
+            <article><div class="entry-content"><p>Wet Leg&rsquo;s second album.</p></div></article>"#;
+        let url = format!("{}/reviews/wet-leg-moisturizer-review/", BASE);
+        let review = parse_review_page(&url, html).unwrap();
+        assert_eq!(review.rating, Some(7.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Reef Younis"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-11"));
+        let excerpt = review.excerpt.unwrap();
+        assert_eq!(excerpt, "Wet Leg\u{2019}s second album.");
+
+        let bare = r#"<div class="review-score">8</div>"#;
+        assert_eq!(parse_score(None, bare), Some(8.0));
+        let out_of_range = r#"<div class="review-score">80</div>"#;
+        assert_eq!(parse_score(None, out_of_range), None);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod metacritic;
+
+/// Review fields this source can populate.
+pub const CAPABILITIES: &[&str] = &["rating", "rating_count", "accolade"];
+
+editorial_common::editorial_plugin!(metacritic, "metacritic", CAPABILITIES);
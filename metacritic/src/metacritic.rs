@@ -0,0 +1,166 @@
+use editorial_common::{
+    build_request, clean_title, configured_base_url, decode_html_entities, fetch_page,
+    find_json_ld_node, json_ld_name, json_ld_number, release_ids_conflict, slugify,
+    strip_html_tags, AlbumReviewInput, FetchError, FetchOutcome, SearchCandidate, SearchResult,
+    SiteReview,
+};
+
+/// Production site root; override with the `metacritic_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://www.metacritic.com";
+const BASE_URL_CONFIG: &str = "metacritic_base_url";
+
+/// Metascore bands worth surfacing as an accolade, most distinguished first.
+const ACCOLADES: &[&str] = &["Must-Hear", "Universal Acclaim"];
+
+/// The site root, honoring the `metacritic_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch the Metacritic metascore for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_album(input).into()
+}
+
+/// Metacritic pages carry only the metascore, so this is the same lookup as
+/// `fetch_review`.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_album(input).into()
+}
+
+/// Parse the album page at a known Metacritic URL. The page's artist isn't
+/// checked since there's no album to compare it with.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_album_page(url, &body, "").ok_or(FetchError::NotFound)
+}
+
+/// Fetch the album page at its slug-derived URL and read the metascore.
+fn lookup_album(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let url = album_url(&base_url(), &input.artist, &input.title).ok_or(FetchError::NotFound)?;
+    let body = fetch_page(&build_request(&url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+
+    let mut review = parse_album_page(&url, &body, &input.artist).ok_or(FetchError::NotFound)?;
+    // The URL is built from the requested slugs, so a page that passes the
+    // artist check is the album itself
+    review.match_confidence = Some(1.0);
+    Ok(review)
+}
+
+/// Metacritic has no search to list; report the album URL `fetch_review`
+/// would request.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    let Some(url) = album_url(&base_url(), &input.artist, &input.title) else {
+        return SearchResult::default();
+    };
+    let artist_slug = slugify(&input.artist);
+    let title_slug = slugify(clean_title(&input.title));
+    let slug = format!("{}-{}", artist_slug, title_slug);
+
+    let mut result = SearchResult {
+        chosen: Some(url.clone()),
+        ..SearchResult::default()
+    };
+    result.add(SearchCandidate::new(url, slug, &artist_slug, &title_slug));
+    result
+}
+
+/// The album page URL: "/music/{title-slug}/{artist-slug}".
+fn album_url(base: &str, artist: &str, title: &str) -> Option<String> {
+    let artist_slug = slugify(artist);
+    let title_slug = slugify(clean_title(title));
+    if artist_slug.is_empty() || title_slug.is_empty() {
+        return None;
+    }
+    Some(format!("{}/music/{}/{}", base, title_slug, artist_slug))
+}
+
+/// Parse the MusicAlbum JSON-LD of an album page for the metascore (out of
+/// 100) and the number of critic reviews behind it. With a non-empty
+/// `artist`, pages by another artist are rejected.
+fn parse_album_page(url: &str, html: &str, artist: &str) -> Option<SiteReview> {
+    let album = find_json_ld_node(html, &["MusicAlbum"])?;
+
+    let artist_slug = slugify(artist);
+    if !artist_slug.is_empty() {
+        let page_artist = album.get("byArtist").and_then(json_ld_name)?;
+        if !slugify(&page_artist).contains(&artist_slug) {
+            return None;
+        }
+    }
+
+    let aggregate = album.get("aggregateRating")?;
+    let best = aggregate
+        .get("bestRating")
+        .and_then(json_ld_number)
+        .filter(|best| *best > 0.0)
+        .unwrap_or(100.0);
+    let metascore = aggregate
+        .get("ratingValue")
+        .and_then(json_ld_number)
+        .filter(|score| (0.0..=best).contains(score))?;
+    let critics = aggregate
+        .get("ratingCount")
+        .or_else(|| aggregate.get("reviewCount"))
+        .and_then(json_ld_number)
+        .map(|count| count as u32);
+
+    Some(
+        SiteReview::builder(url)
+            .native_rating(Some((metascore, best)))
+            .rating_count(critics)
+            .accolade(parse_accolade(html))
+            .build(),
+    )
+}
+
+/// The metascore band shown on the page, if it's one of `ACCOLADES`.
+fn parse_accolade(html: &str) -> Option<String> {
+    let text = decode_html_entities(&strip_html_tags(html));
+    ACCOLADES
+        .iter()
+        .find(|accolade| text.contains(*accolade))
+        .map(|accolade| accolade.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://www.metacritic.com/music/ok-computer/radiohead";
+
+    #[test]
+    fn album_url_from_slugs() {
+        let base = "https://www.metacritic.com";
+        let url = album_url(base, "Radiohead", "OK Computer (Deluxe Edition)");
+        assert_eq!(url.as_deref(), Some(URL));
+        assert_eq!(album_url(base, "Radiohead", "()"), None);
+    }
+
+    #[test]
+    fn metascore_normalized_with_critic_count() {
+        let html = r#"<script type="application/ld+json">{"@type":"MusicAlbum","name":"OK Computer",
+            "byArtist":{"@type":"MusicGroup","name":"Radiohead"},
+            "aggregateRating":{"ratingValue":91,"bestRating":100,"ratingCount":13}}</script>
+            <div class="c-siteReviewScore"><span>91</span></div><span>Universal Acclaim</span>"#;
+        let review = parse_album_page(URL, html, "Radiohead").unwrap();
+        assert_eq!(review.rating, Some(9.1));
+        assert_eq!(review.rating_raw, Some(91.0));
+        assert_eq!(review.rating_scale, Some(100.0));
+        assert_eq!(review.rating_count, Some(13));
+        assert_eq!(review.accolade.as_deref(), Some("Universal Acclaim"));
+
+        assert!(parse_album_page(URL, html, "Portishead").is_none());
+        let mixed = html.replace("Universal Acclaim", "Mixed or Average");
+        assert_eq!(parse_album_page(URL, &mixed, "").unwrap().accolade, None);
+        let unscored = html.replace("\"ratingValue\":91,", "");
+        assert!(parse_album_page(URL, &unscored, "Radiohead").is_none());
+    }
+}
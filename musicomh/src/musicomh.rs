@@ -0,0 +1,240 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, fetch_page, find_json_ld_node,
+    json_ld_name, json_ld_rating, match_confidence, meta_content, normalize_date, paragraph_text,
+    parse_star_rating, release_ids_conflict, search_terms, site_links, slugify,
+    truncate_at_sentence, url_encode, AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query,
+    SearchCandidate, SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `musicomh_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://www.musicomh.com";
+const BASE_URL_CONFIG: &str = "musicomh_base_url";
+
+/// musicOMH scores albums out of five stars, in half-star steps.
+const STAR_SCALE: f64 = 5.0;
+
+/// Class of the element holding the star icons, for pages whose JSON-LD
+/// carries no `reviewRating`.
+const RATING_MARKER: &str = "class=\"rating";
+
+/// Class of the element holding the review text.
+const BODY_MARKER: &str = "class=\"entry-content";
+
+/// JSON-LD types a musicOMH review page may describe itself as.
+const ARTICLE_TYPES: &[&str] = &["Review", "Article"];
+
+/// The site root, honoring the `musicomh_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch a musicOMH review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its star rating, skipping the review
+/// text, author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known musicOMH URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let article = find_json_ld_node(&body, ARTICLE_TYPES);
+    let rating = parse_stars(article.as_ref(), &body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((rating, STAR_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the album review URLs musicOMH's search offers for an album and the
+/// one `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search musicOMH for "artist title" and keep every album review it lists
+/// along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/?s={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+
+    // Single and live reviews share the section, under their own subpaths
+    let urls: Vec<String> = site_links(&html, &base, "reviews")
+        .into_iter()
+        .filter(|url| review_slug(url).is_some())
+        .collect();
+    let mut result = SearchResult {
+        chosen: pick_review_url(&urls, &album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    Ok(result)
+}
+
+/// The best-scoring review for the album; see `best_candidate`.
+fn pick_review_url(urls: &[String], query: &Query) -> Option<String> {
+    best_candidate(query, urls, |url| review_slug(url).map(Candidate::new)).cloned()
+}
+
+/// The slug of an album review URL:
+/// "/reviews/albums/wet-leg-moisturizer" -> "wet-leg-moisturizer". None for
+/// the section pages and for single, live and book reviews.
+fn review_slug(url: &str) -> Option<String> {
+    let path = url.split_once("/reviews/albums/")?.1;
+    let slug = path.split(['/', '?', '#']).next()?;
+    Some(slugify(slug)).filter(|slug| !slug.is_empty() && slug != "page")
+}
+
+/// Parse a musicOMH review page for the star rating (out of 5) and the
+/// review's text, author and date.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let article = find_json_ld_node(html, ARTICLE_TYPES);
+    let field = |key: &str| article.as_ref().and_then(|node| node.get(key));
+
+    let rating = parse_stars(article.as_ref(), html);
+    let excerpt = parse_review_body(html);
+    if rating.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    let reviewer = field("author")
+        .and_then(json_ld_name)
+        .or_else(|| meta_content(html, "author"));
+    let review_date = field("datePublished")
+        .and_then(|date| date.as_str())
+        .and_then(normalize_date);
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(rating.map(|r| (r, STAR_SCALE)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The star rating, from the JSON-LD `reviewRating` or else by counting the
+/// icons in the rating element. JSON-LD ratings on another scale are
+/// converted to stars; anything outside 0-5 is rejected.
+fn parse_stars(article: Option<&serde_json::Value>, html: &str) -> Option<f64> {
+    let from_json_ld = article
+        .and_then(|node| node.get("reviewRating"))
+        .and_then(|rating| json_ld_rating(rating, STAR_SCALE));
+    from_json_ld.or_else(|| {
+        let start = html.find(RATING_MARKER)?;
+        let end = html[start..].find("</div>")?;
+        parse_star_rating(&html[start..start + end], STAR_SCALE)
+    })
+}
+
+/// The review's paragraphs, cut to ~2000 chars at a sentence boundary.
+fn parse_review_body(html: &str) -> Option<String> {
+    let start = html.find(BODY_MARKER)?;
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |end| start + end);
+    let text = paragraph_text(&html[start..end]);
+    Some(truncate_at_sentence(&text, MAX_EXCERPT_CHARS)).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://www.musicomh.com";
+
+    #[test]
+    fn only_album_reviews_are_candidates() {
+        let html = r#"<a href="/reviews/albums/">Albums</a>
+            <a href="/reviews/albums/page/2">Next</a>
+            <a href="/reviews/live/wet-leg-o2-academy">Live</a>
+            <a href="/reviews/albums/wet-leg-moisturizer">Moisturizer</a>
+            <a href="/news/wet-leg-moisturizer-tour">News</a>"#;
+        let urls: Vec<String> = site_links(html, BASE, "reviews")
+            .into_iter()
+            .filter(|url| review_slug(url).is_some())
+            .collect();
+        assert_eq!(urls.len(), 1);
+        assert_eq!(review_slug(&urls[0]).unwrap(), "wet-leg-moisturizer");
+        let chosen = pick_review_url(&urls, &Query::new("wet-leg", "moisturizer"));
+        assert_eq!(chosen.as_ref(), Some(&urls[0]));
+    }
+
+    #[test]
+    fn star_rating_kept_on_five_point_scale() {
+        let html = r#"<script type="application/ld+json">{"@graph":[{"@type":"Article",
+            "author":{"@type":"Person","name":"John Murphy"},
+            "datePublished":"2025-07-11T08:30:00+01:00"}]}</script>
+            <div class="rating"><i class="fa fa-star"></i><i class="fa fa-star"></i>
+            <i class="fa fa-star"></i><i class="fa fa-star-half-o"></i>
+            <i class="fa fa-star-o"></i></div>
+            <article><div class="entry-content"><p>Wet Leg return, louder.</p></div></article>"#;
+        let url = format!("{}/reviews/albums/wet-leg-moisturizer", BASE);
+        let review = parse_review_page(&url, html).unwrap();
+        assert_eq!(review.rating, Some(7.0));
+        assert_eq!(review.rating_raw, Some(3.5));
+        assert_eq!(review.rating_scale, Some(5.0));
+        assert_eq!(review.reviewer.as_deref(), Some("John Murphy"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-11"));
+        assert_eq!(review.excerpt.as_deref(), Some("Wet Leg return, louder."));
+
+        let rated = serde_json::json!({"reviewRating": {"ratingValue": "4", "bestRating": "5"}});
+        assert_eq!(parse_stars(Some(&rated), ""), Some(4.0));
+        assert_eq!(parse_stars(None, "<p>No rating</p>"), None);
+    }
+}
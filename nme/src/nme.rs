@@ -0,0 +1,262 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, decode_html_entities,
+    extract_json_ld, fetch_page, json_ld_name, json_ld_rating, match_confidence, parse_star_rating,
+    release_ids_conflict, search_terms, site_links, slugify, strip_html_tags, truncate_at_sentence,
+    url_encode, AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query, SearchCandidate,
+    SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+use serde::Deserialize;
+
+/// Production site root; override with the `nme_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://www.nme.com";
+const BASE_URL_CONFIG: &str = "nme_base_url";
+
+/// NME scores albums out of five stars.
+const STAR_SCALE: f64 = 5.0;
+
+/// Class of a full star icon in the rating markup; half stars use the
+/// `-half` variant and empty stars `-empty`. The first icon marks where the
+/// rating markup starts.
+const STAR_ICON: &str = "td-icon-star";
+
+/// The site root, honoring the `nme_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch an NME review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its star rating, skipping the review
+/// text, author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known NME URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let json_ld = parse_json_ld(&body);
+    let rating = parse_stars(json_ld.as_ref(), &body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((rating, STAR_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the review URLs NME's search offers for an album and the one
+/// `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search NME for "artist title" and keep every album review URL it lists
+/// along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/?s={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+
+    let urls = site_links(&html, &base, "reviews");
+    let mut result = SearchResult {
+        chosen: pick_review_url(&urls, &album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    Ok(result)
+}
+
+/// The best-scoring review for the album; see `best_candidate`.
+fn pick_review_url(urls: &[String], query: &Query) -> Option<String> {
+    best_candidate(query, urls, |url| review_slug(url).map(Candidate::new)).cloned()
+}
+
+/// The album part of a review URL's slug. NME appends "-review" and usually
+/// a numeric post ID to its slugs, both dropped here:
+/// "/reviews/album/wet-leg-moisturizer-review-3881234" ->
+/// "wet-leg-moisturizer". None for section pages like "/reviews/album".
+fn review_slug(url: &str) -> Option<String> {
+    let path = url.split_once("/reviews/")?.1;
+    let path = path.split(['?', '#']).next()?.trim_end_matches('/');
+    let (section, slug) = path.rsplit_once('/')?;
+    if section.is_empty() {
+        return None;
+    }
+
+    let mut slug = slugify(slug);
+    if let Some((rest, id)) = slug.rsplit_once('-') {
+        if id.chars().all(|c| c.is_ascii_digit()) {
+            slug.truncate(rest.len());
+        }
+    }
+    if let Some(rest) = slug.strip_suffix("-review") {
+        slug.truncate(rest.len());
+    }
+    Some(slug).filter(|slug| !slug.is_empty() && slug != "review")
+}
+
+/// JSON-LD schema for NME review pages.
+#[derive(Deserialize)]
+struct JsonLdReview {
+    #[serde(rename = "reviewBody")]
+    review_body: Option<String>,
+    description: Option<String>,
+    author: Option<serde_json::Value>,
+    #[serde(rename = "datePublished")]
+    date_published: Option<String>,
+    #[serde(rename = "reviewRating")]
+    review_rating: Option<serde_json::Value>,
+}
+
+fn parse_json_ld(html: &str) -> Option<JsonLdReview> {
+    serde_json::from_str(&extract_json_ld(html)?).ok()
+}
+
+/// Parse an NME review page for the star rating (out of 5) and the review's
+/// text, author and date from its JSON-LD.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let json_ld = parse_json_ld(html);
+    let rating = parse_stars(json_ld.as_ref(), html);
+    let Some(review) = json_ld else {
+        return rating.map(|r| {
+            SiteReview::builder(url)
+                .native_rating(Some((r, STAR_SCALE)))
+                .build()
+        });
+    };
+
+    let excerpt = review
+        .review_body
+        .or(review.description)
+        .map(|body| decode_html_entities(&strip_html_tags(&body)))
+        .map(|body| truncate_at_sentence(body.trim(), MAX_EXCERPT_CHARS))
+        .filter(|body| !body.is_empty());
+    if rating.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(rating.map(|r| (r, STAR_SCALE)))
+            .reviewer(review.author.as_ref().and_then(json_ld_name))
+            .review_date(review.date_published)
+            .build(),
+    )
+}
+
+/// The star rating, from the JSON-LD `reviewRating` or else by counting the
+/// star icons in the page's rating markup. JSON-LD ratings on another scale
+/// are converted to stars; anything outside 0-5 is rejected.
+fn parse_stars(json_ld: Option<&JsonLdReview>, html: &str) -> Option<f64> {
+    let from_json_ld = json_ld
+        .and_then(|review| review.review_rating.as_ref())
+        .and_then(|rating| json_ld_rating(rating, STAR_SCALE));
+    from_json_ld.or_else(|| {
+        let start = html[..html.find(STAR_ICON)?].rfind('<')?;
+        let end = html[start..]
+            .find("</div>")
+            .map_or(html.len(), |end| start + end);
+        parse_star_rating(&html[start..end], STAR_SCALE)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://www.nme.com";
+
+    #[test]
+    fn review_suffix_and_post_id_dropped_from_slug() {
+        let html = r#"<a href="/reviews/album">Album reviews</a>
+            <a href="/reviews/album/wet-leg-moisturizer-review-3881234">Moisturizer</a>
+            <a href="https://www.nme.com/reviews/album/wet-leg-self-titled-review">Wet Leg</a>
+            <a href="/news/music/wet-leg-moisturizer-tour-3881000">Tour</a>"#;
+        let urls = site_links(html, BASE, "reviews");
+        assert_eq!(urls.len(), 3);
+        let slugs: Vec<Option<String>> = urls.iter().map(|url| review_slug(url)).collect();
+        assert_eq!(slugs[0], None);
+        assert_eq!(slugs[1].as_deref(), Some("wet-leg-moisturizer"));
+        assert_eq!(slugs[2].as_deref(), Some("wet-leg-self-titled"));
+
+        let chosen = pick_review_url(&urls, &Query::new("wet-leg", "moisturizer"));
+        assert_eq!(chosen.as_ref(), Some(&urls[1]));
+        let filler = Query::new("wet-leg", "review");
+        assert_eq!(pick_review_url(&urls, &filler), None);
+    }
+
+    #[test]
+    fn star_rating_from_json_ld_or_icons() {
+        let html = r#"<script type="application/ld+json">{"@type":"Review",
+            "author":{"@type":"Person","name":"Rhian Daly"},"datePublished":"2025-07-11T09:00:00Z",
+            "reviewBody":"Bigger, bolder &amp; still weird.",
+            "reviewRating":{"@type":"Rating","ratingValue":4,"bestRating":5}}</script>"#;
+        let review = parse_review_page("https://www.nme.com/reviews/album/x", html).unwrap();
+        assert_eq!(review.rating, Some(8.0));
+        assert_eq!(review.rating_scale, Some(5.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Rhian Daly"));
+        let excerpt = review.excerpt.unwrap();
+        assert_eq!(excerpt, "Bigger, bolder & still weird.");
+
+        let icons = r#"<span class="td-icon-star"></span><span class="td-icon-star"></span>
+            <span class="td-icon-star"></span><span class="td-icon-star-half"></span>
+            <span class="td-icon-star-empty"></span>"#;
+        assert_eq!(parse_stars(None, icons), Some(3.5));
+        assert_eq!(parse_stars(None, "<p>No stars here</p>"), None);
+        let overrated = html.replace("\"ratingValue\":4", "\"ratingValue\":9");
+        let json_ld = parse_json_ld(&overrated);
+        assert_eq!(parse_stars(json_ld.as_ref(), &overrated), None);
+    }
+}
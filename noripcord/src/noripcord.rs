@@ -0,0 +1,280 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, element_text, fetch_page,
+    find_json_ld_node, json_ld_name, json_ld_rating, match_confidence, meta_content,
+    normalize_date, paragraph_text, parse_fractional_rating, release_ids_conflict, search_terms,
+    site_links, slugify, truncate_at_sentence, url_encode, AlbumReviewInput, Candidate, FetchError,
+    FetchOutcome, Query, SearchCandidate, SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `noripcord_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://noripcord.com";
+const BASE_URL_CONFIG: &str = "noripcord_base_url";
+
+/// No Ripcord scores albums out of ten.
+const SCORE_SCALE: f64 = 10.0;
+
+/// Class of the element showing the score on current pages.
+const SCORE_MARKER: &str = "class=\"review-score";
+
+/// Class of the element holding the review text.
+const BODY_MARKER: &str = "class=\"review-body";
+
+/// JSON-LD types a No Ripcord review page may describe itself as.
+const ARTICLE_TYPES: &[&str] = &["Review", "Article"];
+
+/// The site root, honoring the `noripcord_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch a No Ripcord review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its score, skipping the review text,
+/// author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known No Ripcord URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let article = find_json_ld_node(&body, ARTICLE_TYPES);
+    let score = parse_score(article.as_ref(), &body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((score, SCORE_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the review URLs No Ripcord's search offers for an album and the one
+/// `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search No Ripcord for "artist title" and keep every music review it
+/// lists along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/?s={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+    Ok(parse_search_page(&html, &base, &album))
+}
+
+/// The music reviews linked from a search results page, and the pick.
+/// Film and TV reviews share the section; only music reviews count.
+fn parse_search_page(html: &str, base: &str, album: &Query) -> SearchResult {
+    let urls: Vec<String> = site_links(html, base, "reviews")
+        .into_iter()
+        .filter(|url| review_slug(url).is_some())
+        .collect();
+    let mut result = SearchResult {
+        chosen: pick_review_url(&urls, album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    result
+}
+
+/// The best-scoring review for the album; see `best_candidate`.
+fn pick_review_url(urls: &[String], query: &Query) -> Option<String> {
+    best_candidate(query, urls, |url| review_slug(url).map(Candidate::new)).cloned()
+}
+
+/// The artist and album slug of a music review URL:
+/// "/reviews/music/wet-leg-moisturizer" -> "wet-leg-moisturizer". None for
+/// film and TV reviews and the section pages.
+fn review_slug(url: &str) -> Option<String> {
+    let path = url.split_once("/reviews/music/")?.1;
+    let slug = slugify(path.split(['/', '?', '#']).next()?);
+    Some(slug).filter(|slug| !slug.is_empty())
+}
+
+/// Parse a No Ripcord review page for the score (out of 10) and the
+/// review's text, author and date.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let article = find_json_ld_node(html, ARTICLE_TYPES);
+    let field = |key: &str| article.as_ref().and_then(|node| node.get(key));
+
+    let score = parse_score(article.as_ref(), html);
+    let excerpt = parse_review_body(html).or_else(|| meta_content(html, "og:description"));
+    if score.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    let reviewer = field("author")
+        .and_then(json_ld_name)
+        .or_else(|| meta_content(html, "author"));
+    let review_date = meta_content(html, "article:published_time")
+        .or_else(|| field("datePublished")?.as_str().map(str::to_string))
+        .and_then(|date| normalize_date(&date));
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(score.map(|s| (s, SCORE_SCALE)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The score, from the JSON-LD `reviewRating`, the score element ("7" or
+/// "7/10") or, on older reviews without either, the last "N/10" in the
+/// review text. Anything outside 0-10 is rejected.
+fn parse_score(article: Option<&serde_json::Value>, html: &str) -> Option<f64> {
+    let from_json_ld = article
+        .and_then(|node| node.get("reviewRating"))
+        .and_then(|rating| json_ld_rating(rating, SCORE_SCALE));
+    let score = from_json_ld
+        .or_else(|| parse_fractional_rating(&element_text(html, SCORE_MARKER)?))
+        .or_else(|| score_in_text(&review_text(html)?))?;
+    Some(score).filter(|score| (0.0..=SCORE_SCALE).contains(score))
+}
+
+/// The last "N/10" in `text`, as older reviews sign off with it ("... a
+/// promising step. 7/10"). "/100" and dates like "10/10/2008" don't count.
+fn score_in_text(text: &str) -> Option<f64> {
+    let positions: Vec<usize> = text.match_indices("/10").map(|(pos, _)| pos).collect();
+    positions.into_iter().rev().find_map(|pos| {
+        let after = &text[pos + "/10".len()..];
+        if after.starts_with(|c: char| c.is_ascii_digit() || c == '/') {
+            return None;
+        }
+        let before = &text[..pos];
+        let value_start = before
+            .rfind(|c: char| !c.is_ascii_digit() && c != '.')
+            .map_or(0, |i| i + 1);
+        if before[..value_start].ends_with('/') {
+            return None;
+        }
+        parse_fractional_rating(&text[value_start..pos + "/10".len()])
+    })
+}
+
+/// The review's paragraphs, up to the end of the article.
+fn review_text(html: &str) -> Option<String> {
+    let start = html.find(BODY_MARKER)?;
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |end| start + end);
+    Some(paragraph_text(&html[start..end])).filter(|text| !text.is_empty())
+}
+
+/// The review text cut to ~2000 chars at a sentence boundary, without a
+/// closing paragraph that only gives the score.
+fn parse_review_body(html: &str) -> Option<String> {
+    let text = review_text(html)?;
+    let paragraphs: Vec<&str> = text
+        .split("\n\n")
+        .filter(|paragraph| parse_fractional_rating(paragraph).is_none())
+        .collect();
+    let text = truncate_at_sentence(&paragraphs.join("\n\n"), MAX_EXCERPT_CHARS);
+    Some(text).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://noripcord.com";
+
+    #[test]
+    fn music_reviews_sit_under_their_own_subsection() {
+        let slug = |path: &str| review_slug(&format!("{}{}", BASE, path));
+        let review = slug("/reviews/music/wet-leg-moisturizer?replytocom=12#respond");
+        assert_eq!(review.as_deref(), Some("wet-leg-moisturizer"));
+        assert_eq!(slug("/reviews/music"), None);
+        assert_eq!(slug("/reviews/music/"), None);
+        assert_eq!(slug("/reviews/film/moisturizer"), None);
+        assert_eq!(slug("/reviews/tv/wet-leg-moisturizer"), None);
+    }
+
+    #[test]
+    fn search_page_keeps_music_reviews_only() {
+        let html = r#"<a href="/reviews/music">Music</a>
+            <a href="/reviews/film/moisturizer">Film</a>
+            <a href="/features/wet-leg-interview">Interview</a>
+            <a href="/reviews/music/wet-leg-wet-leg">Wet Leg</a>
+            <a href="https://noripcord.com/reviews/music/wet-leg-moisturizer">Review</a>"#;
+        let album = Query::new("wet-leg", "moisturizer");
+        let result = parse_search_page(html, BASE, &album);
+        let slugs: Vec<&str> = result.candidates.iter().map(|c| c.slug.as_str()).collect();
+        assert_eq!(slugs, ["wet-leg-wet-leg", "wet-leg-moisturizer"]);
+        let chosen = format!("{}/reviews/music/wet-leg-moisturizer", BASE);
+        assert_eq!(result.chosen, Some(chosen));
+    }
+
+    #[test]
+    fn score_read_from_older_review_text() {
+        let html = r#"<head><meta name="author" content="Juan Edgardo Rodriguez">
+            <meta property="article:published_time" content="2025-07-15T09:00:00+00:00"></head>
+            <article><div class="review-body"><p>Recorded 10/10/2024, it is louder.</p>
+            <p>A promising step.</p><p>7/10</p></div></article>"#;
+        let url = format!("{}/reviews/music/wet-leg-moisturizer", BASE);
+        let review = parse_review_page(&url, html).unwrap();
+        assert_eq!(review.rating, Some(7.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Juan Edgardo Rodriguez"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-15"));
+        let excerpt = review.excerpt.unwrap();
+        let expected = "Recorded 10/10/2024, it is louder.\n\nA promising step.";
+        assert_eq!(excerpt, expected);
+
+        let markup = r#"<div class="review-score"><span>8</span>/10</div>"#;
+        assert_eq!(parse_score(None, markup), Some(8.0));
+        assert_eq!(score_in_text("Scored 85/100 elsewhere."), None);
+        assert_eq!(score_in_text("In short: 6.5/10."), Some(6.5));
+    }
+}
@@ -1,16 +1,12 @@
-mod northern_transmissions;
+pub mod northern_transmissions;
 
-use editorial_common::{wrap_review, AlbumReviewInput};
-use extism_pdk::*;
+/// Review fields this source can populate.
+pub const CAPABILITIES: &[&str] = &["rating", "excerpt", "reviewer", "date"];
 
-#[plugin_fn]
-pub fn riff_health_check(_input: String) -> FnResult<String> {
-    Ok("ok".to_string())
-}
-
-#[plugin_fn]
-pub fn riff_get_album_reviews(input: String) -> FnResult<String> {
-    let params: AlbumReviewInput = serde_json::from_str(&input)?;
-    let review = northern_transmissions::fetch_review(&params.artist, &params.title);
-    Ok(wrap_review("northern-transmissions", review))
-}
+editorial_common::editorial_plugin!(
+    northern_transmissions,
+    "northern-transmissions",
+    CAPABILITIES,
+    // A reachable site whose REST API is failing searches is still degraded
+    api_error: northern_transmissions::last_api_error,
+);
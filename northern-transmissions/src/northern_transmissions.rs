@@ -1,7 +1,35 @@
-use editorial_common::{clean_title, slugify, url_encode, SiteReview};
+use editorial_common::{
+    best_candidate, build_request, cached_review_url, clean_title, configured_base_url,
+    decode_html_entities, fetch_api_page, fetch_page, forget_review_url, parse_fractional_rating,
+    record_review_url, score_candidate, strip_html_tags, truncate_at_sentence, url_encode,
+    AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query, SearchCandidate, SearchResult,
+    SiteReview, MAX_EXCERPT_CHARS,
+};
 use extism_pdk::*;
 use serde::Deserialize;
 
+/// Name this source's entries are kept under in the shared caches.
+const SOURCE: &str = "northern-transmissions";
+
+/// Production site root; override with the `nt_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://northerntransmissions.com";
+const BASE_URL_CONFIG: &str = "nt_base_url";
+/// Extism config key holding a comma-separated list of WP category IDs.
+const CATEGORIES_CONFIG: &str = "nt_categories";
+/// Album reviews category used when no override is configured.
+const DEFAULT_CATEGORIES: &str = "15";
+/// Posts requested per WP REST search page.
+const PER_PAGE: u32 = 10;
+/// Upper bound on search pages fetched per query.
+const MAX_SEARCH_PAGES: u32 = 2;
+/// Extism var recording the last WP REST API error code (cleared on success).
+const API_ERROR_VAR: &str = "nt_api_error";
+
+/// The site root, honoring the `nt_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
 /// WordPress REST API post structure (relevant fields only).
 #[derive(Deserialize)]
 struct WpPost {
@@ -16,162 +44,406 @@ struct WpContent {
     rendered: Option<String>,
 }
 
+/// WordPress REST API error object, e.g. `{"code":"rest_invalid_param",...}`.
+#[derive(Deserialize)]
+struct WpError {
+    code: Option<String>,
+}
+
 /// Attempt to fetch a Northern Transmissions review for the given album.
-pub fn fetch_review(artist: &str, title: &str) -> Option<SiteReview> {
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(&input.artist, &input.title).into()
+}
+
+/// Resolve the album and read only its rating from the review page, skipping
+/// the excerpt and reviewer extraction.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(&input.artist, &input.title).into()
+}
+
+fn lookup_rating(artist: &str, title: &str) -> Result<SiteReview, FetchError> {
     let cleaned = clean_title(title);
-    let (review_url, content_html, date) = search_for_review(artist, cleaned)?;
-
-    // Extract excerpt from REST API content (strip HTML tags)
-    let excerpt = content_html
-        .as_ref()
-        .map(|html| strip_html_tags(html))
-        .map(|text| {
-            let trimmed = text.trim();
-            // Truncate to ~2000 chars at a sentence boundary
-            if trimmed.len() > 2000 {
-                if let Some(pos) = trimmed[..2000].rfind(". ") {
-                    trimmed[..=pos].to_string()
-                } else {
-                    let mut s = trimmed[..2000].to_string();
-                    s.push_str("...");
-                    s
-                }
-            } else {
-                trimmed.to_string()
+    let (review_url, page_html) = match cached_page(artist, cleaned)? {
+        Some(page) => page,
+        None => {
+            let (review_url, _, _) = search_for_review(artist, cleaned)?;
+            let page_html = fetch_page(&build_request(&review_url, "text/html"))?;
+            (review_url, page_html)
+        }
+    };
+
+    let confidence = post_confidence(&review_url, artist, cleaned);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(parse_rating(&page_html).map(|r| (r, 10.0)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// The URL and page of the review an earlier lookup resolved the album to. A
+/// URL that no longer resolves is forgotten, leaving the album to be
+/// searched again.
+fn cached_page(artist: &str, title: &str) -> Result<Option<(String, String)>, FetchError> {
+    let Some(url) = cached_review_url(SOURCE, artist, title) else {
+        return Ok(None);
+    };
+    match fetch_page(&build_request(&url, "text/html")) {
+        Ok(html) => Ok(Some((url, html))),
+        Err(FetchError::NotFound) => {
+            forget_review_url(SOURCE, artist, title);
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Parse the review at a known Northern Transmissions URL, skipping search.
+/// The post is looked up by slug in the REST API for its content and date.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let slug = post_slug(url).ok_or(FetchError::NotFound)?;
+    let encoded = url_encode(slug);
+    let post_url = format!("{}/wp-json/wp/v2/posts?slug={}", base_url(), encoded);
+    let body = fetch_page(&build_request(&post_url, "application/json"))?;
+    let post = parse_search_body(&body)
+        .map_err(|_| FetchError::Error)?
+        .into_iter()
+        .next()
+        .ok_or(FetchError::NotFound)?;
+    let content_html = post.content.and_then(|c| c.rendered);
+    review_from_post((post.link, content_html, post.date))
+}
+
+/// Search for the album's review and build it from the matched post. A post
+/// resolved by an earlier lookup is read by its slug instead of searching.
+fn lookup_review(artist: &str, title: &str) -> Result<SiteReview, FetchError> {
+    let cleaned = clean_title(title);
+    let confidence = |url: &str| post_confidence(url, artist, cleaned);
+
+    if let Some(url) = cached_review_url(SOURCE, artist, cleaned) {
+        match lookup_url(&url) {
+            Ok(mut review) => {
+                review.match_confidence = confidence(&url);
+                return Ok(review);
             }
-        })
-        .filter(|s| !s.is_empty());
+            Err(FetchError::NotFound) => forget_review_url(SOURCE, artist, cleaned),
+            Err(err) => return Err(err),
+        }
+    }
+
+    let hit = search_for_review(artist, cleaned)?;
+    let match_confidence = confidence(&hit.0);
+    let mut review = review_from_post(hit)?;
+    review.match_confidence = match_confidence;
+    Ok(review)
+}
+
+/// The post slug's `score_candidate` score, the one it was picked by.
+fn post_confidence(review_url: &str, artist: &str, title: &str) -> Option<f64> {
+    let album = Query::new(artist, title);
+    post_slug(review_url).map(|slug| score_candidate(&album, &Candidate::new(slug)))
+}
+
+/// Excerpt from REST API content: shortcodes, figures and HTML tags
+/// stripped and entities decoded, cut to ~2000 chars at a sentence boundary.
+fn content_excerpt(content_html: &str) -> Option<String> {
+    let stripped = strip_html_tags(&strip_figures(&strip_shortcodes(content_html)));
+    let text = decode_html_entities(&stripped);
+    Some(truncate_at_sentence(text.trim(), MAX_EXCERPT_CHARS)).filter(|s| !s.is_empty())
+}
+
+/// Build a review from a matched post: excerpt and date from the REST API,
+/// rating and reviewer from the post's page.
+fn review_from_post(hit: SearchHit) -> Result<SiteReview, FetchError> {
+    let (review_url, content_html, date) = hit;
+
+    let excerpt = content_html.as_deref().and_then(content_excerpt);
 
     // Fetch the actual page HTML for rating and reviewer (not in REST API)
-    let req = HttpRequest::new(&review_url).with_header("Accept", "text/html");
-    let resp = http::request::<()>(&req, None).ok()?;
-    if resp.status_code() != 200 {
+    let Ok(page_html) = fetch_page(&build_request(&review_url, "text/html")) else {
         // Even without the page, we have excerpt + date from the API
-        return Some(SiteReview {
-            source_url: review_url,
-            excerpt,
-            rating: None,
-            rating_count: None,
-            reviewer: None,
-            review_date: date,
-        });
-    }
-
-    let page_html = String::from_utf8(resp.body().to_vec()).ok()?;
+        return Ok(SiteReview::builder(review_url)
+            .excerpt(excerpt)
+            .review_date(date)
+            .build());
+    };
     let rating = parse_rating(&page_html);
-    let reviewer = parse_reviewer(&page_html);
+    let reviewer = parse_reviewer(&page_html)
+        .or_else(|| content_html.as_deref().and_then(parse_byline))
+        .or_else(|| fetch_embedded_author(&review_url));
 
     if rating.is_none() && excerpt.is_none() {
-        return None;
+        return Err(FetchError::NotFound);
     }
 
-    Some(SiteReview {
-        source_url: review_url,
-        excerpt,
-        rating,
-        rating_count: None,
-        reviewer,
-        review_date: date,
-    })
+    Ok(SiteReview::builder(review_url)
+        .excerpt(excerpt)
+        .native_rating(rating.map(|r| (r, 10.0)))
+        .reviewer(reviewer)
+        .review_date(date)
+        .build())
+}
+
+/// A matched post: (url, content_html, date).
+type SearchHit = (String, Option<String>, Option<String>);
+
+/// Posts returned by the searches that were run, and the index of the chosen one.
+struct SearchPosts {
+    posts: Vec<WpPost>,
+    chosen: Option<usize>,
+}
+
+/// List the posts the WordPress search offers for an album and the one
+/// `fetch_review` would pick, without fetching the review page.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    let artist = input.artist.as_str();
+    let cleaned = clean_title(&input.title);
+    let album = Query::new(artist, cleaned);
+
+    let found =
+        search_posts(artist, cleaned, fetch_search_page).map(|SearchPosts { posts, chosen }| {
+            let mut result = SearchResult {
+                chosen: chosen.map(|index| posts[index].link.clone()),
+                ..SearchResult::default()
+            };
+            for post in posts {
+                result.add(SearchCandidate::scored(post.link, post.slug, &album));
+            }
+            result
+        });
+    found.into()
 }
 
-/// Search the WordPress REST API for a matching review.
-/// Returns (url, content_html, date) on success.
-fn search_for_review(artist: &str, title: &str) -> Option<(String, Option<String>, Option<String>)> {
-    let title_slug = slugify(title);
-    let artist_slug = slugify(artist);
+/// Search the WordPress REST API for a matching review, remembering the
+/// post's URL for later lookups of the album.
+fn search_for_review(artist: &str, title: &str) -> Result<SearchHit, FetchError> {
+    let SearchPosts { mut posts, chosen } = search_posts(artist, title, fetch_search_page)?;
+    let post = posts.swap_remove(chosen.ok_or(FetchError::NotFound)?);
+    record_review_url(SOURCE, artist, title, &post.link);
+    let content_html = post.content.and_then(|c| c.rendered);
+    Ok((post.link, content_html, post.date))
+}
 
-    // Try artist + title first
-    let query = format!("{} {}", artist, title);
-    if let Some(result) = search_and_match(&query, &title_slug, &artist_slug) {
-        return Some(result);
+/// Search with artist + title, falling back to just the artist name, and
+/// keep every post seen along with the best match. Search pages come from
+/// `fetch_page(query, page)`.
+///
+/// A failed API call doesn't stop the artist-only query, but when nothing
+/// matches it is reported instead of a miss, since the failed query might
+/// have found the review.
+fn search_posts(
+    artist: &str,
+    title: &str,
+    fetch_page: impl Fn(&str, u32) -> Result<Vec<WpPost>, FetchError>,
+) -> Result<SearchPosts, FetchError> {
+    let album = Query::new(artist, title);
+    if album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let mut seen = Vec::new();
+    let mut failure = None;
+
+    for query in [format!("{} {}", artist, title), artist.to_string()] {
+        let found = search_and_match(&query, &album, &fetch_page);
+        let (posts, chosen) = match found {
+            Ok(found) => found,
+            Err(FetchError::NotFound) => continue,
+            Err(FetchError::Error) => {
+                failure = Some(FetchError::Error);
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+        let offset = seen.len();
+        seen.extend(posts);
+        if let Some(index) = chosen {
+            return Ok(SearchPosts {
+                posts: seen,
+                chosen: Some(offset + index),
+            });
+        }
     }
 
-    // Fallback: search with just artist name
-    search_and_match(artist, &title_slug, &artist_slug)
+    match failure {
+        Some(err) => Err(err),
+        None => Ok(SearchPosts {
+            posts: seen,
+            chosen: None,
+        }),
+    }
 }
 
-/// Query the WordPress REST API and match results by slug.
+/// Query the WordPress REST API and match results by slug, returning the
+/// posts fetched and the index of the best match.
+/// A second page is fetched only when the first is full and yielded no
+/// artist+title match, bounding the search to `MAX_SEARCH_PAGES * PER_PAGE` posts.
+/// A failed first page is reported as-is; a failed later page just ends the scan.
 fn search_and_match(
     query: &str,
-    title_slug: &str,
-    artist_slug: &str,
-) -> Option<(String, Option<String>, Option<String>)> {
+    album: &Query,
+    fetch_page: impl Fn(&str, u32) -> Result<Vec<WpPost>, FetchError>,
+) -> Result<(Vec<WpPost>, Option<usize>), FetchError> {
+    let mut posts: Vec<WpPost> = Vec::new();
+
+    for page in 1..=MAX_SEARCH_PAGES {
+        let page_posts = match fetch_page(query, page) {
+            Ok(page_posts) => page_posts,
+            Err(err) if page == 1 => return Err(err),
+            Err(_) => break,
+        };
+        let page_full = page_posts.len() >= PER_PAGE as usize;
+        posts.extend(page_posts);
+
+        let strong = matches!(best_post(&posts, album), Some((_, true)));
+        if strong || !page_full {
+            break;
+        }
+    }
+
+    let chosen = best_post(&posts, album).map(|(index, _)| index);
+    Ok((posts, chosen))
+}
+
+/// Fetch one page of WordPress REST search results.
+fn fetch_search_page(query: &str, page: u32) -> Result<Vec<WpPost>, FetchError> {
     let encoded = url_encode(query);
     let search_url = format!(
-        "https://northerntransmissions.com/wp-json/wp/v2/posts?categories=15&search={}&per_page=5",
-        encoded
+        "{}/wp-json/wp/v2/posts?categories={}&search={}&per_page={}&page={}",
+        base_url(),
+        review_categories(),
+        encoded,
+        PER_PAGE,
+        page
     );
 
-    let req = HttpRequest::new(&search_url).with_header("Accept", "application/json");
-    let resp = http::request::<()>(&req, None).ok()?;
-    if resp.status_code() != 200 {
-        return None;
+    let response = fetch_api_page(&build_request(&search_url, "application/json"));
+    match read_search_response(response) {
+        Ok(posts) => {
+            let _ = var::remove(API_ERROR_VAR);
+            Ok(posts)
+        }
+        Err((err, code)) => {
+            // The health check reports the recorded code
+            if let Some(code) = code {
+                let _ = var::set(API_ERROR_VAR, code.as_bytes());
+            }
+            Err(err)
+        }
     }
+}
 
-    let body = String::from_utf8(resp.body().to_vec()).ok()?;
-    let posts: Vec<WpPost> = serde_json::from_str(&body).ok()?;
+/// The posts in a search response, or why there are none along with the WP
+/// REST error code to record. WordPress sends its error objects with a 4xx
+/// status (`rest_invalid_param` with a 400), so a failed response's body is
+/// read for a code too; a 200 that isn't a posts array is a transient
+/// failure.
+fn read_search_response(
+    response: Result<String, (FetchError, String)>,
+) -> Result<Vec<WpPost>, (FetchError, Option<String>)> {
+    match response {
+        Ok(body) => parse_search_body(&body).map_err(|code| (FetchError::Error, Some(code))),
+        Err((err, body)) => {
+            let code = serde_json::from_str::<WpError>(&body)
+                .ok()
+                .and_then(|e| e.code);
+            Err((err, code))
+        }
+    }
+}
 
-    // Find the best matching post by slug
-    // Prefer posts whose slug contains both title_slug and artist_slug
-    let mut best_match: Option<&WpPost> = None;
-    let mut best_has_artist = false;
+/// Parse a WP REST search response body.
+/// Returns the API error code when the body is an error object (or otherwise
+/// not a posts array) rather than silently treating it as "no match".
+fn parse_search_body(body: &str) -> Result<Vec<WpPost>, String> {
+    if let Ok(posts) = serde_json::from_str::<Vec<WpPost>>(body) {
+        return Ok(posts);
+    }
+    let code = serde_json::from_str::<WpError>(body)
+        .ok()
+        .and_then(|e| e.code)
+        .unwrap_or_else(|| "invalid_response".to_string());
+    Err(code)
+}
 
-    for post in &posts {
-        if !post.slug.contains(title_slug) {
-            continue;
-        }
+/// The last WP REST API error code seen by a search, if the most recent
+/// search request failed.
+pub fn last_api_error() -> Option<String> {
+    let bytes: Option<Vec<u8>> = var::get(API_ERROR_VAR).ok().flatten();
+    bytes.and_then(|b| String::from_utf8(b).ok())
+}
 
-        // Length ratio guard: title_slug should be at least 30% of the full slug
-        // (NT slugs combine artist + album, so they're longer)
-        if !title_slug.is_empty() && !post.slug.is_empty() {
-            let ratio = title_slug.len() as f64 / post.slug.len() as f64;
-            if ratio < 0.3 {
-                continue;
-            }
-        }
+/// Find the index of the best matching post by slug (see `best_candidate`);
+/// the returned flag is true when the chosen post's slug also names the
+/// artist, under any of its aliases.
+fn best_post(posts: &[WpPost], album: &Query) -> Option<(usize, bool)> {
+    let indices: Vec<usize> = (0..posts.len()).collect();
+    let slug_of = |&index: &usize| Some(Candidate::new(posts[index].slug.as_str()));
+    let index = *best_candidate(album, &indices, slug_of)?;
+    let slug = &posts[index].slug;
+    let has_artist = !album.artist_slug.is_empty()
+        && std::iter::once(&album.artist_slug)
+            .chain(&album.artist_aliases)
+            .any(|artist| slug.contains(artist.as_str()));
+    Some((index, has_artist))
+}
 
-        let has_artist = !artist_slug.is_empty() && post.slug.contains(artist_slug);
+/// Read the review category IDs from Extism config, falling back to the default.
+/// Non-numeric entries are dropped so a bad value can't break the query.
+fn review_categories() -> String {
+    let configured = config::get(CATEGORIES_CONFIG).ok().flatten();
+    configured
+        .and_then(|raw| parse_categories(&raw))
+        .unwrap_or_else(|| DEFAULT_CATEGORIES.to_string())
+}
 
-        if has_artist && !best_has_artist {
-            best_match = Some(post);
-            best_has_artist = true;
-        } else if best_match.is_none() {
-            best_match = Some(post);
+/// Normalize a comma-separated category list, e.g. " 15, 22, 15 " -> "15,22".
+fn parse_categories(raw: &str) -> Option<String> {
+    let mut ids: Vec<&str> = Vec::new();
+    for id in raw.split(',').map(str::trim) {
+        if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) && !ids.contains(&id) {
+            ids.push(id);
         }
     }
-
-    best_match.map(|post| {
-        let content_html = post
-            .content
-            .as_ref()
-            .and_then(|c| c.rendered.clone());
-        (post.link.clone(), content_html, post.date.clone())
-    })
+    if ids.is_empty() {
+        None
+    } else {
+        Some(ids.join(","))
+    }
 }
 
 /// Extract a numeric rating (0-10) from the page HTML.
 /// The rating appears in `<h2 class="review">` or `<h2 class="score">` tags
-/// (Beaver Builder template). Tags have attributes, so we match by prefix.
+/// (Beaver Builder template), and on some posts in `<div class="rating">`.
+/// Tags have attributes, so we match by prefix.
 fn parse_rating(html: &str) -> Option<f64> {
     // Primary: <h2 class="review">9</h2> (the album's own rating)
-    if let Some(rating) = extract_rating_from_tags(html, "<h2 class=\"review\"", "</h2>") {
+    if let Some(rating) = extract_rating_from_tags(html, "<h2 class=\"review\"", "</h2>", false) {
         return Some(rating);
     }
 
-    // Fallback: any <h2> tag containing a rating
-    if let Some(rating) = extract_rating_from_tags(html, "<h2", "</h2>") {
-        return Some(rating);
+    // Fallbacks only accept bare integers that sit near a rating marker, so a
+    // stray "10" in an unrelated heading isn't mistaken for the score.
+    for (open_prefix, close_tag) in [("<div", "</div>"), ("<h2", "</h2>"), ("<span", "</span>")] {
+        if let Some(rating) = extract_rating_from_tags(html, open_prefix, close_tag, true) {
+            return Some(rating);
+        }
     }
 
-    // Last resort: <span> tags
-    extract_rating_from_tags(html, "<span", "</span>")
+    None
 }
 
 /// Scan for tags (matched by prefix) and try to parse their text content as a rating.
 /// The open_tag is a prefix like `<h2` or `<h2 class="review"` — we skip to `>` to
-/// handle attributes.
-fn extract_rating_from_tags(html: &str, open_prefix: &str, close_tag: &str) -> Option<f64> {
+/// handle attributes. With `require_context`, a bare integer is only accepted
+/// when `has_rating_context` finds a rating marker around the tag.
+fn extract_rating_from_tags(
+    html: &str,
+    open_prefix: &str,
+    close_tag: &str,
+    require_context: bool,
+) -> Option<f64> {
     let mut search_from = 0;
 
     loop {
@@ -192,8 +464,12 @@ fn extract_rating_from_tags(html: &str, open_prefix: &str, close_tag: &str) -> O
         let inner = strip_html_tags(&html[abs_start..abs_end]);
         let text = inner.trim();
 
-        if let Some(rating) = try_parse_rating(text) {
-            return Some(rating);
+        if let Some((rating, explicit)) = try_parse_rating(text) {
+            let open_tag = &html[abs_tag_start..abs_start];
+            let after = abs_end + close_tag.len();
+            if !require_context || explicit || has_rating_context(html, open_tag, after) {
+                return Some(rating);
+            }
         }
 
         search_from = abs_end + close_tag.len();
@@ -205,56 +481,454 @@ fn extract_rating_from_tags(html: &str, open_prefix: &str, close_tag: &str) -> O
     None
 }
 
-/// Try to parse a text string as a rating value 0-10.
-/// Handles formats like "7.5", "8", "7.5/10", "8/10".
-fn try_parse_rating(text: &str) -> Option<f64> {
-    // Strip optional "/10" suffix
-    let text = text.strip_suffix("/10").unwrap_or(text).trim();
-
-    // Must be a short numeric string (avoid matching paragraphs)
-    if text.len() > 5 || text.is_empty() {
-        return None;
+/// Check whether a candidate rating is backed by a nearby rating marker:
+/// a class mentioning "rating"/"score", or "out of" / "/10" immediately
+/// following the tag.
+fn has_rating_context(html: &str, open_tag: &str, after: usize) -> bool {
+    let open_tag = open_tag.to_ascii_lowercase();
+    if open_tag.contains("rating") || open_tag.contains("score") {
+        return true;
     }
 
-    let val: f64 = text.parse().ok()?;
-    if (0.0..=10.0).contains(&val) {
-        Some(val)
-    } else {
-        None
+    let mut window_end = (after + 40).min(html.len());
+    while !html.is_char_boundary(window_end) {
+        window_end -= 1;
     }
+    let following = strip_html_tags(&html[after..window_end]).to_ascii_lowercase();
+    let following = following.trim_start();
+    following.starts_with("out of") || following.starts_with("/10") || following.starts_with("/ 10")
+}
+
+/// Try to parse a text string as a rating, scaled to 0-10.
+/// Handles formats like "7.5", "8", "8/10", "4/5" and "3.5 out of 5". Also
+/// reports whether the form alone marks it as a rating (a decimal or an
+/// explicit scale), as opposed to a bare integer that could be a track number
+/// or list position.
+fn try_parse_rating(text: &str) -> Option<(f64, bool)> {
+    let rating = parse_fractional_rating(text)?;
+    let explicit = text.contains(['.', '/']) || text.to_ascii_lowercase().contains(" out of ");
+    Some((rating, explicit))
 }
 
 /// Extract reviewer name from "Words by {Name}" pattern in page HTML.
 fn parse_reviewer(html: &str) -> Option<String> {
     let marker = "Words by ";
     let pos = html.find(marker)?;
-    let name_start = pos + marker.len();
+    capture_name(&html[pos + marker.len()..])
+}
+
+/// Extract a "By {Name}" byline that starts a text node in the rendered content.
+fn parse_byline(html: &str) -> Option<String> {
+    for marker in [">By ", "\nBy "] {
+        if let Some(pos) = html.find(marker) {
+            if let Some(name) = capture_name(&html[pos + marker.len()..]) {
+                return Some(name);
+            }
+        }
+    }
+    None
+}
 
-    // Find the next HTML tag or newline after the name
-    let rest = &html[name_start..];
-    let end = rest
-        .find(['<', '\n'])
+/// Entities decoded in a captured byline name.
+const NAME_ENTITIES: &[(&str, char)] = &[
+    ("&nbsp;", ' '),
+    ("&#160;", ' '),
+    ("&amp;", '&'),
+    ("&#038;", '&'),
+    ("&#39;", '\''),
+    ("&#039;", '\''),
+    ("&apos;", '\''),
+    ("&#8217;", '\u{2019}'),
+    ("&rsquo;", '\u{2019}'),
+    ("&bull;", '\u{2022}'),
+    ("&#8226;", '\u{2022}'),
+];
+
+/// Capture a name up to the next HTML tag, newline, or em-dash, decoding
+/// entities and dropping a trailing separator such as "|" or "\u{2022}".
+fn capture_name(rest: &str) -> Option<String> {
+    let end = ["<", "\n", "\u{2014}", "&#8212;", "&mdash;"]
+        .iter()
+        .filter_map(|stop| rest.find(stop))
+        .min()
         .unwrap_or(rest.len());
 
-    let name = rest[..end].trim();
+    let decoded = decode_name_entities(&rest[..end]);
+    let name = decoded
+        .split(['|', '\u{2022}'])
+        .next()
+        .and_then(|name| name.split(" - ").next())
+        .unwrap_or_default()
+        .trim_end_matches(|c: char| c.is_whitespace() || c == '-' || c == ',')
+        .trim();
     if name.is_empty() {
         None
     } else {
-        Some(name.to_string())
+        Some(name.split_whitespace().collect::<Vec<_>>().join(" "))
     }
 }
 
-/// Strip HTML tags from a string, keeping only text content.
-fn strip_html_tags(html: &str) -> String {
-    let mut result = String::with_capacity(html.len());
-    let mut in_tag = false;
-    for ch in html.chars() {
-        match ch {
-            '<' => in_tag = true,
-            '>' => in_tag = false,
-            _ if !in_tag => result.push(ch),
-            _ => {}
+/// Decode `NAME_ENTITIES`, stopping at any other entity since it's more
+/// likely markup trailing the name than part of it. A bare "&" is kept.
+fn decode_name_entities(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        result.push_str(&rest[..amp]);
+        let entity = &rest[amp..];
+        let known = NAME_ENTITIES
+            .iter()
+            .find(|(name, _)| entity.starts_with(name));
+        if let Some((name, ch)) = known {
+            result.push(*ch);
+            rest = &entity[name.len()..];
+            continue;
+        }
+        let is_entity = entity[1..].split_once(';').is_some_and(|(name, _)| {
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '#')
+        });
+        if is_entity {
+            return result;
+        }
+        result.push('&');
+        rest = &entity[1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// WordPress post with `_embed` data (author only).
+#[derive(Deserialize)]
+struct WpEmbeddedPost {
+    #[serde(rename = "_embedded")]
+    embedded: Option<WpEmbedded>,
+}
+
+#[derive(Deserialize)]
+struct WpEmbedded {
+    author: Option<Vec<WpAuthor>>,
+}
+
+#[derive(Deserialize)]
+struct WpAuthor {
+    name: Option<String>,
+}
+
+/// Look up the post's author via the REST API with `_embed`.
+/// The post slug is taken from the last path segment of the review URL.
+fn fetch_embedded_author(review_url: &str) -> Option<String> {
+    let slug = post_slug(review_url)?;
+
+    let url = format!(
+        "{}/wp-json/wp/v2/posts?slug={}&_embed=author",
+        base_url(),
+        url_encode(slug)
+    );
+    let body = fetch_page(&build_request(&url, "application/json")).ok()?;
+    let posts: Vec<WpEmbeddedPost> = serde_json::from_str(&body).ok()?;
+    posts
+        .into_iter()
+        .filter_map(|p| p.embedded?.author)
+        .flatten()
+        .find_map(|a| a.name.filter(|n| !n.trim().is_empty()))
+}
+
+/// The post slug: the last non-empty path segment of a post URL.
+fn post_slug(url: &str) -> Option<&str> {
+    url.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|slug| !slug.is_empty())
+}
+
+/// WordPress shortcodes that are removed (with their enclosed content) from excerpts.
+/// Only these names are stripped so bracketed text in prose is left alone.
+const SHORTCODES: &[&str] = &[
+    "caption",
+    "wp_caption",
+    "embed",
+    "gallery",
+    "audio",
+    "video",
+    "playlist",
+];
+
+/// Remove leftover WordPress shortcode blocks like `[caption ...]...[/caption]`.
+/// Unclosed shortcodes have just their opening tag removed.
+fn strip_shortcodes(html: &str) -> String {
+    let mut result = html.to_string();
+    for name in SHORTCODES {
+        let open = format!("[{}", name);
+        let close = format!("[/{}]", name);
+        let mut search_from = 0;
+        while let Some(pos) = result[search_from..].find(&open) {
+            let start = search_from + pos;
+            let after_name = start + open.len();
+            // Require a real shortcode boundary so "[captions]" etc. aren't touched
+            if !matches!(result.as_bytes().get(after_name), Some(b' ') | Some(b']')) {
+                search_from = after_name;
+                continue;
+            }
+            let Some(open_end) = result[start..].find(']').map(|p| start + p + 1) else {
+                break;
+            };
+            let end = match result[open_end..].find(&close) {
+                Some(p) => open_end + p + close.len(),
+                None => open_end,
+            };
+            result.replace_range(start..end, "");
+            search_from = start;
+        }
+    }
+    result
+}
+
+/// Remove `<figure>` and `<figcaption>` elements (image credits, captions).
+fn strip_figures(html: &str) -> String {
+    let mut result = html.to_string();
+    for tag in ["figure", "figcaption"] {
+        let open = format!("<{}", tag);
+        let close = format!("</{}>", tag);
+        let mut search_from = 0;
+        while let Some(pos) = result[search_from..].find(&open) {
+            let start = search_from + pos;
+            let after_name = start + open.len();
+            if !matches!(result.as_bytes().get(after_name), Some(b' ') | Some(b'>')) {
+                search_from = after_name;
+                continue;
+            }
+            let Some(end) = result[start..]
+                .find(&close)
+                .map(|p| start + p + close.len())
+            else {
+                break;
+            };
+            result.replace_range(start..end, "");
+            search_from = start;
         }
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excerpt_cut_never_splits_a_character() {
+        // A curly quote straddles byte 2000 and there's no sentence break
+        let content = format!("<p>{}\u{201c}{}</p>", "x".repeat(1999), "y".repeat(600));
+        let excerpt = content_excerpt(&content).unwrap();
+        assert!(excerpt.len() > 2000);
+        assert_eq!(excerpt.chars().count(), MAX_EXCERPT_CHARS + 3);
+        assert!(excerpt.ends_with("\u{201c}..."));
+
+        let short = content_excerpt("<p>Short. And sweet.</p>");
+        assert_eq!(short.as_deref(), Some("Short. And sweet."));
+        assert_eq!(content_excerpt("<figure><img></figure>"), None);
+    }
+
+    #[test]
+    fn rating_from_div() {
+        let html = r#"<div class="post"><p>Some intro text for the review body.</p></div>
+            <div class="rating">7.5</div>
+            <p>Footer content that pads out the remainder of the page markup.</p>"#;
+        assert_eq!(parse_rating(html), Some(7.5));
+    }
+
+    #[test]
+    fn rating_ignores_decoy_heading() {
+        let html = r#"<h2>10 Best Albums</h2><p>Sidebar list of recommended records.</p>
+            <h2>10</h2><p>Another unrelated sidebar widget with a bare number.</p>
+            <span class="score-value">8</span><span>out of 10</span>
+            <p>Footer content that pads out the remainder of the page markup.</p>"#;
+        assert_eq!(parse_rating(html), Some(8.0));
+    }
+
+    #[test]
+    fn rating_needs_rating_like_form_or_marker() {
+        assert_eq!(try_parse_rating("7.5"), Some((7.5, true)));
+        assert_eq!(try_parse_rating("8/10"), Some((8.0, true)));
+        assert_eq!(try_parse_rating("4/5"), Some((8.0, true)));
+        assert_eq!(try_parse_rating("8"), Some((8.0, false)));
+        for text in ["1e1", "+5", "NaN", "7.", ".5", "7.555", "11", "05:32"] {
+            assert_eq!(try_parse_rating(text), None, "{}", text);
+        }
+
+        // A tracklist's bare track numbers are skipped; the decimal is the rating
+        let html = r#"<ol class="tracklist"><li><span>8</span> Closing Track</li></ol>
+            <p>Some intro text for the review body, long enough to pad things.</p>
+            <span>7.5</span>
+            <p>Footer content that pads out the remainder of the page markup.</p>"#;
+        assert_eq!(parse_rating(html), Some(7.5));
+
+        let html = r#"<h2>5</h2><p>Sidebar list widget with a bare number in its heading.</p>
+            <p>Footer content that pads out the remainder of the page markup.</p>"#;
+        assert_eq!(parse_rating(html), None);
+    }
+
+    #[test]
+    fn excerpt_strips_leading_caption() {
+        let html = r#"[caption id="attachment_1" align="alignnone"]<img src="a.jpg"> Photo by Someone[/caption]<figure><img src="b.jpg"><figcaption>Cover art</figcaption></figure><p>A bold [sic] record.</p>"#;
+        let text = strip_html_tags(&strip_figures(&strip_shortcodes(html)));
+        assert_eq!(text.trim(), "A bold [sic] record.");
+    }
+
+    #[test]
+    fn search_body_error_object() {
+        let body = r#"{"code":"rest_invalid_param","message":"Invalid parameter(s): categories","data":{"status":400}}"#;
+        assert_eq!(
+            parse_search_body(body).err(),
+            Some("rest_invalid_param".to_string())
+        );
+        assert!(parse_search_body("[]").unwrap().is_empty());
+
+        // WordPress sends the error object with a 400, not a 200
+        let failed = read_search_response(Err((FetchError::Error, body.to_string())));
+        let (err, code) = failed.err().unwrap();
+        assert_eq!(err, FetchError::Error);
+        assert_eq!(code.as_deref(), Some("rest_invalid_param"));
+        let blocked = read_search_response(Err((FetchError::Blocked, "<html>".to_string())));
+        assert_eq!(blocked.err(), Some((FetchError::Blocked, None)));
+        let garbled = read_search_response(Ok("<html>".to_string()))
+            .err()
+            .unwrap();
+        assert_eq!(garbled.1.as_deref(), Some("invalid_response"));
+    }
+
+    #[test]
+    fn byline_by_name() {
+        let html = "<p><em>By Jane Doe</em></p><p>The record opens with a drone.</p>";
+        assert_eq!(parse_reviewer(html), None);
+        assert_eq!(parse_byline(html), Some("Jane Doe".to_string()));
+
+        let dashed = "<p>By Jane Doe \u{2014} March 2024</p>";
+        assert_eq!(parse_byline(dashed), Some("Jane Doe".to_string()));
+    }
+
+    #[test]
+    fn reviewer_name_drops_entities_and_separators() {
+        let joined = "<p>Words by Jane&nbsp;Doe</p>";
+        assert_eq!(parse_reviewer(joined), Some("Jane Doe".to_string()));
+
+        for byline in [
+            "<p>Words by Jane Doe |</p>",
+            "<p>Words by Jane Doe | Photos by Someone Else</p>",
+            "<p>Words by Jane Doe &bull; March 2024</p>",
+            "<p>Words by Jane Doe -</p>",
+            "<p>Words by Jane Doe&#124; more</p>",
+        ] {
+            let name = parse_reviewer(byline);
+            assert_eq!(name.as_deref(), Some("Jane Doe"), "{}", byline);
+        }
+
+        let names = "<p>Words by Mary-Kate O&#8217;Neil &amp; Sam</p>";
+        let expected = "Mary-Kate O\u{2019}Neil & Sam";
+        assert_eq!(parse_reviewer(names).as_deref(), Some(expected));
+    }
+
+    fn post(slug: &str) -> WpPost {
+        WpPost {
+            slug: slug.to_string(),
+            link: format!("https://northerntransmissions.com/{}/", slug),
+            date: None,
+            content: None,
+        }
+    }
+
+    #[test]
+    fn artist_query_runs_after_an_api_error() {
+        let fetch = |query: &str, _page: u32| match query {
+            "Wet Leg Moisturizer" => Err(FetchError::Error),
+            _ => Ok(vec![post("wet-leg-moisturizer")]),
+        };
+        let found = search_posts("Wet Leg", "Moisturizer", fetch).ok().unwrap();
+        assert_eq!(found.chosen, Some(0));
+
+        // Nothing matched, but the failed query might have: not a miss
+        let unmatched = |query: &str, _page: u32| match query {
+            "Wet Leg Moisturizer" => Err(FetchError::Error),
+            _ => Ok(vec![post("wet-leg-live-at-brixton")]),
+        };
+        let result = search_posts("Wet Leg", "Moisturizer", unmatched);
+        assert_eq!(result.err(), Some(FetchError::Error));
+    }
+
+    #[test]
+    fn categories_drop_bad_and_repeated_ids() {
+        assert_eq!(parse_categories(" 15, 22 ").as_deref(), Some("15,22"));
+        assert_eq!(parse_categories("15,abc,-3,,22").as_deref(), Some("15,22"));
+        assert_eq!(parse_categories("15,22,15").as_deref(), Some("15,22"));
+        assert_eq!(parse_categories(""), None);
+        assert_eq!(parse_categories("reviews, ,"), None);
+    }
+
+    #[test]
+    fn second_page_only_when_first_is_full_and_unmatched() {
+        use std::cell::RefCell;
+
+        let album = Query::new("Wet Leg", "Moisturizer");
+        let pages = RefCell::new(Vec::new());
+        let full_then_match = |_: &str, page: u32| {
+            pages.borrow_mut().push(page);
+            if page == 1 {
+                Ok((0..PER_PAGE)
+                    .map(|i| post(&format!("wet-leg-tour-{}", i)))
+                    .collect())
+            } else {
+                Ok(vec![post("wet-leg-moisturizer")])
+            }
+        };
+        let (posts, chosen) = search_and_match("Wet Leg", &album, full_then_match)
+            .ok()
+            .unwrap();
+        assert_eq!(*pages.borrow(), vec![1, 2]);
+        assert_eq!(chosen, Some(posts.len() - 1));
+
+        // A strong match on page 1 stops there, even on a full page
+        pages.borrow_mut().clear();
+        let full_with_match = |_: &str, page: u32| {
+            pages.borrow_mut().push(page);
+            let mut posts: Vec<WpPost> = (1..PER_PAGE)
+                .map(|i| post(&format!("wet-leg-tour-{}", i)))
+                .collect();
+            posts.push(post("wet-leg-moisturizer"));
+            Ok(posts)
+        };
+        search_and_match("Wet Leg", &album, full_with_match)
+            .ok()
+            .unwrap();
+        assert_eq!(*pages.borrow(), vec![1]);
+
+        // A short page 1 is all there is
+        pages.borrow_mut().clear();
+        let short = |_: &str, page: u32| {
+            pages.borrow_mut().push(page);
+            Ok(vec![post("wet-leg-tour-1")])
+        };
+        search_and_match("Wet Leg", &album, short).ok().unwrap();
+        assert_eq!(*pages.borrow(), vec![1]);
+    }
+
+    #[test]
+    fn exact_slug_ends_the_scan() {
+        let posts = [
+            post("moisturizer-tour-wet-leg"),
+            post("wet-leg-moisturizer-live-wet-leg"),
+            post("wet-leg-moisturizer"),
+        ];
+        let album = Query::new("Wet Leg", "Moisturizer");
+        assert_eq!(best_post(&posts, &album), Some((2, true)));
+        // A looser artist match is still taken when no slug is exact
+        assert_eq!(best_post(&posts[..2], &album), Some((0, true)));
+        let untitled_artist = Query::new("", "Moisturizer");
+        assert_eq!(best_post(&posts, &untitled_artist), Some((2, false)));
+        let beatles = Query::new("The Beatles", "Abbey Road");
+        let abbey_road = [post("beatles-abbey-road")];
+        assert_eq!(best_post(&abbey_road, &beatles), Some((0, true)));
+        // A slug padded well beyond the title isn't a match at all
+        let padded = [post("moisturizer-and-other-things-we-heard-this-week")];
+        assert_eq!(best_post(&padded, &album), None);
+    }
+}
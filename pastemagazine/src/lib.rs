@@ -0,0 +1,6 @@
+pub mod pastemagazine;
+
+/// Review fields this source can populate.
+pub const CAPABILITIES: &[&str] = &["rating", "excerpt", "reviewer", "date"];
+
+editorial_common::editorial_plugin!(pastemagazine, "pastemagazine", CAPABILITIES);
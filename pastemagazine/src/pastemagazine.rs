@@ -0,0 +1,252 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, decode_html_entities,
+    extract_json_ld, fetch_page, json_ld_name, json_ld_rating, match_confidence,
+    release_ids_conflict, search_terms, site_links, slugify, strip_html_tags, truncate_at_sentence,
+    url_encode, AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query, SearchCandidate,
+    SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+use serde::Deserialize;
+
+/// Production site root; override with the `pastemagazine_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://www.pastemagazine.com";
+const BASE_URL_CONFIG: &str = "pastemagazine_base_url";
+
+/// Paste rates albums out of ten, sometimes to one decimal place.
+const RATING_SCALE: f64 = 10.0;
+
+/// Class of the element showing the rating, for pages whose JSON-LD carries
+/// no `reviewRating`.
+const RATING_MARKER: &str = "class=\"rating";
+
+/// The site root, honoring the `pastemagazine_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch a Paste review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its rating, skipping the review text,
+/// author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known Paste URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let json_ld = parse_json_ld(&body);
+    let rating = parse_rating(json_ld.as_ref(), &body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((rating, RATING_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the music articles Paste's search offers for an album and the
+/// review `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Paste for "artist title review" and keep every music article it
+/// lists along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = format!("{} review", search_terms(&input.artist, cleaned));
+    let search_url = format!("{}/search?q={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+
+    let mut result = SearchResult::default();
+    for url in site_links(&html, &base, "music") {
+        let Some(slug) = review_slug(&url) else {
+            continue;
+        };
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    result.chosen = pick_review(&result.candidates, &album);
+    Ok(result)
+}
+
+/// The review that best fits the album; see `best_candidate`. Paste's
+/// search is dominated by list articles ("The 50 Best Albums of 2024") that
+/// mention the album among many others; their long slugs score too low to
+/// be picked.
+fn pick_review(candidates: &[SearchCandidate], album: &Query) -> Option<String> {
+    let best = best_candidate(album, candidates, |c| Some(Candidate::new(c.slug.as_str())));
+    best.map(|candidate| candidate.url.clone())
+}
+
+/// The slug of a music article URL, without the "-review" or
+/// "-album-review" suffix Paste gives reviews:
+/// "/music/wet-leg/wet-leg-moisturizer-album-review" -> "wet-leg-moisturizer".
+/// None for section pages like "/music/reviews".
+fn review_slug(url: &str) -> Option<String> {
+    let path = url.split_once("/music/")?.1;
+    let path = path.split(['?', '#']).next()?.trim_end_matches('/');
+    let (_, slug) = path.rsplit_once('/')?;
+    let slug = slugify(slug);
+    let album = slug
+        .strip_suffix("-album-review")
+        .or_else(|| slug.strip_suffix("-review"))
+        .unwrap_or(&slug);
+    Some(album.to_string()).filter(|album| !album.is_empty())
+}
+
+/// JSON-LD schema for Paste review pages.
+#[derive(Deserialize)]
+struct JsonLdReview {
+    #[serde(rename = "reviewBody")]
+    review_body: Option<String>,
+    description: Option<String>,
+    author: Option<serde_json::Value>,
+    #[serde(rename = "datePublished")]
+    date_published: Option<String>,
+    #[serde(rename = "reviewRating")]
+    review_rating: Option<serde_json::Value>,
+}
+
+fn parse_json_ld(html: &str) -> Option<JsonLdReview> {
+    serde_json::from_str(&extract_json_ld(html)?).ok()
+}
+
+/// Parse a Paste review page for the rating (out of 10) and the review's
+/// text, author and date from its JSON-LD.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let json_ld = parse_json_ld(html);
+    let rating = parse_rating(json_ld.as_ref(), html);
+
+    let (excerpt, reviewer, review_date) = match json_ld {
+        Some(review) => {
+            let excerpt = review
+                .review_body
+                .or(review.description)
+                .map(|body| decode_html_entities(&strip_html_tags(&body)))
+                .map(|body| truncate_at_sentence(body.trim(), MAX_EXCERPT_CHARS))
+                .filter(|body| !body.is_empty());
+            let reviewer = review.author.as_ref().and_then(json_ld_name);
+            (excerpt, reviewer, review.date_published)
+        }
+        None => (None, None, None),
+    };
+
+    if rating.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(rating.map(|r| (r, RATING_SCALE)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The rating, from the JSON-LD `reviewRating` or else the rating element's
+/// text ("8.4"). JSON-LD ratings on another scale are converted; anything
+/// outside 0-10 is rejected.
+fn parse_rating(json_ld: Option<&JsonLdReview>, html: &str) -> Option<f64> {
+    let from_json_ld = json_ld
+        .and_then(|review| review.review_rating.as_ref())
+        .and_then(|rating| json_ld_rating(rating, RATING_SCALE));
+    let rating = from_json_ld.or_else(|| {
+        let start = html.find(RATING_MARKER)?;
+        let content = &html[start + html[start..].find('>')? + 1..];
+        let text = strip_html_tags(&content[..content.find("</")?]);
+        text.trim().parse().ok()
+    })?;
+    Some(rating).filter(|rating| (0.0..=RATING_SCALE).contains(rating))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://www.pastemagazine.com";
+
+    #[test]
+    fn list_articles_rejected_by_score() {
+        let html = r#"<a href="/music/reviews">Reviews</a>
+            <a href="/music/best-albums/the-50-best-albums-of-2025-so-far-wet-leg-moisturizer">List</a>
+            <a href="/music/wet-leg/wet-leg-moisturizer-album-review">Review</a>
+            <a href="/music/wet-leg/wet-leg-announce-moisturizer-tour">News</a>"#;
+        let mut result = SearchResult::default();
+        for url in site_links(html, BASE, "music") {
+            if let Some(slug) = review_slug(&url) {
+                result.add(SearchCandidate::new(url, slug, "wet-leg", "moisturizer"));
+            }
+        }
+        assert_eq!(result.candidates.len(), 3);
+
+        let album = Query::new("wet-leg", "moisturizer");
+        let review = format!("{}/music/wet-leg/wet-leg-moisturizer-album-review", BASE);
+        assert_eq!(pick_review(&result.candidates, &album), Some(review));
+        assert_eq!(pick_review(&result.candidates[..1], &album), None);
+    }
+
+    #[test]
+    fn decimal_rating_from_json_ld_or_markup() {
+        let html = r#"<script type="application/ld+json">{"@type":"Review",
+            "author":[{"@type":"Person","name":"Matt Mitchell"}],"datePublished":"2025-07-11",
+            "description":"Wet Leg&#8217;s second album is a blast.",
+            "reviewRating":{"@type":"Rating","ratingValue":"8.4","bestRating":"10"}}</script>"#;
+        let review = parse_review_page(&format!("{}/music/x/y", BASE), html).unwrap();
+        assert_eq!(review.rating, Some(8.4));
+        assert_eq!(review.reviewer.as_deref(), Some("Matt Mitchell"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-11"));
+        assert!(review.excerpt.unwrap().starts_with("Wet Leg\u{2019}s"));
+
+        let markup = r#"<div class="rating large"><span>7.9</span></div>"#;
+        assert_eq!(parse_rating(None, markup), Some(7.9));
+        assert_eq!(parse_rating(None, r#"<div class="rating">79</div>"#), None);
+    }
+}
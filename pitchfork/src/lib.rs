@@ -1,16 +1,11 @@
-mod pitchfork;
+pub mod pitchfork;
 
-use editorial_common::{wrap_review, AlbumReviewInput};
-use extism_pdk::*;
+/// Review fields this source can populate.
+pub const CAPABILITIES: &[&str] = &["rating", "excerpt", "reviewer", "date", "accolade", "image"];
 
-#[plugin_fn]
-pub fn riff_health_check(_input: String) -> FnResult<String> {
-    Ok("ok".to_string())
-}
-
-#[plugin_fn]
-pub fn riff_get_album_reviews(input: String) -> FnResult<String> {
-    let params: AlbumReviewInput = serde_json::from_str(&input)?;
-    let review = pitchfork::fetch_review(&params.artist, &params.title);
-    Ok(wrap_review("pitchfork", review))
-}
+editorial_common::editorial_plugin!(
+    pitchfork,
+    "pitchfork",
+    CAPABILITIES,
+    fetch_editions: Some(pitchfork::fetch_editions),
+);
@@ -1,82 +1,284 @@
-use editorial_common::{clean_title, extract_json_ld, slugify, url_encode, SiteReview};
-use extism_pdk::*;
-use serde::Deserialize;
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, fetch_page,
+    find_json_ld_node_by, json_ld_image, json_ld_is, json_ld_name, meta_content, paragraph_text,
+    release_ids_conflict, score_candidate, search_terms, truncate_at_sentence, url_encode,
+    AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query, ReleaseType, SearchCandidate,
+    SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `pitchfork_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://pitchfork.com";
+const BASE_URL_CONFIG: &str = "pitchfork_base_url";
+
+/// Class of the element holding the review text, read when the JSON-LD has
+/// no `reviewBody`.
+const BODY_MARKER: &str = "class=\"body__inner-container";
+
+/// Most matching reviews fetched to tell an album's original review from
+/// its Sunday Review or reissue review.
+const MAX_EDITIONS: usize = 3;
+
+/// Preloaded-state markers of a review that revisits an earlier album, most
+/// specific first, with the accolade each is reported as.
+const REISSUE_MARKERS: &[(&str, &str)] = &[
+    ("\"isBestNewReissue\":true", "Best New Reissue"),
+    ("\"Sunday Review\"", "Sunday Review"),
+    ("\"isReissue\":true", "Reissue"),
+];
+
+/// The site root, honoring the `pitchfork_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
 
 /// Attempt to fetch a Pitchfork review for the given album.
-pub fn fetch_review(artist: &str, title: &str) -> Option<SiteReview> {
-    let review_url = search_for_review(artist, title)?;
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    fetch_editions(input).0
+}
 
-    let req = HttpRequest::new(&review_url).with_header("Accept", "text/html");
-    let resp = http::request::<()>(&req, None).ok()?;
-    if resp.status_code() != 200 {
-        return None;
+/// Fetch the album's review along with any other reviews of it fetched to
+/// choose between (e.g. a Sunday Review of a classic), which are returned
+/// after the chosen one.
+pub fn fetch_editions(input: &AlbumReviewInput) -> (FetchOutcome, Vec<SiteReview>) {
+    match lookup_reviews(input) {
+        Ok(mut reviews) => {
+            let chosen = reviews.remove(0);
+            (FetchOutcome::from(Ok(chosen)), reviews)
+        }
+        Err(err) => (FetchOutcome::from(Err(err)), Vec::new()),
     }
+}
 
-    let body = String::from_utf8(resp.body().to_vec()).ok()?;
-    parse_review_page(&review_url, &body)
+/// Resolve the album and read only its rating, skipping the JSON-LD review
+/// text, author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
 }
 
-/// Search Pitchfork to find the review URL for an album.
-/// Tries artist+title first, then falls back to artist-only with slug matching.
-fn search_for_review(artist: &str, title: &str) -> Option<String> {
-    let cleaned = clean_title(title);
-    let title_slug = slugify(cleaned);
+/// Parse the review at a known Pitchfork URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
 
-    // Try artist+title first (works for most albums)
-    let query = format!("{} {}", artist, cleaned);
-    if let Some(url) = search_and_match(&query, &title_slug) {
-        return Some(url);
+/// The chosen review first, then any other editions that parse.
+fn lookup_reviews(input: &AlbumReviewInput) -> Result<Vec<SiteReview>, FetchError> {
+    let mut reviews = Vec::new();
+    for (index, (review_url, body)) in fetch_review_pages(input)?.iter().enumerate() {
+        let Some(mut review) = parse_review_page(review_url, body) else {
+            if index == 0 {
+                return Err(FetchError::NotFound);
+            }
+            continue;
+        };
+        review.match_confidence = review_confidence(review_url, input);
+        reviews.push(review);
     }
+    Ok(reviews)
+}
 
-    // Fall back to artist-only (Pitchfork search chokes on some album titles)
-    search_and_match(artist, &title_slug)
+/// Fetch only the chosen review's page: weighing it against other editions
+/// isn't worth two more requests for a rating.
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    let rating = extract_rating_from_preloaded(&body);
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(rating.map(|r| (r, 10.0)))
+        .match_confidence(confidence)
+        .build())
 }
 
-/// Search Pitchfork and return the review URL whose slug best matches title_slug.
-fn search_and_match(query: &str, title_slug: &str) -> Option<String> {
-    let encoded = url_encode(query);
-    let search_url = format!("https://pitchfork.com/search/?q={}", encoded);
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input. Classic albums often have both
+/// an original review and a later Sunday Review or reissue review under
+/// near-identical slugs; when several reviews match, up to `MAX_EDITIONS`
+/// are fetched and ordered by `order_editions`.
+fn fetch_review_pages(input: &AlbumReviewInput) -> Result<Vec<(String, String)>, FetchError> {
+    let album = Query::from_input(input);
+    let result = search_candidates(input)?;
+    let chosen = result.chosen.ok_or(FetchError::NotFound)?;
+    let matches = |candidate: &SearchCandidate| {
+        let one = std::slice::from_ref(candidate);
+        best_candidate(&album, one, |c| review_slug(&c.url).map(Candidate::new)).is_some()
+    };
+    // The pick goes first, so it wins when nothing tells the reviews apart
+    let others = result
+        .candidates
+        .iter()
+        .filter(|candidate| candidate.url != chosen && matches(candidate))
+        .map(|candidate| candidate.url.as_str());
+    let editions: Vec<&str> = std::iter::once(chosen.as_str())
+        .chain(others)
+        .take(MAX_EDITIONS)
+        .collect();
 
-    let req = HttpRequest::new(&search_url).with_header("Accept", "text/html");
-    let resp = http::request::<()>(&req, None).ok()?;
-    if resp.status_code() != 200 {
-        return None;
+    let mut pages = Vec::new();
+    for url in editions {
+        match fetch_page(&build_request(url, "text/html")) {
+            Ok(body) => pages.push((url.to_string(), body)),
+            Err(FetchError::NotFound) if url != chosen => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    order_editions(pages, input)
+}
+
+/// Put the review `pick_edition` chooses first, followed by the other
+/// fetched reviews in search order. Reviews whose release identifiers
+/// contradict the input are dropped; if that's the chosen one, the album
+/// counts as not found.
+fn order_editions(
+    mut pages: Vec<(String, String)>,
+    input: &AlbumReviewInput,
+) -> Result<Vec<(String, String)>, FetchError> {
+    let found: Vec<Edition> = pages.iter().map(|(_, body)| Edition::of(body)).collect();
+    let index = pick_edition(&found, input.year, input.release_type);
+    let chosen = pages.remove(index);
+
+    let (mbid, barcode) = (input.mbid.as_deref(), input.barcode.as_deref());
+    if release_ids_conflict(&chosen.1, mbid, barcode) {
+        return Err(FetchError::NotFound);
     }
+    pages.retain(|(_, body)| !release_ids_conflict(body, mbid, barcode));
+    pages.insert(0, chosen);
+    Ok(pages)
+}
+
+/// What tells one review of an album from another: the year it was
+/// published and whether it revisits the album as a reissue.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Edition {
+    published: Option<i32>,
+    reissue: bool,
+}
 
-    let html = String::from_utf8(resp.body().to_vec()).ok()?;
-    let urls = extract_review_urls(&html);
-
-    // Find the URL whose slug contains the title slug
-    urls.into_iter().find(|url| {
-        if let Some(slug_part) = url.split("/reviews/albums/").nth(1) {
-            let slug = slug_part.trim_end_matches('/');
-            // Strip optional numeric prefix (e.g. "17253-")
-            let slug = if let Some(pos) = slug.find('-') {
-                if slug[..pos].chars().all(|c| c.is_ascii_digit()) {
-                    &slug[pos + 1..]
-                } else {
-                    slug
-                }
-            } else {
-                slug
-            };
-            slug.contains(title_slug)
-        } else {
-            false
+impl Edition {
+    fn of(html: &str) -> Self {
+        let published = review_node(html)
+            .and_then(|node| node.get("datePublished")?.as_str()?.get(..4)?.parse().ok());
+        Edition {
+            published,
+            reissue: reissue_accolade(html).is_some(),
         }
-    })
+    }
+}
+
+/// The index of the review to use among several of the same album. With a
+/// `year`, the review published closest to it wins, as the original review
+/// came out with the album and a reissue review years later. Without one,
+/// the original wins unless a reissue was asked for, then the earliest.
+fn pick_edition(editions: &[Edition], year: Option<i32>, release: ReleaseType) -> usize {
+    let wants_reissue = release == ReleaseType::Reissue;
+    let key = |edition: &Edition| {
+        let other_kind = edition.reissue != wants_reissue;
+        match year {
+            Some(year) => {
+                let distance = edition.published.map_or(i32::MAX, |p| (p - year).abs());
+                (distance, other_kind as i32)
+            }
+            None => (other_kind as i32, edition.published.unwrap_or(i32::MAX)),
+        }
+    };
+    (0..editions.len())
+        .min_by_key(|&index| key(&editions[index]))
+        .unwrap_or(0)
+}
+
+/// The review slug's `score_candidate` score, the one it was picked by.
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    let album = Query::from_input(input);
+    review_slug(review_url).map(|slug| score_candidate(&album, &Candidate::new(slug)))
+}
+
+/// List the review URLs Pitchfork's search offers for an album and the one
+/// `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Pitchfork, keeping every review URL seen along with the pick.
+/// Tries artist+title first, then falls back to artist-only with slug matching.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let artist = input.artist.as_str();
+    let cleaned = clean_title(&input.title);
+    let mut result = SearchResult::default();
+
+    // Artist+title works for most albums; Pitchfork search chokes on some
+    // album titles, so artist-only is the fallback (and the only search for
+    // a self-titled album)
+    let mut queries = vec![search_terms(artist, cleaned), artist.to_string()];
+    queries.dedup();
+    for query in queries {
+        let urls = match search_review_urls(&query) {
+            Ok(urls) => urls,
+            Err(FetchError::NotFound) => continue,
+            Err(err) => return Err(err),
+        };
+
+        result.chosen = pick_review_url(&urls, &album);
+        for url in urls {
+            let slug = review_slug(&url).unwrap_or_default().to_string();
+            let candidate = SearchCandidate::scored(url, slug, &album);
+            result.add(candidate);
+        }
+        if result.chosen.is_some() {
+            break;
+        }
+    }
+
+    Ok(result)
+}
+
+/// The best-scoring review URL for the album; see `best_candidate`. An empty
+/// title matches nothing rather than everything.
+fn pick_review_url(urls: &[String], album: &Query) -> Option<String> {
+    let best = best_candidate(album, urls, |url| review_slug(url).map(Candidate::new));
+    best.cloned()
+}
+
+/// Run one Pitchfork search and return the review URLs it lists.
+fn search_review_urls(query: &str) -> Result<Vec<String>, FetchError> {
+    let encoded = url_encode(query);
+    let base = base_url();
+    let search_url = format!("{}/search/?q={}", base, encoded);
+
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+    Ok(extract_review_urls(&html, &base))
+}
+
+/// The album slug of a review URL, without its optional numeric prefix
+/// (e.g. "17253-"). None for a bare numeric ID, which names no album.
+fn review_slug(url: &str) -> Option<&str> {
+    let slug = url.split("/reviews/albums/").nth(1)?.trim_end_matches('/');
+    let slug = match slug.split_once('-') {
+        Some((prefix, rest)) if prefix.chars().all(|c| c.is_ascii_digit()) => rest,
+        _ => slug,
+    };
+    Some(slug).filter(|slug| !slug.is_empty() && !slug.chars().all(|c| c.is_ascii_digit()))
 }
 
 /// Extract all review album URLs from Pitchfork search HTML.
-fn extract_review_urls(html: &str) -> Vec<String> {
+fn extract_review_urls(html: &str, base: &str) -> Vec<String> {
     let pattern = "href=\"/reviews/albums/";
     let mut urls = Vec::new();
     let mut search_from = 0;
 
-    loop {
-        let Some(pos) = html[search_from..].find(pattern) else {
-            break;
-        };
+    while let Some(pos) = html[search_from..].find(pattern) {
         let abs_pos = search_from + pos;
         let path_start = abs_pos + "href=\"".len();
         let Some(end_offset) = html[path_start..].find('"') else {
@@ -86,7 +288,7 @@ fn extract_review_urls(html: &str) -> Vec<String> {
         let path = &html[path_start..path_end];
 
         if path != "/reviews/albums/" && path.len() > "/reviews/albums/".len() {
-            let full_url = format!("https://pitchfork.com{}", path);
+            let full_url = format!("{}{}", base, path);
             if !urls.contains(&full_url) {
                 urls.push(full_url);
             }
@@ -101,67 +303,87 @@ fn extract_review_urls(html: &str) -> Vec<String> {
     urls
 }
 
-/// JSON-LD schema for Pitchfork review pages.
-#[derive(Deserialize)]
-struct JsonLdReview {
-    #[serde(rename = "reviewBody")]
-    review_body: Option<String>,
-    author: Option<serde_json::Value>,
-    #[serde(rename = "datePublished")]
-    date_published: Option<String>,
+/// The page's review JSON-LD node.
+fn review_node(html: &str) -> Option<serde_json::Value> {
+    find_json_ld_node_by(html, |node| {
+        json_ld_is(node, &["Review"]) || node.get("reviewBody").is_some()
+    })
 }
 
 /// Parse a Pitchfork review page for rating (from __PRELOADED_STATE__) and
-/// review text/author/date (from JSON-LD).
+/// review text/author/date from its review JSON-LD, which is parsed once and
+/// read for every field. Pages whose JSON-LD leaves out `reviewBody` get the
+/// excerpt from the article markup instead. A Sunday Review or reissue
+/// review is reported as the accolade, and the JSON-LD `image` (or else the
+/// `og:image`) as the cover art.
 fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
     let rating = extract_rating_from_preloaded(html);
 
-    let json_ld = extract_json_ld(html);
-    let (excerpt, reviewer, review_date) = if let Some(ref ld_str) = json_ld {
-        if let Ok(review) = serde_json::from_str::<JsonLdReview>(ld_str) {
-            let excerpt = review.review_body;
-
-            let reviewer = review.author.and_then(|a| match a {
-                serde_json::Value::Array(arr) => arr
-                    .first()
-                    .and_then(|v| v.get("name"))
-                    .and_then(|n| n.as_str())
-                    .map(|s| s.to_string()),
-                serde_json::Value::Object(obj) => {
-                    obj.get("name").and_then(|n| n.as_str()).map(|s| s.to_string())
-                }
-                _ => None,
-            });
-
-            let review_date = review.date_published;
-
-            (excerpt, reviewer, review_date)
-        } else {
-            (None, None, None)
-        }
-    } else {
-        (None, None, None)
-    };
+    let review = review_node(html);
+    let field = |key: &str| review.as_ref().and_then(|node| node.get(key));
+
+    let excerpt = field("reviewBody")
+        .and_then(|body| body.as_str())
+        .map(|body| truncate_at_sentence(body.trim(), MAX_EXCERPT_CHARS))
+        .or_else(|| parse_review_body(html));
+    let reviewer = field("author").and_then(json_ld_name);
+    let review_date = field("datePublished")
+        .and_then(|date| date.as_str())
+        .map(str::to_string);
+    let image_url = field("image")
+        .and_then(json_ld_image)
+        .or_else(|| meta_content(html, "og:image"));
 
     if rating.is_none() && excerpt.is_none() {
         return None;
     }
 
-    Some(SiteReview {
-        source_url: url.to_string(),
-        excerpt,
-        rating,
-        rating_count: None,
-        reviewer,
-        review_date,
-    })
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(rating.map(|r| (r, 10.0)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .accolade(reissue_accolade(html).map(str::to_string))
+            .image_url(image_url)
+            .build(),
+    )
+}
+
+/// The article's paragraphs, cut to ~2000 chars at a sentence boundary.
+fn parse_review_body(html: &str) -> Option<String> {
+    let start = html.find(BODY_MARKER)?;
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |end| start + end);
+    let text = paragraph_text(&html[start..end]);
+    Some(truncate_at_sentence(&text, MAX_EXCERPT_CHARS)).filter(|text| !text.is_empty())
+}
+
+/// Pitchfork's __PRELOADED_STATE__ script, up to its `</script>`, so scans
+/// of it don't run on into the rest of the page.
+fn preloaded_state(html: &str) -> Option<&str> {
+    let state_pos = html.find("__PRELOADED_STATE__")?;
+    let state_end = html[state_pos..]
+        .find("</script>")
+        .map_or(html.len(), |end| state_pos + end);
+    Some(&html[state_pos..state_end])
+}
+
+/// The accolade for a review that revisits an earlier album ("Sunday
+/// Review", "Best New Reissue"), from the first of `REISSUE_MARKERS` in the
+/// preloaded state.
+fn reissue_accolade(html: &str) -> Option<&'static str> {
+    let state = preloaded_state(html)?;
+    REISSUE_MARKERS
+        .iter()
+        .find(|(marker, _)| state.contains(marker))
+        .map(|(_, accolade)| *accolade)
 }
 
 /// Extract the numeric rating from Pitchfork's __PRELOADED_STATE__ JSON.
 fn extract_rating_from_preloaded(html: &str) -> Option<f64> {
-    let state_marker = "__PRELOADED_STATE__";
-    let state_pos = html.find(state_marker)?;
-    let state_region = &html[state_pos..];
+    let state_region = preloaded_state(html)?;
 
     let pattern = "\"rating\":";
     let mut search_from = 0;
@@ -172,7 +394,11 @@ fn extract_rating_from_preloaded(html: &str) -> Option<f64> {
 
         // Skip if preceded by another letter (like "bestRating")
         if abs_pos > 0 {
-            let before = state_region.as_bytes().get(abs_pos - 1).copied().unwrap_or(b'"');
+            let before = state_region
+                .as_bytes()
+                .get(abs_pos - 1)
+                .copied()
+                .unwrap_or(b'"');
             if before.is_ascii_alphabetic() {
                 search_from = value_start;
                 continue;
@@ -196,3 +422,174 @@ fn extract_rating_from_preloaded(html: &str) -> Option<f64> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn review_slug_needs_album_words() {
+        let url = |path: &str| format!("https://pitchfork.com/reviews/albums/{}", path);
+        assert_eq!(review_slug(&url("17253-blue-lines/")), Some("blue-lines"));
+        let unprefixed = url("massive-attack-blue-lines/");
+        assert_eq!(review_slug(&unprefixed), Some("massive-attack-blue-lines"));
+        assert_eq!(review_slug(&url("17253/")), None);
+        assert_eq!(review_slug(&url("17253-/")), None);
+
+        let urls = vec![url("17253/"), url("massive-attack-blue-lines/")];
+        let pick = |title: &str| pick_review_url(&urls, &Query::new("massive-attack", title));
+        assert_eq!(pick(""), None);
+        assert_eq!(pick("17"), None);
+        assert_eq!(pick("blue-lines"), Some(urls[1].clone()));
+    }
+
+    #[test]
+    fn confidence_is_the_score_picked_by() {
+        let url = |path: &str| format!("https://pitchfork.com/reviews/albums/{}/", path);
+        let urls = vec![
+            url("blue-lines"),
+            url("massive-attack-blue-lines-and-more-thoughts"),
+        ];
+        let input: AlbumReviewInput =
+            serde_json::from_str(r#"{"artist":"Massive Attack","title":"Blue Lines"}"#).unwrap();
+        let album = Query::from_input(&input);
+        let chosen = pick_review_url(&urls, &album).unwrap();
+
+        let scores: Vec<f64> = urls
+            .iter()
+            .map(|u| SearchCandidate::scored(u.clone(), review_slug(u).unwrap().into(), &album))
+            .map(|candidate| candidate.score)
+            .collect();
+        let confidence = review_confidence(&chosen, &input).unwrap();
+        assert_eq!(confidence, scores.iter().copied().fold(0.0, f64::max));
+        let chosen_slug = Candidate::new(review_slug(&chosen).unwrap());
+        assert_eq!(confidence, score_candidate(&album, &chosen_slug));
+    }
+
+    #[test]
+    fn self_titled_album_not_mistaken_for_another() {
+        let html = r#"<a href="/artists/4627-weezer/">Weezer</a>
+            <a href="/reviews/albums/weezer-pinkerton/">Pinkerton</a>
+            <a href="/reviews/albums/8526-weezer/">Weezer</a>"#;
+        let urls = extract_review_urls(html, "https://pitchfork.com");
+        assert_eq!(urls.len(), 2);
+        assert_eq!(review_slug(&urls[1]), Some("weezer"));
+
+        let album = Query::new("Weezer", "Weezer (Blue Album)");
+        assert!(album.self_titled());
+        assert_eq!(search_terms("Weezer", "Weezer"), "Weezer");
+        assert_eq!(pick_review_url(&urls, &album), Some(urls[1].clone()));
+        assert_eq!(pick_review_url(&urls[..1], &album), None);
+    }
+
+    #[test]
+    fn long_review_body_is_truncated() {
+        let body = format!("{}. {}", "\u{e9}".repeat(1500), "word ".repeat(400));
+        let html = format!(
+            r#"<script type="application/ld+json">{{"reviewBody":"{}","author":[{{"name":"A Writer"}}]}}</script>"#,
+            body
+        );
+        let review = parse_review_page("https://pitchfork.com/reviews/albums/x/", &html).unwrap();
+        let excerpt = review.excerpt.unwrap();
+        assert_eq!(excerpt, format!("{}.", "\u{e9}".repeat(1500)));
+        assert_eq!(review.reviewer.as_deref(), Some("A Writer"));
+    }
+
+    #[test]
+    fn excerpt_from_markup_without_review_body() {
+        let html = r#"<script type="application/ld+json">{"@type":"Review",
+            "author":[{"@type":"Person","name":"A Writer"}]}</script>
+            <article><div class="body__inner-container"><p>Wet Leg&rsquo;s second album.</p>
+            <figure><img src="x.jpg"><figcaption>Photo</figcaption></figure>
+            <p>It is <em>louder</em>.</p></div></article><p>Most Read</p>"#;
+        let review = parse_review_page("https://pitchfork.com/reviews/albums/x/", html).unwrap();
+        let excerpt = review.excerpt.unwrap();
+        assert_eq!(excerpt, "Wet Leg\u{2019}s second album.\n\nIt is louder.");
+        assert_eq!(review.reviewer.as_deref(), Some("A Writer"));
+        assert_eq!(review.image_url, None);
+
+        let cover = r#"<meta property="og:image" content="https://media.pitchfork.com/x.jpg">"#;
+        let html = format!("{}{}", cover, html);
+        let review = parse_review_page("https://pitchfork.com/reviews/albums/x/", &html).unwrap();
+        let image_url = review.image_url.as_deref();
+        assert_eq!(image_url, Some("https://media.pitchfork.com/x.jpg"));
+    }
+
+    #[test]
+    fn original_review_preferred_over_sunday_review() {
+        let page = |date: &str, state: &str| {
+            format!(
+                r#"<script>window.__PRELOADED_STATE__ = {{{}"rating":10}};</script>
+                <script type="application/ld+json">{{"@type":"Review","datePublished":"{}"}}</script>"#,
+                state, date
+            )
+        };
+        let original = page("2000-10-02T06:00:00.000Z", "");
+        let sunday = page("2017-07-30T05:00:00.000Z", r#""channel":"Sunday Review","#);
+        let editions = [Edition::of(&sunday), Edition::of(&original)];
+        assert_eq!(editions[0].published, Some(2017));
+        assert!(editions[0].reissue && !editions[1].reissue);
+
+        let studio = ReleaseType::Studio;
+        assert_eq!(pick_edition(&editions, None, studio), 1);
+        assert_eq!(pick_edition(&editions, Some(2000), studio), 1);
+        assert_eq!(pick_edition(&editions, Some(2017), studio), 0);
+        assert_eq!(pick_edition(&editions, None, ReleaseType::Reissue), 0);
+
+        let url = "https://pitchfork.com/reviews/albums/radiohead-kid-a/";
+        let review = parse_review_page(url, &sunday).unwrap();
+        assert_eq!(review.accolade.as_deref(), Some("Sunday Review"));
+        let review = parse_review_page(url, &original).unwrap();
+        assert_eq!(review.accolade, None);
+    }
+
+    #[test]
+    fn other_editions_follow_the_chosen_review() {
+        let page = |date: &str, state: &str| {
+            format!(
+                r#"<script>window.__PRELOADED_STATE__ = {{{}"rating":9}};</script>
+                <script type="application/ld+json">{{"@type":"Review","datePublished":"{}"}}</script>"#,
+                state, date
+            )
+        };
+        let sunday_url = "https://pitchfork.com/reviews/albums/joni-mitchell-blue/";
+        let original_url = "https://pitchfork.com/reviews/albums/123-joni-mitchell-blue/";
+        let pages = vec![
+            (
+                sunday_url.to_string(),
+                page("2019-06-23", r#""channel":"Sunday Review","#),
+            ),
+            (original_url.to_string(), page("1999-05-01", "")),
+        ];
+        let mut input: AlbumReviewInput =
+            serde_json::from_str(r#"{"artist":"Joni Mitchell","title":"Blue"}"#).unwrap();
+        let ordered = order_editions(pages.clone(), &input).ok().unwrap();
+        let urls: Vec<&str> = ordered.iter().map(|(url, _)| url.as_str()).collect();
+        assert_eq!(urls, [original_url, sunday_url]);
+
+        let reviews: Vec<SiteReview> = ordered
+            .iter()
+            .filter_map(|(url, body)| parse_review_page(url, body))
+            .collect();
+        let json = editorial_common::wrap_reviews("pitchfork", reviews, None);
+        let output: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let reviews = output["reviews"].as_array().unwrap();
+        assert_eq!(reviews.len(), 2);
+        assert_eq!(reviews[0]["source_url"], original_url);
+        assert_eq!(reviews[1]["accolade"], "Sunday Review");
+
+        input.year = Some(2019);
+        let ordered = order_editions(pages, &input).ok().unwrap();
+        assert_eq!(ordered[0].0, sunday_url);
+    }
+
+    #[test]
+    fn preloaded_scan_stops_at_state_end() {
+        let state = r#"<script>window.__PRELOADED_STATE__ = {"review":{"bestRating":10,"rating":7.9}};</script>"#;
+        assert_eq!(extract_rating_from_preloaded(state), Some(7.9));
+
+        let unrated = r#"<script>window.__PRELOADED_STATE__ = {"review":{"rating":null}};</script>
+            <div data-json='{"rating":9.1}'></div>"#;
+        assert_eq!(extract_rating_from_preloaded(unrated), None);
+    }
+}
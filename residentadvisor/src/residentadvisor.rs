@@ -0,0 +1,292 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, fetch_page, html_to_text,
+    json_ld_name, json_ld_number, normalize_date, path_segment, search_terms, slugify,
+    truncate_at_sentence, url_encode, AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query,
+    SearchCandidate, SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+/// Production site root; override with the `residentadvisor_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://ra.co";
+const BASE_URL_CONFIG: &str = "residentadvisor_base_url";
+
+/// Resident Advisor rates releases from 0.0 to 5.0. Reviews published
+/// after RA dropped scores carry no rating at all.
+const RATING_SCALE: f64 = 5.0;
+
+/// Review search over the site's GraphQL API; hits' `value` is the
+/// "Artist - Title" headline.
+const SEARCH_QUERY: &str = "query SearchReviews($term: String!) { \
+    search(searchTerm: $term, limit: 16, indices: [REVIEW]) { id value contentUrl } }";
+
+/// A single review by ID over the GraphQL API.
+const REVIEW_QUERY: &str = "query GetReview($id: ID!) { \
+    review(id: $id) { id title rating date blurb content author { name } } }";
+
+/// The site root, honoring the `residentadvisor_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch a Resident Advisor review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its rating, skipping the review text,
+/// author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input)
+        .and_then(|review| {
+            let rating = review.rating_raw.ok_or(FetchError::NotFound)?;
+            Ok(SiteReview::builder(review.source_url)
+                .native_rating(Some((rating, RATING_SCALE)))
+                .match_confidence(review.match_confidence)
+                .build())
+        })
+        .into()
+}
+
+/// Fetch the review at a known Resident Advisor URL ("/reviews/{id}"),
+/// skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let id = review_id(url).ok_or(FetchError::NotFound)?;
+    let review = fetch_ra_review(id)?;
+    parse_review(url, review).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let result = search_candidates(input)?;
+    let chosen = result
+        .candidates
+        .into_iter()
+        .find(|candidate| result.chosen.as_ref() == Some(&candidate.url))
+        .ok_or(FetchError::NotFound)?;
+
+    let id = review_id(&chosen.url).ok_or(FetchError::NotFound)?;
+    let mut review = parse_review(&chosen.url, fetch_ra_review(id)?).ok_or(FetchError::NotFound)?;
+    review.match_confidence = Some(chosen.score);
+    Ok(review)
+}
+
+/// The numeric review ID in a review URL: "/reviews/36512" -> "36512".
+fn review_id(url: &str) -> Option<&str> {
+    path_segment(url, "reviews", 0).filter(|id| id.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// List the reviews Resident Advisor's search offers for an album and the
+/// one `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search RA's reviews for "artist title" and keep every hit along with the
+/// pick. Hits are matched on their "Artist - Title" headline, since review
+/// URLs carry only an ID.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let variables = serde_json::json!({ "term": search_terms(&input.artist, cleaned) });
+    let data: SearchData = fetch_graphql(SEARCH_QUERY, &variables)?;
+
+    let mut result = SearchResult::default();
+    for (url, slug) in search_hits(data, &base_url()) {
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    result.chosen = pick_review(&result.candidates, &album);
+    Ok(result)
+}
+
+/// Review hits as `(url, slug of the headline)`. Hits without a review URL
+/// or a usable headline are dropped.
+fn search_hits(data: SearchData, base: &str) -> Vec<(String, String)> {
+    data.search
+        .into_iter()
+        .filter_map(|hit| {
+            let path = hit.content_url.filter(|p| p.starts_with("/reviews/"))?;
+            let slug = slugify(&hit.value);
+            (!slug.is_empty()).then(|| (format!("{}{}", base, path), slug))
+        })
+        .collect()
+}
+
+/// The review whose headline best fits the album; see `best_candidate`.
+fn pick_review(candidates: &[SearchCandidate], album: &Query) -> Option<String> {
+    let best = best_candidate(album, candidates, |c| Some(Candidate::new(c.slug.as_str())));
+    best.map(|candidate| candidate.url.clone())
+}
+
+/// Envelope of a GraphQL response; errors come back with `data` null.
+#[derive(Deserialize)]
+struct GraphQlResponse<T> {
+    data: Option<T>,
+}
+
+#[derive(Deserialize)]
+struct SearchData {
+    search: Vec<SearchHit>,
+}
+
+#[derive(Deserialize)]
+struct SearchHit {
+    value: String,
+    #[serde(rename = "contentUrl")]
+    content_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ReviewData {
+    review: Option<RaReview>,
+}
+
+/// A review as RA's API returns it. `content` and `blurb` are HTML.
+#[derive(Deserialize)]
+struct RaReview {
+    rating: Option<serde_json::Value>,
+    date: Option<String>,
+    blurb: Option<String>,
+    content: Option<String>,
+    author: Option<serde_json::Value>,
+}
+
+/// Run a GraphQL query over GET, so responses go through the shared
+/// retry, throttle and cache path like any other page.
+fn fetch_graphql<T: DeserializeOwned>(
+    query: &str,
+    variables: &serde_json::Value,
+) -> Result<T, FetchError> {
+    let url = format!(
+        "{}/graphql?query={}&variables={}",
+        base_url(),
+        url_encode(query),
+        url_encode(&variables.to_string())
+    );
+    // Apollo only serves GET queries that can't be simple cross-site requests
+    let request =
+        build_request(&url, "application/json").with_header("apollo-require-preflight", "true");
+    parse_graphql(&fetch_page(&request)?)
+}
+
+/// The `data` of a GraphQL response body. A failed query comes back with
+/// `data` null and only `errors`, which is an error rather than a miss.
+fn parse_graphql<T: DeserializeOwned>(body: &str) -> Result<T, FetchError> {
+    serde_json::from_str::<GraphQlResponse<T>>(body)
+        .ok()
+        .and_then(|response| response.data)
+        .ok_or(FetchError::Error)
+}
+
+fn fetch_ra_review(id: &str) -> Result<RaReview, FetchError> {
+    let data: ReviewData = fetch_graphql(REVIEW_QUERY, &serde_json::json!({ "id": id }))?;
+    data.review.ok_or(FetchError::NotFound)
+}
+
+/// Build a review from RA's API data: the 0-5 rating (kept as published,
+/// scaled to 0-10), the blurb and body as the excerpt, the author and date.
+fn parse_review(url: &str, review: RaReview) -> Option<SiteReview> {
+    let rating = review
+        .rating
+        .as_ref()
+        .and_then(json_ld_number)
+        .filter(|rating| (0.0..=RATING_SCALE).contains(rating));
+    let text = [review.blurb.as_deref(), review.content.as_deref()]
+        .iter()
+        .flatten()
+        .map(|html| html_to_text(html))
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let excerpt = Some(truncate_at_sentence(&text, MAX_EXCERPT_CHARS)).filter(|t| !t.is_empty());
+    if rating.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(rating.map(|r| (r, RATING_SCALE)))
+            .reviewer(review.author.as_ref().and_then(json_ld_name))
+            .review_date(review.date.as_deref().and_then(normalize_date))
+            .build(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://ra.co";
+
+    #[test]
+    fn search_hits_matched_on_headline() {
+        let body = r#"{"data":{"search":[
+            {"id":"1","value":"Four Tet","contentUrl":"/dj/fourtet"},
+            {"id":"36512","value":"Four Tet - Three","contentUrl":"/reviews/36512"},
+            {"id":"29001","value":"Four Tet - New Energy","contentUrl":"/reviews/29001"},
+            {"id":"3","value":"Four Tet - New Energy","contentUrl":null},
+            {"id":"2","value":"","contentUrl":"/reviews/2"}]}}"#;
+        let data: SearchData = parse_graphql(body).unwrap();
+        let hits = search_hits(data, BASE);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].1, "four-tet-three");
+
+        let mut result = SearchResult::default();
+        for (url, slug) in hits {
+            result.add(SearchCandidate::new(url, slug, "four-tet", "new-energy"));
+        }
+        let album = Query::new("four-tet", "new-energy");
+        let chosen = pick_review(&result.candidates, &album).unwrap();
+        assert_eq!(chosen, format!("{}/reviews/29001", BASE));
+    }
+
+    #[test]
+    fn review_urls_carry_only_a_numeric_id() {
+        let id = |path: &str| review_id(&format!("{}{}", BASE, path)).map(str::to_string);
+        assert_eq!(id("/reviews/29001").as_deref(), Some("29001"));
+        assert_eq!(id("/reviews/29001?ref=search").as_deref(), Some("29001"));
+        assert_eq!(id("/reviews/albums"), None);
+        assert_eq!(id("/news/29001"), None);
+
+        let failed = r#"{"errors":[{"message":"Not authorised"}],"data":null}"#;
+        let data: Result<SearchData, _> = parse_graphql(failed);
+        assert_eq!(data.err(), Some(FetchError::Error));
+    }
+
+    #[test]
+    fn rating_kept_out_of_five() {
+        let body = r#"{"data":{"review":{"id":"29001","title":"Four Tet - New Energy",
+            "rating":"4.2","date":"2017-10-03T00:00:00.000",
+            "blurb":"<p>Kieran Hebden&#39;s warmest album.</p>",
+            "content":"<p>It glows.</p><p>It hums.</p>","author":{"name":"Andrew Ryce"}}}}"#;
+        let data: ReviewData = parse_graphql(body).unwrap();
+        let url = format!("{}/reviews/29001", BASE);
+        let review = parse_review(&url, data.review.unwrap()).unwrap();
+        assert_eq!(review.rating, Some(8.4));
+        assert_eq!(review.rating_raw, Some(4.2));
+        assert_eq!(review.rating_scale, Some(5.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Andrew Ryce"));
+        assert_eq!(review.review_date.as_deref(), Some("2017-10-03"));
+        let excerpt = review.excerpt.unwrap();
+        assert!(excerpt.starts_with("Kieran Hebden's warmest album.\n\nIt glows."));
+
+        let rating = |json: &str| {
+            let review: RaReview = serde_json::from_str(json).unwrap();
+            parse_review(&url, review).unwrap().rating_raw
+        };
+        assert_eq!(rating(r#"{"rating":4.5}"#), Some(4.5));
+        let unrated = r#"{"rating":null,"content":"<p>No score.</p>"}"#;
+        assert_eq!(rating(unrated), None);
+        let out_of_range = r#"{"rating":42,"content":"<p>Old percent score.</p>"}"#;
+        assert_eq!(rating(out_of_range), None);
+    }
+}
@@ -0,0 +1,270 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, decode_html_entities,
+    fetch_page, find_json_ld_node, json_ld_name, json_ld_rating, match_confidence, meta_content,
+    normalize_date, paragraph_text, parse_star_rating, release_ids_conflict, search_terms,
+    site_links, slugify, strip_html_tags, truncate_at_sentence, url_encode, AlbumReviewInput,
+    Candidate, FetchError, FetchOutcome, Query, SearchCandidate, SearchResult, SiteReview,
+    MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `slantmagazine_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://www.slantmagazine.com";
+const BASE_URL_CONFIG: &str = "slantmagazine_base_url";
+
+/// Slant rates albums out of five stars, in half-star steps.
+const STAR_SCALE: f64 = 5.0;
+
+/// Class of the element holding the star rating, for pages whose JSON-LD
+/// carries no `reviewRating`.
+const RATING_MARKER: &str = "class=\"rating";
+
+/// Class of the element holding the review text.
+const BODY_MARKER: &str = "class=\"entry-content";
+
+/// JSON-LD types a Slant review page may describe itself as.
+const ARTICLE_TYPES: &[&str] = &["Review", "Article"];
+
+/// The site root, honoring the `slantmagazine_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch a Slant review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its star rating, skipping the review
+/// text, author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known Slant URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let article = find_json_ld_node(&body, ARTICLE_TYPES);
+    let rating = parse_stars(article.as_ref(), &body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((rating, STAR_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the album review URLs Slant's search offers for an album and the one
+/// `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Slant for "artist title" and keep every music review it lists
+/// along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/?s={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+    Ok(parse_search_page(&html, &base, &album))
+}
+
+/// The music reviews linked from a search results page, and the pick.
+/// Interviews and features share the music section; only review slugs count.
+fn parse_search_page(html: &str, base: &str, album: &Query) -> SearchResult {
+    let urls: Vec<String> = site_links(html, base, "music")
+        .into_iter()
+        .filter(|url| review_slug(url).is_some())
+        .collect();
+    let mut result = SearchResult {
+        chosen: pick_review_url(&urls, album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    result
+}
+
+/// The best-scoring review for the album; see `best_candidate`.
+fn pick_review_url(urls: &[String], query: &Query) -> Option<String> {
+    best_candidate(query, urls, |url| review_slug(url).map(Candidate::new)).cloned()
+}
+
+/// The artist and album part of a music review URL:
+/// "/music/review-wet-leg-moisturizer/" and
+/// "/music/wet-leg-moisturizer-album-review/" -> "wet-leg-moisturizer".
+/// None for interviews, features and the section pages.
+fn review_slug(url: &str) -> Option<String> {
+    let path = url.split_once("/music/")?.1;
+    let slug = slugify(path.split(['/', '?', '#']).next()?);
+    let album = slug
+        .strip_prefix("review-")
+        .or_else(|| slug.strip_suffix("-album-review"))
+        .or_else(|| slug.strip_suffix("-review"))?;
+    Some(album.to_string()).filter(|album| !album.is_empty())
+}
+
+/// Parse a Slant review page for the star rating (out of 5) and the
+/// review's text, author and date.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let article = find_json_ld_node(html, ARTICLE_TYPES);
+    let field = |key: &str| article.as_ref().and_then(|node| node.get(key));
+
+    let rating = parse_stars(article.as_ref(), html);
+    let excerpt = parse_review_body(html);
+    if rating.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    let reviewer = field("author")
+        .and_then(json_ld_name)
+        .or_else(|| meta_content(html, "author"));
+    let review_date = meta_content(html, "article:published_time")
+        .or_else(|| field("datePublished")?.as_str().map(str::to_string))
+        .and_then(|date| normalize_date(&date));
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(rating.map(|r| (r, STAR_SCALE)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The star rating, from the JSON-LD `reviewRating` or else the rating
+/// element, which shows either star glyphs ("★★★½"), star icons or the
+/// bare number. Anything outside 0-5 is rejected.
+fn parse_stars(article: Option<&serde_json::Value>, html: &str) -> Option<f64> {
+    let from_json_ld = article
+        .and_then(|node| node.get("reviewRating"))
+        .and_then(|rating| json_ld_rating(rating, STAR_SCALE));
+    from_json_ld.or_else(|| {
+        let start = html.find(RATING_MARKER)?;
+        let end = html[start..].find("</div>")? + start;
+        let element = &html[start..end];
+        parse_star_rating(element, STAR_SCALE).or_else(|| {
+            let text = decode_html_entities(&strip_html_tags(&element[element.find('>')? + 1..]));
+            let stars: f64 = text.trim().parse().ok()?;
+            Some(stars).filter(|stars| (0.0..=STAR_SCALE).contains(stars))
+        })
+    })
+}
+
+/// The review's paragraphs, cut to ~2000 chars at a sentence boundary.
+fn parse_review_body(html: &str) -> Option<String> {
+    let start = html.find(BODY_MARKER)?;
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |end| start + end);
+    let text = paragraph_text(&html[start..end]);
+    Some(truncate_at_sentence(&text, MAX_EXCERPT_CHARS)).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://www.slantmagazine.com";
+
+    #[test]
+    fn review_marker_read_from_either_end_of_the_slug() {
+        let slug = |path: &str| review_slug(&format!("{}{}", BASE, path));
+        let older = slug("/music/review-wet-leg-wet-leg/");
+        assert_eq!(older.as_deref(), Some("wet-leg-wet-leg"));
+        let current = slug("/music/wet-leg-moisturizer-album-review/?amp");
+        assert_eq!(current.as_deref(), Some("wet-leg-moisturizer"));
+        let short = slug("/music/wet-leg-moisturizer-review/");
+        assert_eq!(short.as_deref(), Some("wet-leg-moisturizer"));
+        assert_eq!(slug("/music/"), None);
+        assert_eq!(slug("/music/review/"), None);
+        assert_eq!(slug("/music/interview-wet-leg-moisturizer/"), None);
+        assert_eq!(slug("/film/moisturizer-review/"), None);
+    }
+
+    #[test]
+    fn search_page_keeps_music_reviews_only() {
+        let html = r#"<a href="/music/">Music</a>
+            <a href="/music/interview-wet-leg-moisturizer/">Interview</a>
+            <a href="/film/moisturizer-review/">Film</a>
+            <a href="/music/review-wet-leg-wet-leg/">Wet Leg</a>
+            <a href="/music/wet-leg-moisturizer-album-review/">Review</a>"#;
+        let album = Query::new("wet-leg", "moisturizer");
+        let result = parse_search_page(html, BASE, &album);
+        let slugs: Vec<&str> = result.candidates.iter().map(|c| c.slug.as_str()).collect();
+        assert_eq!(slugs, ["wet-leg-wet-leg", "wet-leg-moisturizer"]);
+        let chosen = format!("{}/music/wet-leg-moisturizer-album-review/", BASE);
+        assert_eq!(result.chosen, Some(chosen));
+    }
+
+    #[test]
+    fn half_stars_read_from_markup() {
+        let html = r#"<head><meta name="author" content="Sal Cinquemani">
+            <meta property="article:published_time" content="2025-07-14T10:00:00+00:00"></head>
+            <div class="rating">&#9733;&#9733;&#9733;&#189;</div>
+            <article><div class="entry-content"><p>Wet Leg get louder.</p></div></article>"#;
+        let url = format!("{}/music/wet-leg-moisturizer-album-review/", BASE);
+        let review = parse_review_page(&url, html).unwrap();
+        assert_eq!(review.rating, Some(7.0));
+        assert_eq!(review.rating_raw, Some(3.5));
+        assert_eq!(review.rating_scale, Some(5.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Sal Cinquemani"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-14"));
+        assert_eq!(review.excerpt.as_deref(), Some("Wet Leg get louder."));
+
+        let icons = r#"<div class="rating"><i class="fa fa-star"></i><i class="fa fa-star"></i>
+            <i class="fa fa-star-half-alt"></i><i class="fa fa-star-o"></i></div>"#;
+        assert_eq!(parse_stars(None, icons), Some(2.5));
+        let glyphs = "<div class=\"rating\">\u{2605}\u{2605}\u{2605}\u{2605}</div>";
+        assert_eq!(parse_stars(None, glyphs), Some(4.0));
+        let number = r#"<div class="rating">4.5</div>"#;
+        assert_eq!(parse_stars(None, number), Some(4.5));
+        assert_eq!(parse_stars(None, r#"<div class="rating">7</div>"#), None);
+    }
+}
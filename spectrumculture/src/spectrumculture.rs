@@ -0,0 +1,283 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, fetch_page, find_json_ld_node,
+    json_ld_name, json_ld_rating, match_confidence, meta_content, normalize_date, paragraph_text,
+    parse_star_rating, release_ids_conflict, search_terms, site_links, slugify,
+    truncate_at_sentence, url_encode, AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query,
+    SearchCandidate, SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `spectrumculture_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://spectrumculture.com";
+const BASE_URL_CONFIG: &str = "spectrumculture_base_url";
+
+/// Spectrum Culture rates albums out of five stars.
+const STAR_SCALE: f64 = 5.0;
+
+/// Class WordPress puts on a post in the album reviews category. Features,
+/// lists and film reviews carry other categories and are skipped.
+const REVIEW_CATEGORY: &str = "category-music-reviews";
+
+/// Class of the element holding the star rating, for pages whose JSON-LD
+/// carries no `reviewRating`.
+const RATING_MARKER: &str = "class=\"star-rating";
+
+/// Class of the element holding the review text.
+const BODY_MARKER: &str = "class=\"entry-content";
+
+/// JSON-LD types a Spectrum Culture review page may describe itself as.
+const ARTICLE_TYPES: &[&str] = &["Review", "Article"];
+
+/// The site root, honoring the `spectrumculture_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch a Spectrum Culture review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its star rating, skipping the review
+/// text, author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known Spectrum Culture URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let article = find_json_ld_node(&body, ARTICLE_TYPES);
+    let rating = parse_stars(article.as_ref(), &body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((rating, STAR_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the album reviews Spectrum Culture's search offers for an album and
+/// the one `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Spectrum Culture for "artist title" and keep every album review it
+/// lists along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/?s={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+
+    let urls = review_links(&html, &base);
+    let mut result = SearchResult {
+        chosen: pick_review_url(&urls, &album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    Ok(result)
+}
+
+/// The post links of the search results filed under the album reviews
+/// category. Posts live under date paths whatever their category, so the
+/// `<article>` classes are the only way to tell a review from a feature.
+fn review_links(html: &str, base: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for post in html.split("<article").skip(1) {
+        let open_tag = &post[..post.find('>').unwrap_or(post.len())];
+        if !open_tag.contains(REVIEW_CATEGORY) {
+            continue;
+        }
+        let post = &post[..post.find("</article>").unwrap_or(post.len())];
+        let link = site_links(post, base, "")
+            .into_iter()
+            .find(|url| review_slug(url).is_some());
+        if let Some(url) = link.filter(|url| !urls.contains(url)) {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+/// The best-scoring review for the album; see `best_candidate`.
+fn pick_review_url(urls: &[String], query: &Query) -> Option<String> {
+    best_candidate(query, urls, |url| review_slug(url).map(Candidate::new)).cloned()
+}
+
+/// The artist and album part of a post URL, without a trailing "-review":
+/// "/2025/07/14/wet-leg-moisturizer-review/" -> "wet-leg-moisturizer". None
+/// for links outside the dated post paths, such as category and author
+/// pages.
+fn review_slug(url: &str) -> Option<String> {
+    let path = url.split_once("://")?.1;
+    let path = path.split(['?', '#']).next()?.trim_end_matches('/');
+    let mut segments = path.split('/').skip(1);
+    let year = segments.next()?;
+    if year.len() != 4 || !year.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let slug = slugify(segments.last()?);
+    let album = slug.strip_suffix("-review").unwrap_or(&slug);
+    Some(album.to_string()).filter(|album| !album.is_empty())
+}
+
+/// Parse a Spectrum Culture review page for the star rating (out of 5) and
+/// the review's text, author and date.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let article = find_json_ld_node(html, ARTICLE_TYPES);
+    let field = |key: &str| article.as_ref().and_then(|node| node.get(key));
+
+    let rating = parse_stars(article.as_ref(), html);
+    let excerpt = parse_review_body(html).or_else(|| meta_content(html, "og:description"));
+    if rating.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    let reviewer = field("author")
+        .and_then(json_ld_name)
+        .or_else(|| meta_content(html, "author"));
+    let review_date = meta_content(html, "article:published_time")
+        .or_else(|| field("datePublished")?.as_str().map(str::to_string))
+        .and_then(|date| normalize_date(&date));
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(rating.map(|r| (r, STAR_SCALE)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The star rating, from the JSON-LD `reviewRating` or else the rating
+/// element's stars (see `parse_star_rating`).
+fn parse_stars(article: Option<&serde_json::Value>, html: &str) -> Option<f64> {
+    let from_json_ld = article
+        .and_then(|node| node.get("reviewRating"))
+        .and_then(|rating| json_ld_rating(rating, STAR_SCALE));
+    from_json_ld.or_else(|| {
+        let start = html.find(RATING_MARKER)?;
+        let end = html[start..].find("</div>")? + start;
+        parse_star_rating(&html[start..end], STAR_SCALE)
+    })
+}
+
+/// The review's paragraphs, cut to ~2000 chars at a sentence boundary.
+fn parse_review_body(html: &str) -> Option<String> {
+    let start = html.find(BODY_MARKER)?;
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |end| start + end);
+    let text = paragraph_text(&html[start..end]);
+    Some(truncate_at_sentence(&text, MAX_EXCERPT_CHARS)).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://spectrumculture.com";
+
+    #[test]
+    fn posts_live_under_date_paths() {
+        let slug = |path: &str| review_slug(&format!("{}{}", BASE, path));
+        let review = slug("/2025/07/14/wet-leg-moisturizer-review/?amp=1");
+        assert_eq!(review.as_deref(), Some("wet-leg-moisturizer"));
+        let older = slug("/2013/02/wet-leg-wet-leg/");
+        assert_eq!(older.as_deref(), Some("wet-leg-wet-leg"));
+        assert_eq!(slug("/category/music-reviews/"), None);
+        assert_eq!(slug("/author/holly-hazelwood/"), None);
+        assert_eq!(slug("/2025/"), None);
+    }
+
+    #[test]
+    fn search_results_filtered_by_post_category() {
+        let html = r#"<article class="post type-post category-features">
+            <a href="/2025/07/10/wet-leg-moisturizer-track-by-track/">Feature</a></article>
+            <article class="post type-post category-film-reviews">
+            <a href="/2025/07/12/moisturizer-review/">Film</a></article>
+            <article id="post-9" class="post type-post status-publish category-music-reviews">
+            <a href="/category/music-reviews/">Music Reviews</a>
+            <a href="/2025/07/14/wet-leg-moisturizer-review/">Moisturizer</a></article>
+            <article class="post category-music-reviews tag-wet-leg">
+            <a href="/2022/04/08/wet-leg-wet-leg-review/">Wet Leg</a></article>
+            <article class="post category-music-reviews">
+            <a href="/2025/07/14/wet-leg-moisturizer-review/">Repeated</a></article>"#;
+        let urls = review_links(html, BASE);
+        let slugs: Vec<String> = urls.iter().filter_map(|url| review_slug(url)).collect();
+        assert_eq!(slugs, ["wet-leg-moisturizer", "wet-leg-wet-leg"]);
+        let chosen = pick_review_url(&urls, &Query::new("wet-leg", "moisturizer"));
+        assert_eq!(chosen.as_ref(), Some(&urls[0]));
+    }
+
+    #[test]
+    fn star_rating_kept_on_its_own_scale() {
+        let html = r#"<head><meta name="author" content="Holly Hazelwood">
+            <meta property="article:published_time" content="2025-07-14T10:00:00+00:00"></head>
+            <div class="star-rating"><span class="star-full"></span><span class="star-full"></span>
+            <span class="star-full"></span><span class="star-half"></span>
+            <span class="star-empty"></span></div>
+            <article><div class="entry-content"><p>Wet Leg get louder.</p></div></article>"#;
+        let url = format!("{}/2025/07/14/wet-leg-moisturizer-review/", BASE);
+        let review = parse_review_page(&url, html).unwrap();
+        assert_eq!(review.rating, Some(7.0));
+        assert_eq!(review.rating_raw, Some(3.5));
+        assert_eq!(review.rating_scale, Some(5.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Holly Hazelwood"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-14"));
+        assert_eq!(review.excerpt.as_deref(), Some("Wet Leg get louder."));
+
+        let data = r#"<div class="star-rating" data-rating="4"></div>"#;
+        assert_eq!(parse_stars(None, data), Some(4.0));
+        let unrated = r#"<div class="star-rating"></div>"#;
+        assert_eq!(parse_stars(None, unrated), None);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod sputnikmusic;
+
+/// Review fields this source can populate.
+pub const CAPABILITIES: &[&str] = &["rating", "rating_count", "excerpt", "reviewer", "date"];
+
+editorial_common::editorial_plugin!(sputnikmusic, "sputnikmusic", CAPABILITIES);
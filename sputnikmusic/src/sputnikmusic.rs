@@ -0,0 +1,294 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, decode_html_entities,
+    fetch_page, html_to_text, match_confidence, path_segment, release_ids_conflict, site_links,
+    slugify, strip_html_tags, truncate_at_sentence, url_encode, AlbumReviewInput, Candidate,
+    FetchError, FetchOutcome, Query, SearchCandidate, SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `sputnikmusic_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://www.sputnikmusic.com";
+const BASE_URL_CONFIG: &str = "sputnikmusic_base_url";
+
+/// Where an album page may give the staff review's publication date.
+const REVIEW_DATE_MARKERS: &[&str] = &[
+    "<time datetime=\"",
+    "property=\"article:published_time\" content=\"",
+];
+
+/// Markers of the element holding the staff review text on an album page.
+const REVIEW_BODY_MARKERS: &[&str] = &["id=\"reviewtext\"", "class=\"reviewtext\""];
+
+/// The site root, honoring the `sputnikmusic_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch the Sputnikmusic staff review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its staff rating and user vote count,
+/// skipping the review text, reviewer and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the album page at a known Sputnikmusic URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_album_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (album_url, body) = fetch_album_page(input)?;
+    let mut review = parse_album_page(&album_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = album_confidence(&album_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (album_url, body) = fetch_album_page(input)?;
+    let text = page_text(&body);
+    let rating = parse_staff_rating(&text).ok_or(FetchError::NotFound)?;
+    let confidence = album_confidence(&album_url, input);
+    Ok(SiteReview::builder(album_url)
+        .native_rating(Some((rating, 5.0)))
+        .rating_count(parse_vote_count(&text))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album and fetch its page, rejecting pages whose release
+/// identifiers contradict the input.
+fn fetch_album_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let album_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&album_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((album_url, body))
+}
+
+fn album_confidence(album_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    album_slug(album_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the albums on the artist's Sputnikmusic band page and the one
+/// `fetch_review` would pick, without fetching the album page itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Sputnikmusic for the artist, then pick the album from the albums
+/// listed on their band page.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+
+    let base = base_url();
+    let search_url = format!(
+        "{}/search_results.php?genreid=0&search_in=Bands&search_text={}",
+        base,
+        url_encode(&input.artist)
+    );
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+
+    // A search with a single hit can land straight on the band page
+    let band_html = match band_page_url(&html, &base, &album.artist_slug) {
+        Some(band_url) => fetch_page(&build_request(&band_url, "text/html"))?,
+        None => html,
+    };
+
+    let album_urls = site_links(&band_html, &base, "album");
+    let mut result = SearchResult {
+        chosen: pick_album(&album_urls, &album),
+        ..SearchResult::default()
+    };
+    for url in album_urls {
+        let slug = album_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    Ok(result)
+}
+
+/// The band page among search results whose name slugs to the artist.
+fn band_page_url(html: &str, base: &str, artist_slug: &str) -> Option<String> {
+    site_links(html, base, "bands")
+        .into_iter()
+        .find(|url| path_segment(url, "bands", 0).is_some_and(|name| slugify(name) == artist_slug))
+}
+
+/// The best-scoring album for the album; see `best_candidate`.
+fn pick_album(album_urls: &[String], query: &Query) -> Option<String> {
+    best_candidate(query, album_urls, |url| album_slug(url).map(Candidate::new)).cloned()
+}
+
+/// The slug of an album URL: "/album/5/Radiohead-OK-Computer/" ->
+/// "radiohead-ok-computer".
+fn album_slug(url: &str) -> Option<String> {
+    path_segment(url, "album", 1)
+        .map(slugify)
+        .filter(|slug| !slug.is_empty())
+}
+
+/// Parse an album page for the staff rating (out of 5), the user vote
+/// count, and the staff review's text, author and date.
+fn parse_album_page(url: &str, html: &str) -> Option<SiteReview> {
+    let text = page_text(html);
+    let rating = parse_staff_rating(&text);
+    let excerpt = parse_review_body(html);
+
+    if rating.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(rating.map(|r| (r, 5.0)))
+            .rating_count(parse_vote_count(&text))
+            .reviewer(parse_reviewer(html))
+            .review_date(parse_review_date(html))
+            .build(),
+    )
+}
+
+/// The visible text of a page, with entities decoded.
+fn page_text(html: &str) -> String {
+    decode_html_entities(&strip_html_tags(html))
+}
+
+/// The staff rating that follows "Staff Rating", e.g. "Staff Rating: 4.5".
+/// Only values on Sputnik's 0-5 scale are accepted.
+fn parse_staff_rating(text: &str) -> Option<f64> {
+    let marker = "Staff Rating";
+    let rest = &text[text.find(marker)? + marker.len()..];
+    let rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ':');
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(rest.len());
+    let rating: f64 = rest[..end].parse().ok()?;
+    Some(rating).filter(|r| (0.0..=5.0).contains(r))
+}
+
+/// The user vote count from "User Rating: 4.2 | 1,234 votes".
+fn parse_vote_count(text: &str) -> Option<u32> {
+    let marker = "User Rating";
+    let rest = &text[text.find(marker)? + marker.len()..];
+    let votes = rest.find("votes").filter(|pos| *pos < 80)?;
+    let count = rest[..votes]
+        .trim_end()
+        .rsplit(|c: char| c.is_whitespace() || c == '|' || c == '(')
+        .next()?
+        .replace(',', "");
+    count.parse().ok()
+}
+
+/// The staff reviewer's name from "Review by <a ...>Name</a>".
+fn parse_reviewer(html: &str) -> Option<String> {
+    let marker = "Review by ";
+    let start = html.find(marker)? + marker.len();
+    let mut end = (start + 200).min(html.len());
+    while !html.is_char_boundary(end) {
+        end -= 1;
+    }
+    let text = page_text(&html[start..end]);
+    let name = text.lines().next()?.split('|').next()?.trim();
+    Some(name.to_string()).filter(|name| !name.is_empty())
+}
+
+/// The review's publication date from a `<time datetime>` attribute or an
+/// `article:published_time` meta tag.
+fn parse_review_date(html: &str) -> Option<String> {
+    for marker in REVIEW_DATE_MARKERS {
+        if let Some(pos) = html.find(marker) {
+            let rest = &html[pos + marker.len()..];
+            let date = rest[..rest.find('"')?].trim();
+            if !date.is_empty() {
+                return Some(date.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// The staff review text, cut to ~2000 chars at a sentence boundary.
+fn parse_review_body(html: &str) -> Option<String> {
+    let marker_pos = REVIEW_BODY_MARKERS
+        .iter()
+        .find_map(|marker| html.find(marker))?;
+    let content_start = html[marker_pos..].find('>')? + marker_pos + 1;
+    let content_end = html[content_start..].find("</div>")? + content_start;
+
+    let body = html_to_text(&html[content_start..content_end]);
+    if body.is_empty() {
+        return None;
+    }
+    Some(truncate_at_sentence(&body, MAX_EXCERPT_CHARS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://www.sputnikmusic.com";
+
+    #[test]
+    fn album_picked_from_band_page() {
+        let html = r#"<a href="/bands/Radiohead/3/">Radiohead</a>
+            <a href="/album/5/Radiohead-OK-Computer/">OK Computer</a>
+            <a href="https://www.sputnikmusic.com/album/246/Radiohead-OK-Computer-OKNOTOK/">OKNOTOK</a>
+            <a href="/album/7/Radiohead-Kid-A/">Kid A</a>
+            <a href="https://elsewhere.example/album/1/Radiohead-Kid-A/">Mirror</a>"#;
+        let albums = site_links(html, BASE, "album");
+        assert_eq!(albums.len(), 3);
+
+        let chosen = pick_album(&albums, &Query::new("radiohead", "ok-computer"));
+        let ok_computer = format!("{}/album/5/Radiohead-OK-Computer/", BASE);
+        assert_eq!(chosen, Some(ok_computer));
+        let kid_a = pick_album(&albums, &Query::new("the-radiohead", "kid-a"));
+        assert_eq!(kid_a, Some(format!("{}/album/7/Radiohead-Kid-A/", BASE)));
+        let amnesiac = Query::new("radiohead", "amnesiac");
+        assert_eq!(pick_album(&albums, &amnesiac), None);
+
+        let band = band_page_url(html, BASE, "radiohead");
+        assert_eq!(band, Some(format!("{}/bands/Radiohead/3/", BASE)));
+        assert_eq!(band_page_url(html, BASE, "radio"), None);
+    }
+
+    #[test]
+    fn album_page_staff_review() {
+        let html = r#"<table><tr><td><b>Staff Rating:</b> <font size="4">4.5</font></td>
+            <td>User Rating: 4.6 | 29,584 votes</td></tr></table>
+            <div>Review by <a href="/user/1">Jane&nbsp;Doe</a> | STAFF</div>
+            <time datetime="2006-06-07">June 7th, 2006</time>
+            <div id="reviewtext">Opening   line &mdash; bold.<br><br>Second paragraph.</div>"#;
+        let url = format!("{}/album/5/Radiohead-OK-Computer/", BASE);
+        let review = parse_album_page(&url, html).unwrap();
+        assert_eq!(review.rating, Some(9.0));
+        assert_eq!(review.rating_raw, Some(4.5));
+        assert_eq!(review.rating_count, Some(29584));
+        assert_eq!(review.reviewer.as_deref(), Some("Jane Doe"));
+        assert_eq!(review.review_date.as_deref(), Some("2006-06-07"));
+        let excerpt = "Opening line \u{2014} bold.\n\nSecond paragraph.";
+        assert_eq!(review.excerpt.as_deref(), Some(excerpt));
+
+        // A rating off the 0-5 scale isn't Sputnik's staff rating
+        assert_eq!(parse_staff_rating("Staff Rating: 7.5"), None);
+        assert!(parse_album_page(&url, "<p>No review here.</p>").is_none());
+    }
+}
@@ -0,0 +1,6 @@
+pub mod stereogum;
+
+/// Review fields this source can populate.
+pub const CAPABILITIES: &[&str] = &["excerpt", "reviewer", "date", "accolade"];
+
+editorial_common::editorial_plugin!(stereogum, "stereogum", CAPABILITIES);
@@ -0,0 +1,258 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, fetch_page, find_json_ld_node,
+    json_ld_name, match_confidence, meta_content, paragraph_text, release_ids_conflict,
+    search_terms, site_links, slugify, truncate_at_sentence, url_encode, AlbumReviewInput,
+    Candidate, FetchError, FetchOutcome, Query, SearchCandidate, SearchResult, SiteReview,
+    MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `stereogum_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://www.stereogum.com";
+const BASE_URL_CONFIG: &str = "stereogum_base_url";
+
+/// Review column slug prefixes, with the accolade each one confers. Premature
+/// Evaluation pieces are reviews but carry no distinction.
+const REVIEW_COLUMNS: &[(&str, Option<&str>)] = &[
+    ("album-of-the-week", Some("Album Of The Week")),
+    ("premature-evaluation", None),
+];
+
+/// Markers of the element holding an article's body.
+const ARTICLE_BODY_MARKERS: &[&str] = &["class=\"article-content", "class=\"entry-content"];
+
+/// The site root, honoring the `stereogum_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch a Stereogum review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Stereogum doesn't score albums: resolve the article search would pick,
+/// without fetching it, and return it with no rating.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the article at a known Stereogum URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_article_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let article_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&article_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+
+    let mut review = parse_article_page(&article_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&article_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let article_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&article_url, input);
+    Ok(SiteReview::builder(article_url)
+        .match_confidence(confidence)
+        .build())
+}
+
+/// How well the article's URL slug matches the album.
+fn review_confidence(article_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_column(article_url).map(|(slug, _)| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the review articles Stereogum's search offers for an album and the
+/// one `fetch_review` would pick, without fetching the article itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Stereogum for "artist title" and keep every review-column article
+/// it lists along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/?s={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+
+    // Article paths start with a numeric post ID ("/2245678/slug/..."), so
+    // every on-site link is a candidate and the slug picks out reviews
+    let urls: Vec<String> = site_links(&html, &base, "")
+        .into_iter()
+        .filter(|url| review_column(url).is_some())
+        .collect();
+    let mut result = SearchResult {
+        chosen: pick_article_url(&urls, &album),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_column(&url)
+            .map(|(slug, _)| slug)
+            .unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    Ok(result)
+}
+
+/// The best-scoring review article for the album; see `best_candidate`.
+fn pick_article_url(urls: &[String], query: &Query) -> Option<String> {
+    let candidate_of = |url: &String| review_column(url).map(|(slug, _)| Candidate::new(slug));
+    best_candidate(query, urls, candidate_of).cloned()
+}
+
+/// Split a review article URL into its album slug and the column's
+/// accolade:
+/// "/2245678/album-of-the-week-wednesday-bleeds/reviews/album-of-the-week/"
+/// -> ("wednesday-bleeds", Some("Album Of The Week")). None for articles
+/// outside the review columns.
+fn review_column(url: &str) -> Option<(String, Option<&'static str>)> {
+    let path = url.split_once("://")?.1;
+    let mut segments = path.split(['?', '#']).next()?.split('/').skip(1);
+    let id = segments.next()?;
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let slug = slugify(segments.next()?);
+    REVIEW_COLUMNS.iter().find_map(|(column, accolade)| {
+        let album = slug.strip_prefix(column)?.strip_prefix('-')?;
+        Some((album.to_string(), *accolade)).filter(|(album, _)| !album.is_empty())
+    })
+}
+
+/// Parse a Stereogum article for its body text, byline and publish date.
+/// There is no score; the Album Of The Week column is reported as an
+/// accolade.
+fn parse_article_page(url: &str, html: &str) -> Option<SiteReview> {
+    let excerpt = parse_article_body(html)?;
+    let article = find_json_ld_node(html, &["NewsArticle", "Article"]);
+    let field = |key: &str| article.as_ref().and_then(|node| node.get(key));
+
+    let reviewer = meta_content(html, "author").or_else(|| field("author").and_then(json_ld_name));
+    let review_date = meta_content(html, "article:published_time").or_else(|| {
+        field("datePublished")
+            .and_then(|date| date.as_str())
+            .map(str::to_string)
+    });
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(Some(excerpt))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .accolade(parse_accolade(url, html))
+            .build(),
+    )
+}
+
+/// "Album Of The Week" when the URL is in that column or the page is tagged
+/// with it.
+fn parse_accolade(url: &str, html: &str) -> Option<String> {
+    let from_url = review_column(url).and_then(|(_, accolade)| accolade);
+    let from_page = || {
+        let section = meta_content(html, "article:section")?;
+        REVIEW_COLUMNS
+            .iter()
+            .filter_map(|(_, accolade)| *accolade)
+            .find(|accolade| section == *accolade)
+    };
+    from_url.or_else(from_page).map(str::to_string)
+}
+
+/// The article's paragraphs as text, cut to ~2000 chars at a sentence
+/// boundary. Only `<p>` elements count, which skips embeds, captions and
+/// share widgets in the body.
+fn parse_article_body(html: &str) -> Option<String> {
+    let start = ARTICLE_BODY_MARKERS
+        .iter()
+        .find_map(|marker| html.find(marker))?;
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |end| start + end);
+
+    let text = paragraph_text(&html[start..end]);
+    Some(truncate_at_sentence(&text, MAX_EXCERPT_CHARS)).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://www.stereogum.com";
+
+    #[test]
+    fn review_columns_recognized_from_url() {
+        let aotw = format!(
+            "{}/2245678/album-of-the-week-wednesday-bleeds/reviews/album-of-the-week/",
+            BASE
+        );
+        let (slug, accolade) = review_column(&aotw).unwrap();
+        assert_eq!(slug, "wednesday-bleeds");
+        assert_eq!(accolade, Some("Album Of The Week"));
+
+        let early = format!(
+            "{}/2240000/premature-evaluation-wednesday-bleeds/reviews/premature-evaluation/",
+            BASE
+        );
+        assert_eq!(review_column(&early).unwrap().1, None);
+        let news = format!("{}/2240001/wednesday-announce-bleeds/news/", BASE);
+        assert_eq!(review_column(&news), None);
+        let column_page = format!("{}/reviews/album-of-the-week/", BASE);
+        assert_eq!(review_column(&column_page), None);
+
+        let urls = vec![news, early.clone(), aotw.clone()];
+        let chosen = pick_article_url(&urls, &Query::new("wednesday", "bleeds"));
+        assert_eq!(chosen, Some(early));
+    }
+
+    #[test]
+    fn article_text_without_rating() {
+        let html = r#"<head><meta name="author" content="Tom Breihan">
+            <meta property="article:published_time" content="2025-09-17T10:41:00+00:00">
+            <meta property="article:section" content="Album Of The Week"></head>
+            <article><div class="article-content">
+            <p>Wednesday have made their &ldquo;big&rdquo; record.</p>
+            <figure><picture><img src="x.jpg"></picture><figcaption>Photo</figcaption></figure>
+            <p class="lead">It <em>rips</em>.</p><div class="share">Share</div>
+            </div></article><p>Related stories</p>"#;
+        let url = format!("{}/2245678/wednesday-bleeds/music/", BASE);
+        let review = parse_article_page(&url, html).unwrap();
+        assert_eq!(review.rating, None);
+        assert_eq!(
+            review.excerpt.as_deref(),
+            Some("Wednesday have made their \u{201c}big\u{201d} record.\n\nIt rips.")
+        );
+        assert_eq!(review.reviewer.as_deref(), Some("Tom Breihan"));
+        let date = review.review_date.unwrap();
+        assert_eq!(date, "2025-09-17T10:41:00+00:00");
+        assert_eq!(review.accolade.as_deref(), Some("Album Of The Week"));
+
+        let plain = html.replace("Album Of The Week", "Music");
+        assert_eq!(parse_article_page(&url, &plain).unwrap().accolade, None);
+    }
+}
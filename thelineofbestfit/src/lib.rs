@@ -1,16 +1,24 @@
-mod thelineofbestfit;
+pub mod thelineofbestfit;
 
-use editorial_common::{wrap_review, AlbumReviewInput};
-use extism_pdk::*;
+/// Review fields this source can populate.
+pub const CAPABILITIES: &[&str] = &["rating", "excerpt", "reviewer", "date"];
 
-#[plugin_fn]
-pub fn riff_health_check(_input: String) -> FnResult<String> {
-    Ok("ok".to_string())
-}
+editorial_common::editorial_plugin!(
+    thelineofbestfit,
+    "thelineofbestfit",
+    CAPABILITIES,
+    // Review every album against a single warm-up of the listing cache
+    fetch_batch: Some(thelineofbestfit::fetch_reviews),
+    // While the crawl is incomplete a miss may just mean "not crawled yet"
+    miss_is_final: thelineofbestfit::crawl_complete,
+    cache_stats: thelineofbestfit::cache_stats,
+    clear_urls: Some(thelineofbestfit::clear_url_cache),
+);
 
-#[plugin_fn]
-pub fn riff_get_album_reviews(input: String) -> FnResult<String> {
-    let params: AlbumReviewInput = serde_json::from_str(&input)?;
-    let review = thelineofbestfit::fetch_review(&params.artist, &params.title);
-    Ok(wrap_review("thelineofbestfit", review))
+editorial_common::plugin_exports! {
+    #[extism_pdk::plugin_fn]
+    pub fn riff_prewarm_cache(input: String) -> extism_pdk::FnResult<String> {
+        let request = editorial_common::PrewarmInput::parse(&input);
+        Ok(thelineofbestfit::prewarm(request.pages).to_json())
+    }
 }
@@ -1,19 +1,172 @@
-use editorial_common::{clean_title, slugify, SiteReview};
+use editorial_common::{
+    best_candidate, build_request, configured_base_url, decode_html_entities, fetch_page,
+    http_get_with_retry, is_challenge_page, json_ld_is, response_header, response_text,
+    scale_rating, score_candidate, strip_html_tags, truncate_at_sentence, unix_now,
+    AlbumReviewInput, CacheStats, Candidate, FetchError, FetchOutcome, Query, SearchCandidate,
+    SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
 use extism_pdk::*;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 
+/// Production site root; override with the `tlobf_base_url` config value.
 const BASE_URL: &str = "https://www.thelineofbestfit.com";
-const LISTING_URL: &str = "https://www.thelineofbestfit.com/albums";
+const BASE_URL_CONFIG: &str = "tlobf_base_url";
 const BATCH_SIZE: u32 = 25;
+/// Extism config keys for tuning crawl aggressiveness.
+const BATCH_SIZE_CONFIG: &str = "tlobf_batch_size";
+const MAX_PAGES_CONFIG: &str = "tlobf_max_pages";
+/// Fallback crawl bound when the listing pagination can't be parsed.
 const MAX_PAGES: u32 = 348;
 const CACHE_VAR: &str = "tlobf_cache";
+/// Upper bound on cached slugs (roughly the newest ~200 listing pages).
+/// The listing is newest-first, so once the cap is hit the crawl stops and the
+/// oldest albums are evicted: those lookups miss, but the serialized var
+/// stays bounded instead of growing with the whole archive on every call.
+const MAX_CACHED_SLUGS: usize = 5000;
+/// Re-scan the front of the listing this often to pick up new publications.
+const FRONT_REFRESH_SECS: u64 = 6 * 60 * 60;
+/// Number of front listing pages re-scanned on each refresh.
+const FRONT_REFRESH_PAGES: u32 = 2;
+/// Trailing slug words that mark a distinct release of the same album, so
+/// "artist-album-remixes" isn't taken as the review of "artist-album".
+/// Numbers and ordinals ("-2", "-10th") are treated the same way.
+const VARIANT_TOKENS: &[&str] = &[
+    "anniversary",
+    "deluxe",
+    "demos",
+    "edition",
+    "expanded",
+    "instrumentals",
+    "live",
+    "remaster",
+    "remastered",
+    "remix",
+    "remixed",
+    "remixes",
+    "reissue",
+];
 
 /// Progressive URL cache stored in Extism vars across calls.
 /// Stores slugs only (not full URLs) to reduce serialized size by ~60%.
+/// Slugs are kept sorted so lookups can binary-search by prefix.
 #[derive(Serialize, Deserialize, Default)]
 struct UrlCache {
     next_page: u32,
     slugs: Vec<String>,
+    /// Listing position of each entry in `slugs`, lower is newer. Used to
+    /// evict the oldest albums and to prefer the newest of colliding slugs.
+    #[serde(default)]
+    ranks: Vec<i64>,
+    /// Unix time the front listing pages were last re-scanned.
+    #[serde(default)]
+    front_refreshed_at: u64,
+    /// Last listing page number parsed from pagination (0 = unknown).
+    /// Refreshed whenever page 1 is fetched.
+    #[serde(default)]
+    max_pages: u32,
+    /// Cache validators per listing page, sent back on re-fetches so an
+    /// unchanged page costs a 304 instead of a full download and parse.
+    /// Cleared with the slugs on reset, so a 304 always means "already cached".
+    #[serde(default)]
+    validators: BTreeMap<u32, PageValidator>,
+}
+
+/// `ETag` / `Last-Modified` values from a listing page response.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+struct PageValidator {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+}
+
+/// Result of fetching one listing page.
+enum ListingFetch {
+    Page {
+        html: String,
+        validator: Option<PageValidator>,
+    },
+    NotModified,
+    Failed,
+}
+
+impl UrlCache {
+    /// Insert a slug at a listing rank, ignoring duplicates. Returns false if
+    /// the slug was already cached.
+    fn insert(&mut self, slug: String, rank: i64) -> bool {
+        match self.slugs.binary_search(&slug) {
+            Ok(_) => false,
+            Err(pos) => {
+                self.slugs.insert(pos, slug);
+                self.ranks.insert(pos, rank);
+                true
+            }
+        }
+    }
+
+    /// Whether the front of the listing is due for a re-scan.
+    fn front_stale(&self, now: u64) -> bool {
+        now.saturating_sub(self.front_refreshed_at) >= FRONT_REFRESH_SECS
+    }
+
+    /// Drop the oldest slugs beyond `MAX_CACHED_SLUGS`.
+    fn evict_oldest(&mut self) {
+        if self.slugs.len() <= MAX_CACHED_SLUGS {
+            return;
+        }
+        let mut ranks = self.ranks.clone();
+        ranks.sort_unstable();
+        let cutoff = ranks[MAX_CACHED_SLUGS - 1];
+        (self.slugs, self.ranks) = std::mem::take(&mut self.slugs)
+            .into_iter()
+            .zip(std::mem::take(&mut self.ranks))
+            .filter(|(_, rank)| *rank <= cutoff)
+            .unzip();
+    }
+
+    /// The crawl bound: the parsed page count (capped by a configured limit),
+    /// the configured limit if the count is unknown, or `MAX_PAGES`.
+    fn crawl_bound(&self, settings: &CrawlSettings) -> u32 {
+        match (self.max_pages, settings.max_pages) {
+            (0, Some(limit)) => limit,
+            (0, None) => MAX_PAGES,
+            (parsed, Some(limit)) => parsed.min(limit),
+            (parsed, None) => parsed,
+        }
+    }
+}
+
+/// Crawl tuning read from Extism config, falling back to the built-in defaults.
+struct CrawlSettings {
+    batch_size: u32,
+    max_pages: Option<u32>,
+}
+
+impl CrawlSettings {
+    fn load() -> Self {
+        let get = |key| config::get(key).ok().flatten();
+        Self::parse(
+            get(BATCH_SIZE_CONFIG).as_deref(),
+            get(MAX_PAGES_CONFIG).as_deref(),
+        )
+    }
+
+    /// Build settings from raw config values, clamping numbers to their ranges
+    /// and falling back to the defaults for anything else.
+    fn parse(batch_size: Option<&str>, max_pages: Option<&str>) -> Self {
+        CrawlSettings {
+            batch_size: parse_clamped(batch_size, 1, 100).unwrap_or(BATCH_SIZE),
+            max_pages: parse_clamped(max_pages, 1, 5000),
+        }
+    }
+}
+
+/// Parse a numeric config value clamped to `min..=max`, ignoring values that
+/// aren't whole numbers.
+fn parse_clamped(raw: Option<&str>, min: u32, max: u32) -> Option<u32> {
+    let value: u64 = raw?.trim().parse().ok()?;
+    Some(value.clamp(min as u64, max as u64) as u32)
 }
 
 /// JSON-LD structures for MusicAlbum review pages.
@@ -28,8 +181,10 @@ struct JsonLd {
 
 #[derive(Deserialize)]
 struct JsonLdReview {
+    /// A `Rating` object, a list of them, or a `Rating` nested inside
+    /// another object; see `ld_rating`.
     #[serde(rename = "reviewRating")]
-    review_rating: Option<JsonLdRating>,
+    review_rating: Option<serde_json::Value>,
     author: Option<JsonLdAuthor>,
     #[serde(rename = "datePublished")]
     date_published: Option<String>,
@@ -37,121 +192,403 @@ struct JsonLdReview {
     review_body: Option<String>,
 }
 
-#[derive(Deserialize)]
-struct JsonLdRating {
-    #[serde(rename = "ratingValue")]
-    rating_value: Option<serde_json::Value>,
-    #[serde(rename = "bestRating")]
-    best_rating: Option<serde_json::Value>,
-}
-
 #[derive(Deserialize)]
 struct JsonLdAuthor {
     name: Option<String>,
 }
 
-/// Fetch a review from The Line of Best Fit for the given album.
-pub fn fetch_review(artist: &str, title: &str) -> Option<SiteReview> {
-    let review_url = find_review_url(artist, title)?;
+/// Fetch reviews from The Line of Best Fit for the given albums, in order.
+/// The listing crawl is advanced once for the whole batch rather than per album.
+pub fn fetch_reviews(albums: &[&AlbumReviewInput]) -> Vec<FetchOutcome> {
+    if albums.is_empty() {
+        return Vec::new();
+    }
+    let cache = warm_cache();
+    albums
+        .iter()
+        .map(|album| lookup_review(&cache, album).into())
+        .collect()
+}
 
-    let req = HttpRequest::new(&review_url).with_header("Accept", "text/html");
-    let resp = http::request::<()>(&req, None).ok()?;
-    if resp.status_code() != 200 {
-        return None;
+/// Fetch a review from The Line of Best Fit for one album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    let mut outcomes = fetch_reviews(&[input]);
+    outcomes.pop().unwrap_or(FetchOutcome::NotFound)
+}
+
+/// Resolve the album and read its rating from the review page's JSON-LD,
+/// skipping the article-body parse.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    let cache = warm_cache();
+    lookup_page(&cache, input).map(|(review, _)| review).into()
+}
+
+/// Parse the review at a known Line of Best Fit URL, skipping the crawl.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let (mut review, html) = fetch_review_page(url)?;
+    review.excerpt = extract_article_body(&html).or(review.excerpt);
+    Ok(review)
+}
+
+fn lookup_review(cache: &UrlCache, input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (mut review, html) = lookup_page(cache, input)?;
+    // Full review text comes from the HTML body rather than JSON-LD
+    if input.options.include_body {
+        if let Some(body_text) = extract_article_body(&html) {
+            review.excerpt = Some(body_text);
+        }
     }
+    Ok(review)
+}
+
+/// Find and fetch the album's review page, returning the rating, reviewer and
+/// date from its JSON-LD along with the page HTML.
+fn lookup_page(
+    cache: &UrlCache,
+    input: &AlbumReviewInput,
+) -> Result<(SiteReview, String), FetchError> {
+    let (artist, title) = (input.artist.as_str(), input.title.as_str());
+    let review_url = find_review_url(cache, artist, title).ok_or(FetchError::NotFound)?;
+
+    let (mut review, html) = fetch_review_page(&review_url)?;
+    let album = Query::new(artist, title);
+    review.match_confidence = review_url
+        .split("/albums/")
+        .nth(1)
+        .map(|slug| score_candidate(&album, &Candidate::new(slug)));
+    Ok((review, html))
+}
 
-    let html = String::from_utf8(resp.body().to_vec()).ok()?;
+/// Fetch a review page and parse its JSON-LD, returning the page HTML too.
+fn fetch_review_page(review_url: &str) -> Result<(SiteReview, String), FetchError> {
+    let html = fetch_page(&build_request(review_url, "text/html"))?;
+    let review = parse_json_ld(&html, review_url).ok_or(FetchError::NotFound)?;
+    Ok((review, html))
+}
 
-    // Get rating, reviewer, date from JSON-LD; full review text from HTML body
-    let mut review = parse_json_ld(&html, &review_url)?;
-    if let Some(body_text) = extract_article_body(&html) {
-        review.excerpt = Some(body_text);
+/// List the cached review URLs sharing the album's slug prefix and the one
+/// `fetch_reviews` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    let (artist, title) = (input.artist.as_str(), input.title.as_str());
+    let cache = warm_cache();
+    let base = base_url();
+    let album = Query::new(artist, title);
+
+    let mut result = SearchResult {
+        chosen: match_url(&cache, &album, &base),
+        ..SearchResult::default()
+    };
+    for prefix in slug_prefixes(&album) {
+        for (slug, _) in prefix_run(&cache, &prefix) {
+            let url = format!("{}/albums/{}", base, slug);
+            result.add(SearchCandidate::scored(url, slug.clone(), &album));
+        }
     }
-    Some(review)
+    result
 }
 
 /// Search the progressive URL cache for a matching review URL.
-fn find_review_url(artist: &str, title: &str) -> Option<String> {
-    let cleaned = clean_title(title);
-    let artist_slug = slugify(artist);
-    let album_slug = slugify(cleaned);
-    let prefix = format!("{}-{}", artist_slug, album_slug);
+fn find_review_url(cache: &UrlCache, artist: &str, title: &str) -> Option<String> {
+    match_url(cache, &Query::new(artist, title), &base_url())
+}
 
-    if prefix.is_empty() {
-        return None;
+/// The `artist-album` slug prefixes a review URL for the album may start
+/// with, one per spelling of the artist (see `artist_aliases`). None if
+/// either half slugs to nothing, as it would match any review.
+fn slug_prefixes(album: &Query) -> Vec<String> {
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Vec::new();
     }
+    std::iter::once(&album.artist_slug)
+        .chain(&album.artist_aliases)
+        .map(|artist| format!("{}-{}", artist, album.title_slug))
+        .collect()
+}
 
+/// Load the progressive URL cache, re-scanning the front of the listing if
+/// it's stale and extending the crawl by one batch if it's incomplete.
+fn warm_cache() -> UrlCache {
     let mut cache = load_cache();
+    let now = unix_now();
+
+    if cache.next_page == 0 {
+        // The first batch starts at page 1, which covers the front
+        cache.front_refreshed_at = now;
+    } else if cache.front_stale(now) {
+        // New reviews push older ones onto later pages; re-scan the front
+        refresh_front(&mut cache, now, &base_url(), fetch_listing_page);
+        save_cache(&cache);
+    }
 
     // Extend the cache if incomplete
-    if cache.next_page < MAX_PAGES {
-        fetch_next_batch(&mut cache);
+    let settings = CrawlSettings::load();
+    if cache.next_page < cache.crawl_bound(&settings) {
+        fetch_next_batch(&mut cache, &settings, &base_url(), fetch_listing_page);
         save_cache(&cache);
     }
 
-    // Search for a matching URL by slug prefix
-    match_url(&cache, &prefix)
+    cache
+}
+
+/// The site root, honoring the `tlobf_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, BASE_URL)
 }
 
-/// Find a URL in the cache whose slug starts with the given prefix.
-fn match_url(cache: &UrlCache, prefix: &str) -> Option<String> {
-    let prefix_with_dash = format!("{}-", prefix);
-    for slug in &cache.slugs {
-        if slug == prefix || slug.starts_with(&prefix_with_dash) {
-            return Some(format!("{}/albums/{}", BASE_URL, slug));
+/// Whether the listing crawl has reached its bound, so a cache miss is final.
+pub fn crawl_complete() -> bool {
+    let cache = load_cache();
+    cache.next_page >= cache.crawl_bound(&CrawlSettings::load())
+}
+
+/// Find a URL in the cache whose slug is one of the album's prefixes (see
+/// `slug_prefixes`) or starts with `prefix-`. Slugs whose extra words mark
+/// a different release are skipped and the rest ranked with
+/// `best_candidate`, the newest listing entry winning ties.
+fn match_url(cache: &UrlCache, album: &Query, base: &str) -> Option<String> {
+    let prefixes = slug_prefixes(album);
+    let mut run: Vec<(&String, i64)> = prefixes
+        .iter()
+        .flat_map(|prefix| prefix_run(cache, prefix))
+        .collect();
+    run.sort_by_key(|&(_, rank)| rank);
+
+    let candidate_of = |&(slug, _): &(&String, i64)| {
+        let names_album = prefixes.iter().any(|prefix| {
+            let extra = match slug.strip_prefix(prefix.as_str()) {
+                Some("") => return true,
+                Some(rest) => rest.strip_prefix('-'),
+                None => None,
+            };
+            extra.is_some_and(|extra| !extra.split('-').any(is_variant_token))
+        });
+        names_album.then(|| Candidate::new(slug.as_str()))
+    };
+    let (slug, _) = best_candidate(album, &run, candidate_of)?;
+    Some(format!("{}/albums/{}", base, slug))
+}
+
+/// Whether a trailing slug word names a different release (see `VARIANT_TOKENS`).
+fn is_variant_token(token: &str) -> bool {
+    let digits = token.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let is_number = !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && matches!(&token[digits.len()..], "" | "st" | "nd" | "rd" | "th");
+    is_number || VARIANT_TOKENS.contains(&token)
+}
+
+/// Cached slugs starting with `prefix`, with their listing ranks. They form a
+/// contiguous run in the sorted cache, so the run is located by binary search
+/// and only it is scanned.
+fn prefix_run<'a>(cache: &'a UrlCache, prefix: &'a str) -> impl Iterator<Item = (&'a String, i64)> {
+    let start = cache.slugs.partition_point(|s| s.as_str() < prefix);
+    cache.slugs[start..]
+        .iter()
+        .zip(cache.ranks[start..].iter().copied())
+        .take_while(move |(slug, _)| slug.starts_with(prefix))
+}
+
+/// Fetch the next batch of listing pages with `fetch` and add discovered URLs
+/// to the cache.
+fn fetch_next_batch(
+    cache: &mut UrlCache,
+    settings: &CrawlSettings,
+    base: &str,
+    mut fetch: impl FnMut(u32, Option<&PageValidator>) -> ListingFetch,
+) {
+    let start = cache.next_page + 1;
+
+    for page in start..start + settings.batch_size {
+        // Re-checked each page: page 1 may have just updated the bound
+        if page > cache.crawl_bound(settings) {
+            break;
+        }
+
+        // Skip failed pages gracefully
+        let listing = fetch(page, cache.validators.get(&page));
+        if !apply_listing_page(cache, page, listing, base) {
+            continue;
+        }
+
+        cache.next_page = page;
+
+        // The listing is newest-first, so stopping here keeps the newest albums
+        if cache.slugs.len() >= MAX_CACHED_SLUGS {
+            cache.evict_oldest();
+            cache.next_page = cache.crawl_bound(settings);
+            break;
         }
     }
-    None
 }
 
-/// Fetch the next batch of listing pages and add discovered URLs to the cache.
-fn fetch_next_batch(cache: &mut UrlCache) {
-    let start = cache.next_page + 1;
-    let end = (start + BATCH_SIZE).min(MAX_PAGES + 1);
+/// Re-scan the newest listing pages with `fetch` and add any unseen slugs
+/// ahead of the cached ones, evicting the oldest past the cap.
+fn refresh_front(
+    cache: &mut UrlCache,
+    now: u64,
+    base: &str,
+    mut fetch: impl FnMut(u32, Option<&PageValidator>) -> ListingFetch,
+) {
+    // Last page first, so each page's new albums rank ahead of the one before
+    for page in (1..=FRONT_REFRESH_PAGES).rev() {
+        let listing = fetch(page, cache.validators.get(&page));
+        apply_listing_page(cache, page, listing, base);
+    }
 
-    for page in start..end {
-        let url = format!("{}?page={}", LISTING_URL, page);
-        let req = HttpRequest::new(&url).with_header("Accept", "text/html");
+    cache.evict_oldest();
+    cache.front_refreshed_at = now;
+}
 
-        let resp = match http::request::<()>(&req, None) {
-            Ok(r) => r,
-            Err(_) => {
-                // Skip failed pages gracefully
-                continue;
+/// Merge a fetched listing page into the cache. Returns false if the fetch
+/// failed. A 304 leaves the cache untouched: the page's slugs were stored when
+/// its validator was.
+fn apply_listing_page(cache: &mut UrlCache, page: u32, fetch: ListingFetch, base: &str) -> bool {
+    let (html, validator) = match fetch {
+        ListingFetch::Page { html, validator } => (html, validator),
+        ListingFetch::NotModified => return true,
+        ListingFetch::Failed => return false,
+    };
+
+    if page == 1 {
+        update_max_pages(cache, &html);
+    }
+    let slugs = extract_album_slugs(&html, base);
+    if page <= cache.next_page {
+        // A page the crawl already passed only gains newly published albums,
+        // which rank ahead of everything cached
+        let fresh: Vec<String> = slugs
+            .into_iter()
+            .filter(|slug| cache.slugs.binary_search(slug).is_err())
+            .collect();
+        let first = cache.ranks.iter().min().map_or(0, |newest| *newest) - fresh.len() as i64;
+        for (offset, slug) in fresh.into_iter().enumerate() {
+            cache.insert(slug, first + offset as i64);
+        }
+    } else {
+        let mut rank = cache.ranks.iter().max().map_or(0, |oldest| oldest + 1);
+        for slug in slugs {
+            if cache.insert(slug, rank) {
+                rank += 1;
             }
-        };
+        }
+    }
+    match validator {
+        Some(v) => cache.validators.insert(page, v),
+        None => cache.validators.remove(&page),
+    };
+    true
+}
 
-        if resp.status_code() != 200 {
-            continue;
+/// Fetch a single listing page, conditionally if a validator from an earlier
+/// fetch is known.
+///
+/// Bypasses the response cache: the front-page refresh depends on seeing the
+/// live listing.
+fn fetch_listing_page(page: u32, validator: Option<&PageValidator>) -> ListingFetch {
+    let url = format!("{}/albums?page={}", base_url(), page);
+    let mut req = build_request(&url, "text/html");
+    if let Some(v) = validator {
+        if let Some(etag) = &v.etag {
+            req = req.with_header("If-None-Match", etag);
+        }
+        if let Some(modified) = &v.last_modified {
+            req = req.with_header("If-Modified-Since", modified);
+        }
+    }
+
+    let Ok(resp) = http_get_with_retry(&req) else {
+        return ListingFetch::Failed;
+    };
+    match resp.status_code() {
+        304 if validator.is_some() => ListingFetch::NotModified,
+        200 => {
+            let html = response_text(&resp);
+            if is_challenge_page(&html) {
+                return ListingFetch::Failed;
+            }
+            ListingFetch::Page {
+                html,
+                validator: page_validator(
+                    response_header(&resp, "ETag"),
+                    response_header(&resp, "Last-Modified"),
+                ),
+            }
         }
+        _ => ListingFetch::Failed,
+    }
+}
 
-        if let Ok(html) = String::from_utf8(resp.body().to_vec()) {
-            let new_slugs = extract_album_slugs(&html);
-            for slug in new_slugs {
-                // Deduplicate: only add if not already present
-                if !cache.slugs.iter().any(|s| s == &slug) {
-                    cache.slugs.push(slug);
+/// Build a validator from response headers, or None if neither is present.
+fn page_validator(etag: Option<&str>, last_modified: Option<&str>) -> Option<PageValidator> {
+    let clean = |v: Option<&str>| v.map(str::trim).filter(|v| !v.is_empty()).map(String::from);
+    let validator = PageValidator {
+        etag: clean(etag),
+        last_modified: clean(last_modified),
+    };
+    (validator != PageValidator::default()).then_some(validator)
+}
+
+/// Record the listing's page count from page 1's pagination, keeping the
+/// previous value if the markup can't be parsed.
+fn update_max_pages(cache: &mut UrlCache, html: &str) {
+    if let Some(last) = parse_last_page(html) {
+        cache.max_pages = last;
+    }
+}
+
+/// Parse the last page number from listing pagination links
+/// (the highest `page=N` among `/albums` hrefs).
+fn parse_last_page(html: &str) -> Option<u32> {
+    let pattern = "href=\"";
+    let mut last = None;
+    let mut search_from = 0;
+
+    while let Some(pos) = html[search_from..].find(pattern) {
+        let value_start = search_from + pos + pattern.len();
+        let Some(end_offset) = html[value_start..].find('"') else {
+            break;
+        };
+        let href = &html[value_start..value_start + end_offset];
+
+        if href.contains("/albums") {
+            if let Some(page_pos) = href.find("page=") {
+                let digits: String = href[page_pos + "page=".len()..]
+                    .chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect();
+                if let Ok(n) = digits.parse::<u32>() {
+                    last = Some(last.map_or(n, |l: u32| l.max(n)));
                 }
             }
         }
 
-        cache.next_page = page;
+        search_from = value_start + end_offset;
     }
+
+    last
 }
 
 /// Extract all album slugs from a listing page HTML.
-/// Matches both relative (`/albums/slug`) and absolute (`https://...thelineofbestfit.com/albums/slug`) URLs.
-fn extract_album_slugs(html: &str) -> Vec<String> {
+/// Matches both relative (`/albums/slug`) and absolute (`https://...thelineofbestfit.com/albums/slug`) URLs,
+/// plus absolute URLs under the configured base.
+fn extract_album_slugs(html: &str, base: &str) -> Vec<String> {
     let mut results = Vec::new();
-    let mut seen = std::collections::HashSet::new();
+    let mut seen = HashSet::new();
 
     // Match both relative and absolute album URL patterns
-    let patterns: &[&str] = &[
-        "href=\"/albums/",
-        "href=\"https://www.thelineofbestfit.com/albums/",
+    let mut patterns = vec![
+        "href=\"/albums/".to_string(),
+        format!("href=\"{}/albums/", BASE_URL),
     ];
+    if base != BASE_URL {
+        patterns.push(format!("href=\"{}/albums/", base));
+    }
 
-    for pattern in patterns {
+    for pattern in &patterns {
         let mut search_from = 0;
         while let Some(pos) = html[search_from..].find(pattern) {
             let abs_pos = search_from + pos;
@@ -162,10 +599,12 @@ fn extract_album_slugs(html: &str) -> Vec<String> {
                 let slug = &html[slug_start..slug_start + end_offset];
 
                 // Skip empty slugs or slugs with query params/fragments
-                if !slug.is_empty() && !slug.contains('?') && !slug.contains('#') {
-                    if seen.insert(slug.to_string()) {
-                        results.push(slug.to_string());
-                    }
+                if !slug.is_empty()
+                    && !slug.contains('?')
+                    && !slug.contains('#')
+                    && seen.insert(slug.to_string())
+                {
+                    results.push(slug.to_string());
                 }
 
                 search_from = slug_start + end_offset;
@@ -187,38 +626,8 @@ fn extract_article_body(html: &str) -> Option<String> {
     // Find the end of the opening tag
     let content_start = html[marker_pos..].find('>')? + marker_pos + 1;
 
-    // Walk nested divs to find the matching close
-    let mut depth: u32 = 1;
-    let mut pos = content_start;
-    let content_end;
-
-    loop {
-        let next_open = html[pos..].find("<div");
-        let next_close = html[pos..].find("</div>");
-
-        let close_abs = match next_close {
-            Some(c) => pos + c,
-            None => return None,
-        };
-
-        if let Some(o) = next_open {
-            let open_abs = pos + o;
-            if open_abs < close_abs {
-                depth += 1;
-                pos = open_abs + 4;
-                continue;
-            }
-        }
-
-        depth -= 1;
-        if depth == 0 {
-            content_end = close_abs;
-            break;
-        }
-        pos = close_abs + 6;
-    }
-
-    let raw = &html[content_start..content_end];
+    let content_end = matching_div_close(html, content_start)?;
+    let raw = strip_hidden_markup(&html[content_start..content_end]);
 
     // Insert paragraph breaks before block-level closing tags
     let raw = raw
@@ -227,20 +636,7 @@ fn extract_article_body(html: &str) -> Option<String> {
         .replace("<br/>", "\n")
         .replace("<br />", "\n");
 
-    // Strip HTML tags
-    let text = strip_html_tags(&raw);
-
-    // Decode common HTML entities
-    let text = text
-        .replace("&amp;", "&")
-        .replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&#39;", "'")
-        .replace("&#039;", "'")
-        .replace("&apos;", "'")
-        .replace("&ndash;", "\u{2013}")
-        .replace("&mdash;", "\u{2014}");
+    let text = decode_html_entities(&strip_html_tags(&raw));
 
     // Collapse runs of whitespace while preserving paragraph breaks (\n\n)
     let paragraphs: Vec<String> = text
@@ -269,46 +665,108 @@ fn extract_article_body(html: &str) -> Option<String> {
     }
 
     let trimmed = paragraphs.join("\n\n");
+    Some(truncate_at_sentence(&trimmed, MAX_EXCERPT_CHARS))
+}
 
-    // Truncate to ~2000 chars at a sentence boundary
-    if trimmed.len() > 2000 {
-        if let Some(pos) = trimmed[..2000].rfind(". ") {
-            Some(trimmed[..=pos].to_string())
-        } else {
-            let mut s = trimmed[..2000].to_string();
-            s.push_str("...");
-            Some(s)
+/// Byte offset of the `</div>` closing the div whose content starts at
+/// `start`. Comments, script/style bodies and quoted attribute values are
+/// skipped so tag-like text inside them doesn't throw off the depth count.
+fn matching_div_close(html: &str, start: usize) -> Option<usize> {
+    let mut depth: u32 = 1;
+    let mut pos = start;
+
+    while let Some(offset) = html[pos..].find('<') {
+        let tag = pos + offset;
+        let rest = &html[tag..];
+        if names_tag(rest, "</div") {
+            depth -= 1;
+            if depth == 0 {
+                return Some(tag);
+            }
+        } else if names_tag(rest, "<div") {
+            depth += 1;
         }
-    } else {
-        Some(trimmed.to_string())
+        pos = skip_markup(html, tag)?;
     }
+    None
 }
 
-/// Strip HTML tags from a string, keeping only text content.
-fn strip_html_tags(html: &str) -> String {
+/// Drop comments and script/style elements, which are never review text.
+fn strip_hidden_markup(html: &str) -> String {
     let mut result = String::with_capacity(html.len());
-    let mut in_tag = false;
-    for ch in html.chars() {
-        match ch {
-            '<' => in_tag = true,
-            '>' => in_tag = false,
-            _ if !in_tag => result.push(ch),
-            _ => {}
+    let mut pos = 0;
+
+    while let Some(offset) = html[pos..].find('<') {
+        let tag = pos + offset;
+        let rest = &html[tag..];
+        if !rest.starts_with("<!--") && !names_tag(rest, "<script") && !names_tag(rest, "<style") {
+            result.push_str(&html[pos..=tag]);
+            pos = tag + 1;
+            continue;
+        }
+        result.push_str(&html[pos..tag]);
+        match skip_markup(html, tag) {
+            Some(end) => pos = end,
+            None => return result,
         }
     }
+    result.push_str(&html[pos..]);
     result
 }
 
+/// Byte offset just past the markup starting at the `<` at `tag`: a whole
+/// comment, a whole script or style element, or otherwise a single tag.
+fn skip_markup(html: &str, tag: usize) -> Option<usize> {
+    let rest = &html[tag..];
+    if rest.starts_with("<!--") {
+        return rest.find("-->").map(|end| tag + end + 3);
+    }
+    let end = tag + tag_end(rest)?;
+    for (open, close) in [("<script", "</script"), ("<style", "</style")] {
+        if names_tag(rest, open) {
+            let close_pos = end + html[end..].to_ascii_lowercase().find(close)?;
+            return Some(close_pos + tag_end(&html[close_pos..])?);
+        }
+    }
+    Some(end)
+}
+
+/// Length of the tag at the start of `rest`, up to and including its `>`.
+/// A `>` inside a quoted attribute value doesn't end the tag.
+fn tag_end(rest: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut prev = b'<';
+    for (i, &b) in rest.as_bytes().iter().enumerate().skip(1) {
+        match quote {
+            Some(q) if b == q => quote = None,
+            Some(_) => {}
+            None if b == b'>' => return Some(i + 1),
+            None if (b == b'"' || b == b'\'') && prev == b'=' => quote = Some(b),
+            None => {}
+        }
+        if !b.is_ascii_whitespace() {
+            prev = b;
+        }
+    }
+    None
+}
+
+/// Whether `rest` starts with the tag opener `open` (e.g. `<div`, `</div`),
+/// case-insensitively and not as a prefix of a longer name like `<divider`.
+fn names_tag(rest: &str, open: &str) -> bool {
+    let Some(name) = rest.get(..open.len()) else {
+        return false;
+    };
+    name.eq_ignore_ascii_case(open)
+        && rest[open.len()..].starts_with(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+}
+
 /// Parse JSON-LD blocks from a review page to extract review data.
 fn parse_json_ld(html: &str, review_url: &str) -> Option<SiteReview> {
     let marker = "application/ld+json";
     let mut search_from = 0;
 
-    loop {
-        let tag_pos = match html[search_from..].find(marker) {
-            Some(p) => p,
-            None => break,
-        };
+    while let Some(tag_pos) = html[search_from..].find(marker) {
         let abs_pos = search_from + tag_pos;
 
         let content_start = match html[abs_pos..].find('>') {
@@ -355,20 +813,7 @@ fn parse_json_ld(html: &str, review_url: &str) -> Option<SiteReview> {
 fn extract_review_from_ld(ld: &JsonLd, review_url: &str) -> Option<SiteReview> {
     let review = ld.review.as_ref()?;
 
-    let rating = review.review_rating.as_ref().and_then(|r| {
-        let value = parse_numeric_value(r.rating_value.as_ref()?)?;
-        let best = r
-            .best_rating
-            .as_ref()
-            .and_then(|b| parse_numeric_value(b))
-            .unwrap_or(10.0);
-
-        if best > 0.0 && best != 10.0 {
-            Some((value / best) * 10.0)
-        } else {
-            Some(value)
-        }
-    });
+    let raw_rating = review.review_rating.as_ref().and_then(ld_rating);
 
     let reviewer = review.author.as_ref().and_then(|a| a.name.clone());
 
@@ -380,32 +825,44 @@ fn extract_review_from_ld(ld: &JsonLd, review_url: &str) -> Option<SiteReview> {
 
     let excerpt = review.review_body.as_ref().map(|body| {
         let cleaned = clean_review_body(body);
-        let trimmed = cleaned.trim();
-        if trimmed.len() > 2000 {
-            if let Some(pos) = trimmed[..2000].rfind(". ") {
-                trimmed[..=pos].to_string()
-            } else {
-                let mut s = trimmed[..2000].to_string();
-                s.push_str("...");
-                s
-            }
-        } else {
-            trimmed.to_string()
-        }
+        truncate_at_sentence(cleaned.trim(), MAX_EXCERPT_CHARS)
     });
 
-    if rating.is_none() && excerpt.is_none() {
+    if raw_rating.is_none() && excerpt.is_none() {
         return None;
     }
 
-    Some(SiteReview {
-        source_url: review_url.to_string(),
-        excerpt,
-        rating,
-        rating_count: None,
-        reviewer,
-        review_date,
-    })
+    Some(
+        SiteReview::builder(review_url)
+            .excerpt(excerpt)
+            .native_rating(raw_rating)
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The (published value, scale maximum) of a JSON-LD `reviewRating`; a
+/// missing or invalid best means 10, and values that don't scale into 0-10
+/// are dropped. Some pages publish a list of ratings, where the first valid
+/// one is used, or wrap the rating in another object
+/// (`"ratingValue":{"@type":"Rating",...}`), which is looked inside.
+fn ld_rating(rating: &serde_json::Value) -> Option<(f64, f64)> {
+    let fields = match rating {
+        serde_json::Value::Array(ratings) => return ratings.iter().find_map(ld_rating),
+        serde_json::Value::Object(fields) => fields,
+        _ => return None,
+    };
+    let Some(value) = fields.get("ratingValue").and_then(parse_numeric_value) else {
+        let mut nested = fields.values().filter(|v| json_ld_is(v, &["Rating"]));
+        return nested.find_map(ld_rating);
+    };
+    let best = fields
+        .get("bestRating")
+        .and_then(parse_numeric_value)
+        .filter(|best| *best > 0.0)
+        .unwrap_or(10.0);
+    Some((value, best)).filter(|_| (0.0..=10.0).contains(&scale_rating(value, best)))
 }
 
 /// Clean a review body from JSON-LD: strip CDATA wrapper, decode HTML entities, strip HTML tags.
@@ -419,21 +876,8 @@ fn clean_review_body(body: &str) -> String {
         }
     }
 
-    // Decode HTML entities
-    s = s
-        .replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&#39;", "'")
-        .replace("&#039;", "'")
-        .replace("&#x27;", "'")
-        .replace("&apos;", "'")
-        .replace("&ndash;", "\u{2013}")
-        .replace("&mdash;", "\u{2014}")
-        .replace("&amp;", "&");
-
-    // Strip HTML tags
-    let text = strip_html_tags(&s);
+    // Entity-encoded markup is decoded first, so its tags are stripped too
+    let text = strip_html_tags(&decode_html_entities(&s));
 
     // Collapse multiple whitespace/newlines into single spaces
     let mut collapsed = String::with_capacity(text.len());
@@ -462,12 +906,78 @@ fn parse_numeric_value(value: &serde_json::Value) -> Option<f64> {
     }
 }
 
+/// Advance the listing crawl by up to `batches` batches, stopping at the crawl
+/// bound or when a batch makes no progress. The cache is saved after every
+/// batch so work survives a host timeout.
+pub fn prewarm(batches: u32) -> CacheStats {
+    let settings = CrawlSettings::load();
+    let mut cache = load_cache();
+
+    for _ in 0..batches {
+        let before = cache.next_page;
+        if before >= cache.crawl_bound(&settings) {
+            break;
+        }
+        if before == 0 {
+            // The first batch starts at page 1, which covers the front
+            cache.front_refreshed_at = unix_now();
+        }
+        fetch_next_batch(&mut cache, &settings, &base_url(), fetch_listing_page);
+        save_cache(&cache);
+        if cache.next_page == before {
+            break;
+        }
+    }
+
+    cache_stats()
+}
+
+/// Crawl progress and stored size of the URL cache. Read-only.
+pub fn cache_stats() -> CacheStats {
+    let bytes: Option<Vec<u8>> = var::get(CACHE_VAR).ok().flatten();
+    let size = bytes.as_ref().map_or(0, Vec::len);
+    stats_for(&parse_cache(bytes), size)
+}
+
+fn stats_for(cache: &UrlCache, bytes: usize) -> CacheStats {
+    CacheStats {
+        next_page: cache.next_page,
+        max_pages: cache.max_pages,
+        slugs_cached: cache.slugs.len(),
+        bytes,
+    }
+}
+
 /// Load the URL cache from an Extism var, or return an empty cache.
 fn load_cache() -> UrlCache {
-    let bytes: Option<Vec<u8>> = var::get(CACHE_VAR).ok().flatten();
-    bytes
+    parse_cache(var::get(CACHE_VAR).ok().flatten())
+}
+
+/// Deserialize a stored URL cache, falling back to an empty one.
+/// Caches written before ranks were stored are ranked by position, which is
+/// listing order for caches that also predate sorting; they are sorted on load.
+fn parse_cache(bytes: Option<Vec<u8>>) -> UrlCache {
+    let mut cache: UrlCache = bytes
         .and_then(|b| serde_json::from_slice(&b).ok())
-        .unwrap_or_default()
+        .unwrap_or_default();
+    if cache.ranks.len() != cache.slugs.len() {
+        cache.ranks = (0..cache.slugs.len() as i64).collect();
+    }
+    if !cache.slugs.is_sorted() {
+        let mut entries: Vec<(String, i64)> = std::mem::take(&mut cache.slugs)
+            .into_iter()
+            .zip(std::mem::take(&mut cache.ranks))
+            .collect();
+        entries.sort();
+        entries.dedup_by(|a, b| a.0 == b.0);
+        (cache.slugs, cache.ranks) = entries.into_iter().unzip();
+    }
+    cache
+}
+
+/// Forget every crawled URL, so the next lookup restarts the crawl from page 1.
+pub fn clear_url_cache() {
+    let _ = var::remove(CACHE_VAR);
 }
 
 /// Save the URL cache to an Extism var.
@@ -476,3 +986,319 @@ fn save_cache(cache: &UrlCache) {
         let _ = var::set(CACHE_VAR, &bytes);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A cache holding `slugs` in listing order, newest first.
+    /// A query from already-slugified halves.
+    fn slugs(artist_slug: &str, title_slug: &str) -> Query {
+        Query {
+            artist_slug: artist_slug.to_string(),
+            title_slug: title_slug.to_string(),
+            ..Query::default()
+        }
+    }
+
+    fn cache_with(slugs: &[&str]) -> UrlCache {
+        let mut cache = UrlCache::default();
+        for (rank, slug) in slugs.iter().enumerate() {
+            cache.insert(slug.to_string(), rank as i64);
+        }
+        cache
+    }
+
+    #[test]
+    fn not_modified_page_keeps_cached_slugs() {
+        let mut cache = cache_with(&["band-album"]);
+        cache.max_pages = 40;
+        let validator = page_validator(Some("\"abc\""), None).expect("validator");
+        cache.validators.insert(4, validator);
+
+        let handled = apply_listing_page(&mut cache, 4, ListingFetch::NotModified, BASE_URL);
+        assert!(handled);
+        assert_eq!(cache.slugs, vec!["band-album".to_string()]);
+        assert_eq!(cache.max_pages, 40);
+        assert!(cache.validators.contains_key(&4));
+
+        let handled = apply_listing_page(&mut cache, 5, ListingFetch::Failed, BASE_URL);
+        assert!(!handled);
+    }
+
+    /// A listing page linking to `slugs`.
+    fn listing(slugs: &[String]) -> ListingFetch {
+        let html = slugs
+            .iter()
+            .map(|slug| format!(r#"<a href="/albums/{}">{}</a>"#, slug, slug))
+            .collect();
+        ListingFetch::Page {
+            html,
+            validator: None,
+        }
+    }
+
+    #[test]
+    fn crawl_stops_at_the_cap_keeping_the_newest() {
+        let settings = CrawlSettings {
+            batch_size: 100,
+            max_pages: Some(100),
+        };
+        let page_slugs = |page: u32| -> Vec<String> {
+            (0..120)
+                .map(|i| format!("band-{}-album-{}", page, i))
+                .collect()
+        };
+        let mut fetched = Vec::new();
+        let mut cache = UrlCache::default();
+        fetch_next_batch(&mut cache, &settings, BASE_URL, |page, _| {
+            fetched.push(page);
+            listing(&page_slugs(page))
+        });
+
+        // 42 pages of 120 overshoot the cap; the tail of page 42 goes
+        assert_eq!(fetched.len(), 42);
+        assert_eq!(cache.next_page, 100);
+        assert_eq!(cache.slugs.len(), MAX_CACHED_SLUGS);
+        let cached = |slug: &str| cache.slugs.binary_search(&slug.to_string()).is_ok();
+        assert!(cached("band-1-album-0"));
+        assert!(cached("band-42-album-79"));
+        assert!(!cached("band-42-album-80"));
+        assert_eq!(cache.slugs.len(), cache.ranks.len());
+    }
+
+    #[test]
+    fn front_rescan_ranks_new_albums_first() {
+        let now = 1_700_000_000;
+        let mut cache = cache_with(&["band-b", "band-c"]);
+        cache.next_page = 3;
+        cache.front_refreshed_at = now - FRONT_REFRESH_SECS + 1;
+        assert!(!cache.front_stale(now));
+        cache.front_refreshed_at = now - FRONT_REFRESH_SECS;
+        assert!(cache.front_stale(now));
+
+        let mut fetched = Vec::new();
+        refresh_front(&mut cache, now, BASE_URL, |page, _| {
+            fetched.push(page);
+            match page {
+                1 => listing(&["band-a".to_string(), "band-b".to_string()]),
+                _ => listing(&["band-b2".to_string(), "band-c".to_string()]),
+            }
+        });
+        assert_eq!(fetched, [2, 1]);
+        assert_eq!(cache.front_refreshed_at, now);
+        assert!(!cache.front_stale(now));
+        let rank = |slug: &str| {
+            let pos = cache.slugs.binary_search(&slug.to_string()).unwrap();
+            cache.ranks[pos]
+        };
+        assert!(rank("band-a") < rank("band-b2"));
+        assert!(rank("band-b2") < rank("band-b"));
+        assert!(rank("band-b") < rank("band-c"));
+
+        // A full cache makes room by dropping its oldest album
+        let slugs: Vec<String> = (0..MAX_CACHED_SLUGS)
+            .map(|i| format!("band-{:04}", i))
+            .collect();
+        let slugs: Vec<&str> = slugs.iter().map(String::as_str).collect();
+        let mut cache = cache_with(&slugs);
+        cache.next_page = 200;
+        refresh_front(&mut cache, now, BASE_URL, |page, _| match page {
+            1 => listing(&["band-new".to_string()]),
+            _ => ListingFetch::NotModified,
+        });
+        assert_eq!(cache.slugs.len(), MAX_CACHED_SLUGS);
+        assert!(cache.slugs.contains(&"band-new".to_string()));
+        assert!(!cache.slugs.contains(&"band-4999".to_string()));
+        assert!(cache.slugs.contains(&"band-4998".to_string()));
+    }
+
+    #[test]
+    fn crawl_settings_clamped_or_default() {
+        let settings = CrawlSettings::parse(None, None);
+        assert_eq!(
+            (settings.batch_size, settings.max_pages),
+            (BATCH_SIZE, None)
+        );
+        let settings = CrawlSettings::parse(Some(" 10 "), Some("120"));
+        assert_eq!((settings.batch_size, settings.max_pages), (10, Some(120)));
+        let settings = CrawlSettings::parse(Some("0"), Some("99999999999"));
+        assert_eq!((settings.batch_size, settings.max_pages), (1, Some(5000)));
+        let settings = CrawlSettings::parse(Some("500"), Some("0"));
+        assert_eq!((settings.batch_size, settings.max_pages), (100, Some(1)));
+        let settings = CrawlSettings::parse(Some("-5"), Some("lots"));
+        assert_eq!(
+            (settings.batch_size, settings.max_pages),
+            (BATCH_SIZE, None)
+        );
+    }
+
+    #[test]
+    fn match_url_skips_other_releases() {
+        let cache = cache_with(&[
+            "kendrick-lamar-good-kid-maad-city-10th-anniversary",
+            "kendrick-lamar-good-kid-maad-city",
+        ]);
+        let prefix = "kendrick-lamar-good-kid-maad-city";
+        let album = Query::new("Kendrick Lamar", "good kid, m.A.A.d city");
+        assert_eq!(
+            match_url(&cache, &album, BASE_URL),
+            Some(format!("{}/albums/{}", BASE_URL, prefix))
+        );
+
+        let cache = cache_with(&[
+            "kendrick-lamar-good-kid-maad-city-10th-anniversary",
+            "kendrick-lamar-good-kid-maad-city-deluxe-review",
+            "kendrick-lamar-good-kid-maad-city-review",
+            "kendrick-lamar-good-kid-maad-city-review-kendrick",
+        ]);
+        assert_eq!(
+            match_url(&cache, &album, BASE_URL),
+            Some(format!("{}/albums/{}-review", BASE_URL, prefix))
+        );
+        let anniversary = format!("{}-10th-anniversary", prefix);
+        let album = slugs("kendrick-lamar", "good-kid-maad-city-10th-anniversary");
+        assert_eq!(
+            match_url(&cache, &album, BASE_URL),
+            Some(format!("{}/albums/{}", BASE_URL, anniversary))
+        );
+    }
+
+    #[test]
+    fn stats_from_stored_cache() {
+        let stored = br#"{"next_page":6,"slugs":["b-album","a-album"],"max_pages":412}"#;
+        let cache = parse_cache(Some(stored.to_vec()));
+        assert_eq!(cache.slugs, ["a-album", "b-album"]);
+        let stats = stats_for(&cache, stored.len());
+        assert_eq!((stats.next_page, stats.max_pages), (6, 412));
+        assert_eq!((stats.slugs_cached, stats.bytes), (2, stored.len()));
+        assert_eq!(stats_for(&parse_cache(None), 0), CacheStats::default());
+    }
+
+    #[test]
+    fn article_body_skips_hidden_div_markup() {
+        let html = r#"<div class="c--article-copy__sections">
+            <div class="section"><p>First paragraph.</p></div>
+            <!-- <div class="old-ad"></div></div> -->
+            <script>document.write("</div>");</script>
+            <div class="pullquote" data-html="</div>"><p>Quoted bit.</p></div>
+            <p>Last paragraph.</p>
+        </div>
+        <div class="related"><p>Not the review.</p></div>"#;
+        let body = extract_article_body(html).unwrap();
+        assert!(body.starts_with("First paragraph."));
+        assert!(body.contains("Quoted bit."));
+        assert!(body.ends_with("Last paragraph."));
+        assert!(!body.contains("-->") && !body.contains("document.write"));
+        assert!(!body.contains("Not the review"));
+    }
+
+    #[test]
+    fn article_body_truncates_on_char_boundary() {
+        // 1999 ASCII bytes, then an em-dash straddling byte 2000
+        let text = format!("{}\u{2014}{}", "a".repeat(1999), "b".repeat(500));
+        let html = format!(
+            r#"<div class="c--article-copy__sections"><p>{}</p></div>"#,
+            text
+        );
+        let body = extract_article_body(&html).unwrap();
+        assert_eq!(body.chars().count(), MAX_EXCERPT_CHARS + 3);
+        assert!(body.ends_with("\u{2014}..."));
+    }
+
+    #[test]
+    fn rating_from_list_or_nested_rating() {
+        let url = "https://www.thelineofbestfit.com/albums/wet-leg/moisturizer";
+        let page = |rating: &str| {
+            format!(
+                r#"<script type="application/ld+json">{{"@type":"MusicAlbum",
+                "review":{{"@type":"Review","reviewRating":{}}}}}</script>"#,
+                rating
+            )
+        };
+        let listed = page(
+            r#"[{"@type":"Rating","ratingValue":"n/a"},
+            {"@type":"Rating","ratingValue":"8","bestRating":"10"}]"#,
+        );
+        let review = parse_json_ld(&listed, url).unwrap();
+        assert_eq!(review.rating_raw, Some(8.0));
+        let nested = page(
+            r#"{"@type":"Rating","ratingValue":
+            {"@type":"Rating","ratingValue":4,"bestRating":5}}"#,
+        );
+        let review = parse_json_ld(&nested, url).unwrap();
+        assert_eq!(review.rating_raw, Some(4.0));
+        assert_eq!(review.rating_scale, Some(5.0));
+        assert_eq!(review.rating, Some(8.0));
+    }
+
+    #[test]
+    fn slug_prefixes_need_both_halves() {
+        let prefixes = slug_prefixes(&Query::new("Oasis", "(What's the Story) Morning Glory?"));
+        assert_eq!(prefixes, ["oasis-whats-the-story-morning-glory"]);
+        assert!(slug_prefixes(&Query::new("Band", "()")).is_empty());
+        assert!(slug_prefixes(&Query::new("", "Album")).is_empty());
+
+        let prefixes = slug_prefixes(&Query::new("The Beatles", "Abbey Road"));
+        assert_eq!(prefixes, ["the-beatles-abbey-road", "beatles-abbey-road"]);
+        let cache = cache_with(&["beatles-abbey-road-50th-anniversary", "beatles-abbey-road"]);
+        let url = match_url(&cache, &Query::new("The Beatles", "Abbey Road"), BASE_URL);
+        assert_eq!(url, Some(format!("{}/albums/beatles-abbey-road", BASE_URL)));
+    }
+
+    #[test]
+    fn match_url_prefix_collisions() {
+        let cache = cache_with(&["band-album-2", "band-albums", "band-album", "band-a"]);
+        let album = slugs("band", "album");
+        assert_eq!(
+            match_url(&cache, &album, BASE_URL),
+            Some(format!("{}/albums/band-album", BASE_URL))
+        );
+        assert_eq!(
+            match_url(&cache, &slugs("band", "album-2"), BASE_URL),
+            Some(format!("{}/albums/band-album-2", BASE_URL))
+        );
+
+        // A numbered sequel is a different album, not a looser match
+        let cache = cache_with(&["band-albums", "band-album-2"]);
+        assert_eq!(match_url(&cache, &album, BASE_URL), None);
+        assert_eq!(match_url(&cache, &slugs("band", "alb"), BASE_URL), None);
+        let run: Vec<&String> = prefix_run(&cache, "band-alb")
+            .map(|(slug, _)| slug)
+            .collect();
+        assert_eq!(run, ["band-album-2", "band-albums"]);
+
+        // Equally good candidates: the newest listing entry wins
+        let cache = cache_with(&["band-album-lp", "band-album-ep"]);
+        assert_eq!(
+            match_url(&cache, &album, BASE_URL),
+            Some(format!("{}/albums/band-album-lp", BASE_URL))
+        );
+        let cache = cache_with(&["band-album-ep", "band-album-lp"]);
+        assert_eq!(
+            match_url(&cache, &album, BASE_URL),
+            Some(format!("{}/albums/band-album-ep", BASE_URL))
+        );
+
+        // A slug padded well past the album is another article
+        let cache = cache_with(&["band-album-and-every-other-record-of-the-year"]);
+        assert_eq!(match_url(&cache, &album, BASE_URL), None);
+    }
+
+    #[test]
+    fn last_page_from_pagination() {
+        let html = r#"<nav class="pagination">
+            <a href="/albums?page=2">2</a>
+            <a href="/albums?page=3">3</a>
+            <span>&hellip;</span>
+            <a href="https://www.thelineofbestfit.com/albums?page=412">412</a>
+            <a href="/albums?page=2" rel="next">Next</a>
+        </nav>
+        <a href="/news?page=9000">News</a>"#;
+        assert_eq!(parse_last_page(html), Some(412));
+        assert_eq!(
+            parse_last_page("<a href=\"/albums/some-album\">x</a>"),
+            None
+        );
+    }
+}
@@ -0,0 +1,6 @@
+pub mod tinymixtapes;
+
+/// Review fields this source can populate.
+pub const CAPABILITIES: &[&str] = &["rating", "excerpt", "reviewer", "date"];
+
+editorial_common::editorial_plugin!(tinymixtapes, "tinymixtapes", CAPABILITIES);
@@ -0,0 +1,260 @@
+use editorial_common::{
+    best_candidate, build_request, configured_base_url, element_text, fetch_archived_page,
+    match_confidence, meta_content, normalize_date, paragraph_text, parse_star_rating,
+    release_ids_conflict, slugify, truncate_at_sentence, AlbumReviewInput, Candidate, FetchError,
+    FetchOutcome, Query, SearchCandidate, SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `tinymixtapes_base_url` config
+/// value (e.g. a Wayback Machine snapshot prefix or a local mirror).
+const DEFAULT_BASE_URL: &str = "https://www.tinymixtapes.com";
+const BASE_URL_CONFIG: &str = "tinymixtapes_base_url";
+
+/// Tiny Mix Tapes rated albums out of five stars, in half-star steps.
+const STAR_SCALE: f64 = 5.0;
+
+/// Section every album review lived under.
+const REVIEW_SECTION: &str = "music-review";
+
+/// Class of the element holding the star rating.
+const RATING_MARKER: &str = "class=\"rating";
+
+/// Class of the "by <reviewer>" line.
+const BYLINE_MARKER: &str = "class=\"byline";
+
+/// Class of the element holding the review text.
+const BODY_MARKER: &str = "class=\"entry-content";
+
+/// The site root, honoring the `tinymixtapes_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch an archived Tiny Mix Tapes review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its star rating, skipping the review
+/// text, author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known Tiny Mix Tapes URL, skipping the lookup.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_archived_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let rating = parse_stars(&body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((rating, STAR_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Fetch the first of the album's review URLs the archive has and whose
+/// headline names the album, rejecting pages whose release identifiers
+/// contradict the input. Archived pages are cached for a week, and a
+/// redirect to an archive mirror is followed like any other.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let album = Query::from_input(input);
+    for url in review_urls(&base_url(), &album) {
+        let body = match fetch_archived_page(&build_request(&url, "text/html")) {
+            Ok(body) => body,
+            Err(FetchError::NotFound) => continue,
+            Err(err) => return Err(err),
+        };
+        if !headline_matches(&body, &album) {
+            continue;
+        }
+        if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+            return Err(FetchError::NotFound);
+        }
+        return Ok((url, body));
+    }
+    Err(FetchError::NotFound)
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    let album = Query::from_input(input);
+    let slug = review_slug(review_url)?;
+    let (artist_slug, title_slug) = (&album.artist_slug, &album.title_slug);
+    Some(match_confidence(&slug, artist_slug, title_slug))
+}
+
+/// The site can no longer be searched; report the review URLs
+/// `fetch_review` would try, in order, picking the first.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    let album = Query::from_input(input);
+    let urls = review_urls(&base_url(), &album);
+    let mut result = SearchResult {
+        chosen: urls.first().cloned(),
+        ..SearchResult::default()
+    };
+    for url in urls {
+        let slug = review_slug(&url).unwrap_or_default();
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    result
+}
+
+/// The album's possible review URLs, "/music-review/{artist}-{title}", once
+/// for the artist's slug and once for each of its aliases ("the-walkmen"
+/// and "walkmen"). Empty when the artist or title has no slug.
+fn review_urls(base: &str, album: &Query) -> Vec<String> {
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Vec::new();
+    }
+    let section = format!("{}/{}", base, REVIEW_SECTION);
+    let artists = std::iter::once(&album.artist_slug).chain(&album.artist_aliases);
+    artists
+        .map(|artist| format!("{}/{}-{}", section, artist, album.title_slug))
+        .collect()
+}
+
+/// The artist and album slug of a review URL, also inside an archive
+/// snapshot: "/music-review/wet-leg-moisturizer" -> "wet-leg-moisturizer".
+fn review_slug(url: &str) -> Option<String> {
+    let marker = format!("/{}/", REVIEW_SECTION);
+    let path = &url[url.find(&marker)? + marker.len()..];
+    let slug = slugify(path.split(['/', '?', '#']).next()?);
+    Some(slug).filter(|slug| !slug.is_empty())
+}
+
+/// Whether the page's headline ("Wet Leg - Moisturizer | Music Review")
+/// names the album. A review URL built for one album can land on another
+/// after a redirect, or on the archive's own "not found" page.
+fn headline_matches(html: &str, album: &Query) -> bool {
+    let Some(title) = meta_content(html, "og:title") else {
+        return false;
+    };
+    let headline = title.split(" | ").next().unwrap_or(&title);
+    let slugs = [slugify(headline)];
+    best_candidate(album, &slugs, |slug| Some(Candidate::new(slug.as_str()))).is_some()
+}
+
+/// Parse a Tiny Mix Tapes review page for the star rating (out of 5) and
+/// the review's text, author and date.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let rating = parse_stars(html);
+    let excerpt = parse_review_body(html);
+    if rating.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    let reviewer = element_text(html, BYLINE_MARKER)
+        .map(|byline| {
+            let name = byline.trim_start_matches("by ").trim_start_matches("By ");
+            name.trim().to_string()
+        })
+        .filter(|name| !name.is_empty())
+        .or_else(|| meta_content(html, "author"));
+    let review_date = meta_content(html, "article:published_time");
+    let review_date = review_date.and_then(|date| normalize_date(&date));
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(rating.map(|r| (r, STAR_SCALE)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The star rating shown in the rating element; see `parse_star_rating`.
+fn parse_stars(html: &str) -> Option<f64> {
+    let start = html.find(RATING_MARKER)?;
+    let end = html[start..].find("</div>")? + start;
+    parse_star_rating(&html[start..end], STAR_SCALE)
+}
+
+/// The review's paragraphs, cut to ~2000 chars at a sentence boundary.
+fn parse_review_body(html: &str) -> Option<String> {
+    let start = html.find(BODY_MARKER)?;
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |end| start + end);
+    let text = paragraph_text(&html[start..end]);
+    Some(truncate_at_sentence(&text, MAX_EXCERPT_CHARS)).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://www.tinymixtapes.com";
+
+    #[test]
+    fn review_urls_tried_for_each_artist_spelling() {
+        let album = Query::new("The Walkmen", "Bows + Arrows");
+        let urls = review_urls(BASE, &album);
+        assert_eq!(
+            urls,
+            [
+                format!("{}/music-review/the-walkmen-bows-arrows", BASE),
+                format!("{}/music-review/walkmen-bows-arrows", BASE),
+            ]
+        );
+        let archived = format!("https://web.archive.org/web/2019/{}", urls[1]);
+        let slug = review_slug(&archived);
+        assert_eq!(slug.as_deref(), Some("walkmen-bows-arrows"));
+
+        let page = r#"<meta property="og:title" content="The Walkmen - Bows + Arrows | Review">"#;
+        assert!(headline_matches(page, &album));
+        let other = r#"<meta property="og:title" content="The Walkmen - Lisbon | Review">"#;
+        assert!(!headline_matches(other, &album));
+    }
+
+    #[test]
+    fn half_star_rating_and_byline() {
+        let html = r#"<head><meta property="article:published_time" content="2004-02-18T00:00:00-05:00"></head>
+            <div class="rating"><span class="star-full"></span><span class="star-full"></span>
+            <span class="star-full"></span><span class="star-full"></span>
+            <span class="star-half"></span></div>
+            <p class="byline">by <a href="/users/adam">Adam Strohm</a></p>
+            <article><div class="entry-content"><p>A ragged, gorgeous record.</p></div></article>"#;
+        let url = format!("{}/music-review/walkmen-bows-arrows", BASE);
+        let review = parse_review_page(&url, html).unwrap();
+        assert_eq!(review.rating, Some(9.0));
+        assert_eq!(review.rating_raw, Some(4.5));
+        assert_eq!(review.rating_scale, Some(5.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Adam Strohm"));
+        assert_eq!(review.review_date.as_deref(), Some("2004-02-18"));
+        let excerpt = review.excerpt.unwrap();
+        assert_eq!(excerpt, "A ragged, gorgeous record.");
+    }
+
+    #[test]
+    fn archive_misses_and_older_star_markup() {
+        let album = Query::new("The Walkmen", "Bows + Arrows");
+        let missing = r#"<title>Wayback Machine</title><p>Hrm. This page is not archived.</p>"#;
+        assert!(!headline_matches(missing, &album));
+        let redirected = r#"<meta property="og:title" content="Tiny Mix Tapes | Music Reviews">"#;
+        assert!(!headline_matches(redirected, &album));
+        assert_eq!(review_slug(&format!("{}/music-review/", BASE)), None);
+        assert_eq!(review_slug(&format!("{}/features/the-walkmen", BASE)), None);
+
+        let glyphs = "<div class=\"rating\">\u{2605}\u{2605}\u{2605}\u{bd}</div>";
+        assert_eq!(parse_stars(glyphs), Some(3.5));
+        let out_of_range = r#"<div class="rating" data-rating="8"></div>"#;
+        assert_eq!(parse_stars(out_of_range), None);
+    }
+}
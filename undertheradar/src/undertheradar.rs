@@ -0,0 +1,236 @@
+use editorial_common::{
+    best_candidate, build_request, clean_title, configured_base_url, decode_html_entities,
+    fetch_page, find_json_ld_node, json_ld_name, match_confidence, meta_content, normalize_date,
+    paragraph_text, release_ids_conflict, search_terms, site_links, slugify, strip_html_tags,
+    truncate_at_sentence, url_encode, AlbumReviewInput, Candidate, FetchError, FetchOutcome, Query,
+    SearchCandidate, SearchResult, SiteReview, MAX_EXCERPT_CHARS,
+};
+
+/// Production site root; override with the `undertheradar_base_url` config value.
+const DEFAULT_BASE_URL: &str = "https://www.undertheradarmag.com";
+const BASE_URL_CONFIG: &str = "undertheradar_base_url";
+
+/// Under the Radar rates albums out of ten, sometimes in half points.
+const SCORE_SCALE: f64 = 10.0;
+
+/// Label of the reviewer's score in the page text.
+const SCORE_MARKER: &str = "Author rating:";
+
+/// Label of the readers' average, shown next to the reviewer's score and
+/// never taken as the rating.
+const READER_SCORE_MARKER: &str = "Average reader rating:";
+
+/// Class of the element holding the review text.
+const BODY_MARKER: &str = "class=\"review-body";
+
+/// The site root, honoring the `undertheradar_base_url` override.
+pub fn base_url() -> String {
+    configured_base_url(BASE_URL_CONFIG, DEFAULT_BASE_URL)
+}
+
+/// Attempt to fetch an Under the Radar review for the given album.
+pub fn fetch_review(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_review(input).into()
+}
+
+/// Resolve the album and read only its score, skipping the review text,
+/// author and date.
+pub fn fetch_rating(input: &AlbumReviewInput) -> FetchOutcome {
+    lookup_rating(input).into()
+}
+
+/// Parse the review at a known Under the Radar URL, skipping search.
+pub fn fetch_review_at(url: &str) -> FetchOutcome {
+    lookup_url(url).into()
+}
+
+fn lookup_url(url: &str) -> Result<SiteReview, FetchError> {
+    let body = fetch_page(&build_request(url, "text/html"))?;
+    parse_review_page(url, &body).ok_or(FetchError::NotFound)
+}
+
+fn lookup_review(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let mut review = parse_review_page(&review_url, &body).ok_or(FetchError::NotFound)?;
+    review.match_confidence = review_confidence(&review_url, input);
+    Ok(review)
+}
+
+fn lookup_rating(input: &AlbumReviewInput) -> Result<SiteReview, FetchError> {
+    let (review_url, body) = fetch_review_page(input)?;
+    let score = parse_score(&body).ok_or(FetchError::NotFound)?;
+    let confidence = review_confidence(&review_url, input);
+    Ok(SiteReview::builder(review_url)
+        .native_rating(Some((score, SCORE_SCALE)))
+        .match_confidence(confidence)
+        .build())
+}
+
+/// Search for the album's review and fetch its page, rejecting pages whose
+/// release identifiers contradict the input.
+fn fetch_review_page(input: &AlbumReviewInput) -> Result<(String, String), FetchError> {
+    let review_url = search_candidates(input)?
+        .chosen
+        .ok_or(FetchError::NotFound)?;
+
+    let body = fetch_page(&build_request(&review_url, "text/html"))?;
+    if release_ids_conflict(&body, input.mbid.as_deref(), input.barcode.as_deref()) {
+        return Err(FetchError::NotFound);
+    }
+    Ok((review_url, body))
+}
+
+fn review_confidence(review_url: &str, input: &AlbumReviewInput) -> Option<f64> {
+    review_slug(review_url).map(|slug| {
+        let title_slug = slugify(clean_title(&input.title));
+        match_confidence(&slug, &slugify(&input.artist), &title_slug)
+    })
+}
+
+/// List the review URLs Under the Radar's search offers for an album and the
+/// one `fetch_review` would pick, without fetching the review itself.
+pub fn search(input: &AlbumReviewInput) -> SearchResult {
+    search_candidates(input).into()
+}
+
+/// Search Under the Radar for "artist title" and keep every review it lists
+/// along with the pick.
+fn search_candidates(input: &AlbumReviewInput) -> Result<SearchResult, FetchError> {
+    let album = Query::from_input(input);
+    if album.artist_slug.is_empty() || album.title_slug.is_empty() {
+        return Err(FetchError::NotFound);
+    }
+    let cleaned = clean_title(&input.title);
+
+    let base = base_url();
+    let query = search_terms(&input.artist, cleaned);
+    let search_url = format!("{}/search/?q={}", base, url_encode(&query));
+    let html = fetch_page(&build_request(&search_url, "text/html"))?;
+
+    let mut result = SearchResult::default();
+    for url in site_links(&html, &base, "reviews") {
+        let Some(slug) = review_slug(&url) else {
+            continue;
+        };
+        let candidate = SearchCandidate::new(url, slug, &album.artist_slug, &album.title_slug);
+        result.add(candidate);
+    }
+    result.chosen = pick_review(&result.candidates, &album);
+    Ok(result)
+}
+
+/// The review that best fits the album; see `best_candidate`. The site's
+/// search mixes news posts in with reviews, and a post about the album
+/// ("wet_leg_announce_new_album_moisturizer") scores too low to be picked.
+fn pick_review(candidates: &[SearchCandidate], album: &Query) -> Option<String> {
+    let best = best_candidate(album, candidates, |c| Some(Candidate::new(c.slug.as_str())));
+    best.map(|candidate| candidate.url.clone())
+}
+
+/// The slug of a review URL, with the underscores Under the Radar separates
+/// words with turned into hyphens: "/reviews/moisturizer_wet_leg" ->
+/// "moisturizer-wet-leg". Title and artist come in either order, which the
+/// token-overlap scorer doesn't mind. None for the reviews index.
+fn review_slug(url: &str) -> Option<String> {
+    let path = url.split_once("/reviews/")?.1;
+    let slug = path.split(['/', '?', '#']).next()?.replace('_', "-");
+    Some(slugify(&slug)).filter(|slug| !slug.is_empty())
+}
+
+/// Parse an Under the Radar review page for the reviewer's score (out of 10)
+/// and the review's text, author and date.
+fn parse_review_page(url: &str, html: &str) -> Option<SiteReview> {
+    let score = parse_score(html);
+    let excerpt = parse_review_body(html);
+    if score.is_none() && excerpt.is_none() {
+        return None;
+    }
+
+    let article = find_json_ld_node(html, &["Review", "Article"]);
+    let field = |key: &str| article.as_ref().and_then(|node| node.get(key));
+    let reviewer = meta_content(html, "author").or_else(|| field("author").and_then(json_ld_name));
+    let review_date = meta_content(html, "article:published_time")
+        .or_else(|| field("datePublished")?.as_str().map(str::to_string))
+        .and_then(|date| normalize_date(&date));
+
+    Some(
+        SiteReview::builder(url)
+            .excerpt(excerpt)
+            .native_rating(score.map(|s| (s, SCORE_SCALE)))
+            .reviewer(reviewer)
+            .review_date(review_date)
+            .build(),
+    )
+}
+
+/// The score after "Author rating:" in the page text ("7.5/10"), accepted
+/// only within 0-10.
+fn parse_score(html: &str) -> Option<f64> {
+    let text = decode_html_entities(&strip_html_tags(html));
+    let rest = &text[text.find(SCORE_MARKER)? + SCORE_MARKER.len()..];
+    let score = rest.split_whitespace().next()?.split('/').next()?;
+    let score: f64 = score.parse().ok()?;
+    Some(score).filter(|score| (0.0..=SCORE_SCALE).contains(score))
+}
+
+/// The review's paragraphs, cut to ~2000 chars at a sentence boundary. The
+/// body ends where either score begins.
+fn parse_review_body(html: &str) -> Option<String> {
+    let start = html.find(BODY_MARKER)?;
+    let end = [SCORE_MARKER, READER_SCORE_MARKER, "</article>"]
+        .iter()
+        .filter_map(|close| html[start..].find(close))
+        .min()
+        .map_or(html.len(), |end| start + end);
+    let text = paragraph_text(&html[start..end]);
+    Some(truncate_at_sentence(&text, MAX_EXCERPT_CHARS)).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://www.undertheradarmag.com";
+
+    #[test]
+    fn news_posts_rejected_by_score() {
+        let html = r#"<a href="/news/wet_leg_announce_new_album_moisturizer">News</a>
+            <a href="/reviews/">Reviews</a>
+            <a href="/reviews/wet_leg_share_video_for_catch_these_fists_from_moisturizer">Video</a>
+            <a href="/reviews/moisturizer_wet_leg">Review</a>"#;
+        let mut result = SearchResult::default();
+        for url in site_links(html, BASE, "reviews") {
+            if let Some(slug) = review_slug(&url) {
+                result.add(SearchCandidate::new(url, slug, "wet-leg", "moisturizer"));
+            }
+        }
+        assert_eq!(result.candidates.len(), 2);
+        assert_eq!(result.candidates[1].slug, "moisturizer-wet-leg");
+        assert_eq!(result.candidates[1].score, 1.0);
+
+        let album = Query::new("wet-leg", "moisturizer");
+        let review = format!("{}/reviews/moisturizer_wet_leg", BASE);
+        assert_eq!(pick_review(&result.candidates, &album), Some(review));
+        assert_eq!(pick_review(&result.candidates[..1], &album), None);
+    }
+
+    #[test]
+    fn author_rating_not_reader_rating() {
+        let html = r#"<head><meta name="author" content="Mark Redfern">
+            <meta property="article:published_time" content="2025-07-11T09:00:00-07:00"></head>
+            <article><div class="review-body"><p>Wet Leg&rsquo;s second album is louder.</p>
+            <p>It works.</p><div class="rating-box"><p>Average reader rating: <b>6/10</b></p>
+            <p>Author rating: <b>7.5/10</b></p></div></div></article>"#;
+        let url = format!("{}/reviews/moisturizer_wet_leg", BASE);
+        let review = parse_review_page(&url, html).unwrap();
+        assert_eq!(review.rating, Some(7.5));
+        assert_eq!(review.rating_scale, Some(10.0));
+        assert_eq!(review.reviewer.as_deref(), Some("Mark Redfern"));
+        assert_eq!(review.review_date.as_deref(), Some("2025-07-11"));
+        let excerpt = review.excerpt.unwrap();
+        assert!(excerpt.starts_with("Wet Leg\u{2019}s second album"));
+        assert!(!excerpt.contains("rating"));
+
+        assert_eq!(parse_score("<p>Author rating: 12/10</p>"), None);
+    }
+}